@@ -0,0 +1,503 @@
+//! Instruction builders for the listing and lease lifecycle.
+//!
+//! Each builder fills in every account the handler expects, deriving the program's PDAs and the
+//! associated token accounts from the wallets and mints passed in. Signers are marked in the
+//! returned account metas, the caller only has to sign.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{instruction::Instruction, sysvar},
+    system_program, Id, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id},
+    stake::Stake,
+    token_2022,
+};
+use capstone::{
+    accounts, instruction as ix, nft_backend, Agreement, Escrow, PaymentSchedule, PropertyDetails,
+};
+
+use crate::pda;
+
+fn build(data: impl InstructionData, accounts: impl ToAccountMetas) -> Instruction {
+    Instruction {
+        program_id: capstone::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Terms of a listing, set by `create_escrow` and changed by `update_escrow`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ListingTerms {
+    pub monthly_rent: u64,
+    pub deposit_amount: u64,
+    pub late_fee_percent: u8,
+    pub min_renter_score: u16,
+    pub max_late_payment_percent: Option<u8>,
+    pub cancel_allowed_after: u16,
+    pub cancel_penalty_percent: u8,
+    pub months: u8,
+    pub schedule: PaymentSchedule,
+    pub yield_renter_share_percent: Option<u8>,
+}
+
+impl From<&Escrow> for ListingTerms {
+    fn from(escrow: &Escrow) -> Self {
+        Self {
+            monthly_rent: escrow.monthly_rent,
+            deposit_amount: escrow.deposit_amount,
+            late_fee_percent: escrow.late_fee_percent,
+            min_renter_score: escrow.min_renter_score,
+            max_late_payment_percent: escrow.max_late_payment_percent,
+            cancel_allowed_after: escrow.cancel_allowed_after,
+            cancel_penalty_percent: escrow.cancel_penalty_percent,
+            months: escrow.months,
+            schedule: escrow.schedule,
+            yield_renter_share_percent: escrow.yield_renter_share_percent,
+        }
+    }
+}
+
+/// Creates the landlord profile and mints their collection NFT.
+pub fn init_landlord(landlord: &Pubkey, name: String, symbol: String, uri: String) -> Instruction {
+    let collection_mint = pda::collection_mint(landlord);
+    let collection_token_account = get_associated_token_address_with_program_id(
+        landlord,
+        &collection_mint,
+        &pda::NFT_TOKEN_PROGRAM,
+    );
+    let accounts = nft_backend!(
+        metaplex => accounts::InitLandlord {
+            landlord: *landlord,
+            landlord_account: pda::landlord(landlord),
+            collection_mint,
+            collection_token_account,
+            metadata: pda::metadata(&collection_mint),
+            master_edition: pda::master_edition(&collection_mint),
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: anchor_spl::metadata::ID,
+            token_program: pda::NFT_TOKEN_PROGRAM,
+            rent: sysvar::rent::ID,
+        },
+        token_2022 => accounts::InitLandlord {
+            landlord: *landlord,
+            landlord_account: pda::landlord(landlord),
+            collection_mint,
+            collection_token_account,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            token_program: pda::NFT_TOKEN_PROGRAM,
+        },
+    );
+    build(ix::InitLandlord { name, symbol, uri }, accounts)
+}
+
+/// Creates the renter profile of `wallet`.
+pub fn init_renter(wallet: &Pubkey) -> Instruction {
+    build(
+        ix::InitRenter {},
+        accounts::InitRenter {
+            signer: *wallet,
+            renter: pda::renter(wallet),
+            scoring_config: pda::scoring_config(),
+            system_program: system_program::ID,
+        },
+    )
+}
+
+/// Lists a unit: creates the escrow and property accounts and mints the edition NFT into the
+/// escrow vault.
+#[allow(clippy::too_many_arguments)]
+pub fn create_escrow(
+    landlord: &Pubkey,
+    property_id: u64,
+    payment_mint: &Pubkey,
+    payment_token_program: &Pubkey,
+    terms: &ListingTerms,
+    property_details: PropertyDetails,
+    nft_name: String,
+    nft_symbol: String,
+    nft_uri: String,
+) -> Instruction {
+    let collection_mint = pda::collection_mint(landlord);
+    let edition_mint = pda::edition_mint(landlord, property_id);
+    let escrow = pda::escrow(&edition_mint);
+    let accounts = nft_backend!(
+        metaplex => accounts::MakeEscrow {
+            landlord: *landlord,
+            escrow,
+            landlord_account: pda::landlord(landlord),
+            property: pda::property(&edition_mint),
+            edition_mint,
+            vault: pda::nft_vault(&escrow, &edition_mint),
+            metadata: pda::metadata(&edition_mint),
+            master_edition: pda::master_edition(&edition_mint),
+            collection_mint,
+            collection_metadata: pda::metadata(&collection_mint),
+            collection_master_edition: pda::master_edition(&collection_mint),
+            payment_mint: *payment_mint,
+            scoring_config: pda::scoring_config(),
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: anchor_spl::metadata::ID,
+            token_program: pda::NFT_TOKEN_PROGRAM,
+            payment_token_program: *payment_token_program,
+            rent: sysvar::rent::ID,
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+        token_2022 => accounts::MakeEscrow {
+            landlord: *landlord,
+            escrow,
+            landlord_account: pda::landlord(landlord),
+            property: pda::property(&edition_mint),
+            edition_mint,
+            vault: pda::nft_vault(&escrow, &edition_mint),
+            collection_mint,
+            payment_mint: *payment_mint,
+            scoring_config: pda::scoring_config(),
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            token_program: pda::NFT_TOKEN_PROGRAM,
+            payment_token_program: *payment_token_program,
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+    );
+    build(
+        ix::CreateEscrow {
+            property_id,
+            monthly_rent: terms.monthly_rent,
+            deposit_amount: terms.deposit_amount,
+            late_fee_percent: terms.late_fee_percent,
+            min_renter_score: terms.min_renter_score,
+            max_late_payment_percent: terms.max_late_payment_percent,
+            cancel_allowed_after: terms.cancel_allowed_after,
+            cancel_penalty_percent: terms.cancel_penalty_percent,
+            months: terms.months,
+            schedule: terms.schedule,
+            yield_renter_share_percent: terms.yield_renter_share_percent,
+            property_details,
+            nft_name,
+            nft_symbol,
+            nft_uri,
+        },
+        accounts,
+    )
+}
+
+/// Replaces the terms of an untaken listing, and the edition NFT uri when `nft_uri` is set. With
+/// the program's `token-2022-nft` feature the new rent and term are written into the NFT's
+/// metadata too.
+pub fn update_escrow(
+    landlord: &Pubkey,
+    edition_mint: &Pubkey,
+    terms: &ListingTerms,
+    nft_uri: Option<String>,
+) -> Instruction {
+    let accounts = nft_backend!(
+        metaplex => accounts::UpdateEscrow {
+            landlord: *landlord,
+            escrow: pda::escrow(edition_mint),
+            edition_mint: *edition_mint,
+            scoring_config: pda::scoring_config(),
+            metadata: nft_uri.is_some().then(|| pda::metadata(edition_mint)),
+            token_metadata_program: nft_uri.is_some().then_some(anchor_spl::metadata::ID),
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+        token_2022 => accounts::UpdateEscrow {
+            landlord: *landlord,
+            escrow: pda::escrow(edition_mint),
+            edition_mint: *edition_mint,
+            scoring_config: pda::scoring_config(),
+            token_program: pda::NFT_TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+    );
+    build(
+        ix::UpdateEscrow {
+            monthly_rent: terms.monthly_rent,
+            deposit_amount: terms.deposit_amount,
+            late_fee_percent: terms.late_fee_percent,
+            min_renter_score: terms.min_renter_score,
+            max_late_payment_percent: terms.max_late_payment_percent,
+            cancel_allowed_after: terms.cancel_allowed_after,
+            cancel_penalty_percent: terms.cancel_penalty_percent,
+            months: terms.months,
+            schedule: terms.schedule,
+            yield_renter_share_percent: terms.yield_renter_share_percent,
+            nft_uri,
+        },
+        accounts,
+    )
+}
+
+/// Takes an untaken listing down, burning its edition NFT.
+pub fn close_escrow(landlord: &Pubkey, edition_mint: &Pubkey) -> Instruction {
+    let collection_mint = pda::collection_mint(landlord);
+    let escrow = pda::escrow(edition_mint);
+    let accounts = nft_backend!(
+        metaplex => accounts::Refund {
+            landlord: *landlord,
+            escrow,
+            landlord_account: pda::landlord(landlord),
+            property: pda::property(edition_mint),
+            edition_mint: *edition_mint,
+            metadata: pda::metadata(edition_mint),
+            master_edition: pda::master_edition(edition_mint),
+            vault: pda::nft_vault(&escrow, edition_mint),
+            collection_mint,
+            collection_metadata: pda::metadata(&collection_mint),
+            token_program: pda::NFT_TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            token_metadata_program: anchor_spl::metadata::ID,
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+        token_2022 => accounts::Refund {
+            landlord: *landlord,
+            escrow,
+            landlord_account: pda::landlord(landlord),
+            property: pda::property(edition_mint),
+            edition_mint: *edition_mint,
+            vault: pda::nft_vault(&escrow, edition_mint),
+            collection_mint,
+            token_program: pda::NFT_TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+    );
+    build(ix::CloseEscrow {}, accounts)
+}
+
+/// Takes the listing of `edition_mint`, paying the deposit and moving the edition NFT into the
+/// agreement's custody.
+pub fn take_escrow(
+    renter: &Pubkey,
+    edition_mint: &Pubkey,
+    escrow: &Escrow,
+    payment_token_program: &Pubkey,
+) -> Instruction {
+    let agreement = pda::agreement(edition_mint);
+    let escrow_address = pda::escrow(edition_mint);
+    build(
+        ix::TakeEscrow {},
+        accounts::Take {
+            renter: *renter,
+            landlord: escrow.landlord,
+            renter_account: pda::renter(renter),
+            agreement,
+            escrow: escrow_address,
+            landlord_account: pda::landlord(&escrow.landlord),
+            property: pda::property(edition_mint),
+            edition_mint: *edition_mint,
+            vault: pda::nft_vault(&escrow_address, edition_mint),
+            payment_mint: escrow.payment_mint,
+            renter_payment_ata: get_associated_token_address_with_program_id(
+                renter,
+                &escrow.payment_mint,
+                payment_token_program,
+            ),
+            deposit_vault: pda::deposit_vault(
+                &agreement,
+                &escrow.payment_mint,
+                payment_token_program,
+            ),
+            nft_vault: pda::nft_vault(&agreement, edition_mint),
+            token_program: pda::NFT_TOKEN_PROGRAM,
+            payment_token_program: *payment_token_program,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+    )
+}
+
+/// Pays the current period of a single-renter agreement from `signer`'s wallet, falling back on
+/// the deposit only when the renter signs for a late period their wallet cannot cover. Pass
+/// `with_ticket` while the agreement has an open maintenance ticket, so an overdue urgent repair
+/// lets the renter withhold part of the rent.
+pub fn pay_rent(
+    signer: &Pubkey,
+    agreement: &Agreement,
+    payment_token_program: &Pubkey,
+    with_ticket: bool,
+) -> Instruction {
+    let agreement_address = pda::agreement(&agreement.edition_mint);
+    build(
+        ix::PayRent {},
+        accounts::MonthlyRent {
+            signer: *signer,
+            landlord: agreement.landlord,
+            deposit_vault: pda::deposit_vault(
+                &agreement_address,
+                &agreement.payment_mint,
+                payment_token_program,
+            ),
+            agreement: agreement_address,
+            renter: pda::renter(&agreement.renter),
+            scoring_config: pda::scoring_config(),
+            ticket: with_ticket.then(|| pda::ticket(&agreement_address)),
+            ticket_vault: with_ticket.then(|| {
+                get_associated_token_address_with_program_id(
+                    &pda::ticket(&agreement_address),
+                    &agreement.payment_mint,
+                    payment_token_program,
+                )
+            }),
+            config: with_ticket.then(pda::config),
+            payment_mint: agreement.payment_mint,
+            signer_payment_ata: get_associated_token_address_with_program_id(
+                signer,
+                &agreement.payment_mint,
+                payment_token_program,
+            ),
+            landlord_payment_ata: get_associated_token_address_with_program_id(
+                &agreement.landlord,
+                &agreement.payment_mint,
+                payment_token_program,
+            ),
+            associated_token_program: associated_token::ID,
+            payment_token_program: *payment_token_program,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+    )
+}
+
+/// Lets the landlord settle a period left unpaid past its grace window from the deposit.
+pub fn pay_from_deposit(agreement: &Agreement, payment_token_program: &Pubkey) -> Instruction {
+    let agreement_address = pda::agreement(&agreement.edition_mint);
+    build(
+        ix::PayFromDeposit {},
+        accounts::PayFromDeposit {
+            landlord: agreement.landlord,
+            deposit_vault: pda::deposit_vault(
+                &agreement_address,
+                &agreement.payment_mint,
+                payment_token_program,
+            ),
+            agreement: agreement_address,
+            renter: pda::renter(&agreement.renter),
+            scoring_config: pda::scoring_config(),
+            payment_mint: agreement.payment_mint,
+            landlord_payment_ata: get_associated_token_address_with_program_id(
+                &agreement.landlord,
+                &agreement.payment_mint,
+                payment_token_program,
+            ),
+            associated_token_program: associated_token::ID,
+            payment_token_program: *payment_token_program,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+    )
+}
+
+/// Refills the deposit back to `deposit_amount` from `signer`'s wallet after a drawdown.
+pub fn top_up_deposit(
+    signer: &Pubkey,
+    agreement: &Agreement,
+    payment_token_program: &Pubkey,
+) -> Instruction {
+    let agreement_address = pda::agreement(&agreement.edition_mint);
+    build(
+        ix::TopUpDeposit {},
+        accounts::TopUpDeposit {
+            signer: *signer,
+            agreement: agreement_address,
+            deposit_vault: pda::deposit_vault(
+                &agreement_address,
+                &agreement.payment_mint,
+                payment_token_program,
+            ),
+            payment_mint: agreement.payment_mint,
+            signer_payment_ata: get_associated_token_address_with_program_id(
+                signer,
+                &agreement.payment_mint,
+                payment_token_program,
+            ),
+            payment_token_program: *payment_token_program,
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+    )
+}
+
+/// Ends the lease: returns the edition NFT to the landlord and starts move-out. The stake and
+/// tenancy token accounts are passed when the agreement has them. Pass `with_ticket` while the
+/// agreement has an open maintenance ticket, which is settled to the landlord.
+pub fn close_agreement(
+    signer: &Pubkey,
+    agreement: &Agreement,
+    payment_token_program: &Pubkey,
+    with_ticket: bool,
+) -> Instruction {
+    let agreement_address = pda::agreement(&agreement.edition_mint);
+    let staked = agreement.deposit_staked();
+    let tenancy_mint = pda::tenancy_mint(&agreement_address);
+    build(
+        ix::CloseAgreementTransferNft {},
+        accounts::CloseAgreement {
+            signer: *signer,
+            deposit_vault: pda::deposit_vault(
+                &agreement_address,
+                &agreement.payment_mint,
+                payment_token_program,
+            ),
+            agreement: agreement_address,
+            renter: pda::renter(&agreement.renter),
+            scoring_config: pda::scoring_config(),
+            landlord: agreement.landlord,
+            landlord_account: pda::landlord(&agreement.landlord),
+            property: pda::property(&agreement.edition_mint),
+            review: pda::review(&agreement_address),
+            landlord_ata: pda::nft_vault(&agreement.landlord, &agreement.edition_mint),
+            edition_mint: agreement.edition_mint,
+            nft_vault: pda::nft_vault(&agreement_address, &agreement.edition_mint),
+            payment_mint: agreement.payment_mint,
+            landlord_payment_ata: get_associated_token_address_with_program_id(
+                &agreement.landlord,
+                &agreement.payment_mint,
+                payment_token_program,
+            ),
+            ticket: pda::ticket(&agreement_address),
+            ticket_vault: with_ticket.then(|| {
+                get_associated_token_address_with_program_id(
+                    &pda::ticket(&agreement_address),
+                    &agreement.payment_mint,
+                    payment_token_program,
+                )
+            }),
+            stake_account: staked.then(|| pda::stake_account(&agreement_address)),
+            clock: staked.then_some(sysvar::clock::ID),
+            stake_program: staked.then(Stake::id),
+            tenancy_mint: agreement.tenancy_token.then_some(tenancy_mint),
+            renter_tenancy_ata: agreement.tenancy_token.then(|| {
+                get_associated_token_address_with_program_id(
+                    &agreement.renter,
+                    &tenancy_mint,
+                    &token_2022::ID,
+                )
+            }),
+            token_2022_program: agreement.tenancy_token.then_some(token_2022::ID),
+            associated_token_program: associated_token::ID,
+            token_program: pda::NFT_TOKEN_PROGRAM,
+            payment_token_program: *payment_token_program,
+            system_program: system_program::ID,
+            event_authority: pda::event_authority(),
+            program: capstone::ID,
+        },
+    )
+}
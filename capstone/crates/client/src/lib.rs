@@ -0,0 +1,18 @@
+//! Off-chain helpers for the capstone rental program.
+//!
+//! - [`pda`] derives every program address the program uses, Metaplex metadata included.
+//! - [`instruction`] builds the instructions of the listing and lease lifecycle with their full
+//!   account lists.
+//! - [`state`] reads `Escrow`, `Agreement` and `Renter` accounts and answers the questions a
+//!   backend keeps asking about them, like when rent is due next.
+//!
+//! Instructions outside the lifecycle can still be built from `capstone::instruction` and
+//! `capstone::accounts`, with the addresses from [`pda`].
+
+pub mod instruction;
+pub mod pda;
+pub mod state;
+
+pub use capstone::{
+    Agreement, Escrow, PaymentSchedule, PropertyDetails, Renter, UnitType, ID as PROGRAM_ID,
+};
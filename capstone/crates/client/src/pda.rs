@@ -0,0 +1,121 @@
+//! Program addresses of the capstone program and the Metaplex accounts it creates.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{associated_token::get_associated_token_address_with_program_id, metadata};
+
+fn find(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &capstone::ID).0
+}
+
+/// Landlord's collection NFT mint, created by `init_landlord`.
+pub fn collection_mint(landlord: &Pubkey) -> Pubkey {
+    find(&[b"collection_mint", landlord.as_ref()])
+}
+
+/// Edition NFT mint of a listing, seeded by the landlord's collection and property id.
+pub fn edition_mint(landlord: &Pubkey, property_id: u64) -> Pubkey {
+    find(&[
+        b"edition",
+        collection_mint(landlord).as_ref(),
+        property_id.to_le_bytes().as_ref(),
+    ])
+}
+
+pub fn escrow(edition_mint: &Pubkey) -> Pubkey {
+    find(&[b"escrow", edition_mint.as_ref()])
+}
+
+pub fn agreement(edition_mint: &Pubkey) -> Pubkey {
+    find(&[b"agreement", edition_mint.as_ref()])
+}
+
+pub fn renter(wallet: &Pubkey) -> Pubkey {
+    find(&[b"renter", wallet.as_ref()])
+}
+
+pub fn landlord(wallet: &Pubkey) -> Pubkey {
+    find(&[b"landlord", wallet.as_ref()])
+}
+
+pub fn property(edition_mint: &Pubkey) -> Pubkey {
+    find(&[b"property", edition_mint.as_ref()])
+}
+
+pub fn review(agreement: &Pubkey) -> Pubkey {
+    find(&[b"review", agreement.as_ref()])
+}
+
+pub fn ticket(agreement: &Pubkey) -> Pubkey {
+    find(&[b"ticket", agreement.as_ref()])
+}
+
+pub fn tenancy_mint(agreement: &Pubkey) -> Pubkey {
+    find(&[b"tenancy", agreement.as_ref()])
+}
+
+pub fn stake_account(agreement: &Pubkey) -> Pubkey {
+    find(&[b"stake", agreement.as_ref()])
+}
+
+pub fn attestation(renter: &Pubkey) -> Pubkey {
+    find(&[b"attestation", renter.as_ref()])
+}
+
+pub fn config() -> Pubkey {
+    find(&[b"config"])
+}
+
+pub fn scoring_config() -> Pubkey {
+    find(&[b"scoring_config"])
+}
+
+/// Signs the self-CPI that `emit_cpi!` logs events through.
+pub fn event_authority() -> Pubkey {
+    find(&[b"__event_authority"])
+}
+
+/// Token account the agreement holds the renter's deposit in.
+pub fn deposit_vault(
+    agreement: &Pubkey,
+    payment_mint: &Pubkey,
+    payment_token_program: &Pubkey,
+) -> Pubkey {
+    get_associated_token_address_with_program_id(agreement, payment_mint, payment_token_program)
+}
+
+/// Token program of the collection and edition NFTs, Token-2022 when the program is built with
+/// its `token-2022-nft` feature.
+pub const NFT_TOKEN_PROGRAM: Pubkey = if capstone::TOKEN_2022_NFT {
+    anchor_spl::token_2022::ID
+} else {
+    anchor_spl::token::ID
+};
+
+/// Token account holding a listing's edition NFT, the escrow's while listed and the agreement's
+/// while leased.
+pub fn nft_vault(owner: &Pubkey, edition_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, edition_mint, &NFT_TOKEN_PROGRAM)
+}
+
+/// Metaplex metadata account of `mint`.
+pub fn metadata(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", metadata::ID.as_ref(), mint.as_ref()],
+        &metadata::ID,
+    )
+    .0
+}
+
+/// Metaplex master edition account of `mint`.
+pub fn master_edition(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            metadata::ID.as_ref(),
+            mint.as_ref(),
+            b"edition",
+        ],
+        &metadata::ID,
+    )
+    .0
+}
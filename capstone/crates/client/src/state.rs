@@ -0,0 +1,63 @@
+//! Readers for the program's accounts and the figures a backend derives from them.
+
+use anchor_lang::{prelude::*, AccountDeserialize};
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::StateWithExtensions, state::Account as TokenAccount,
+};
+use capstone::{error::ErrorCode, Agreement, Escrow, Renter};
+
+/// Deserializes account data as `T`, checking its discriminator.
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+pub fn escrow(data: &[u8]) -> Result<Escrow> {
+    decode(data)
+}
+
+pub fn agreement(data: &[u8]) -> Result<Agreement> {
+    decode(data)
+}
+
+pub fn renter(data: &[u8]) -> Result<Renter> {
+    decode(data)
+}
+
+/// The next rent period an agreement is waiting on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RentDue {
+    pub period: u16,
+    pub due_date: i64,
+    /// Paying after this is late and draws the rent from the deposit.
+    pub late_after: i64,
+    /// Rent and assessed late fees, less any installments already paid toward the period.
+    pub amount: u64,
+}
+
+/// Returns the oldest unpaid period of `agreement`, or `None` once every period is paid.
+pub fn next_rent_due(agreement: &Agreement) -> Result<Option<RentDue>> {
+    if agreement.payments_made >= u16::from(agreement.periods) {
+        return Ok(None);
+    }
+    let period = agreement.payments_made;
+    Ok(Some(RentDue {
+        period,
+        due_date: agreement.schedule.due_date(agreement.start_date, period)?,
+        late_after: agreement
+            .schedule
+            .late_after(agreement.start_date, period)?,
+        amount: agreement.period_balance()?,
+    }))
+}
+
+/// Deposit held for `agreement`: the balance of its deposit vault, whose account data is
+/// `deposit_vault`, plus any principal delegated to the stake account. Works for SPL Token and
+/// Token-2022 vaults.
+pub fn deposit_balance(agreement: &Agreement, deposit_vault: &[u8]) -> Result<u64> {
+    let vault = StateWithExtensions::<TokenAccount>::unpack(deposit_vault)?;
+    vault
+        .base
+        .amount
+        .checked_add(agreement.staked_amount)
+        .ok_or(ErrorCode::Overflow.into())
+}
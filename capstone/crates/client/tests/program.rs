@@ -0,0 +1,172 @@
+#![cfg(all(feature = "test-sbf", not(feature = "token-2022-nft")))]
+
+// the program's litesvm fixtures write listings and agreements straight into the bank, with SPL
+// Token edition NFTs the Token-2022 builders do not address
+#[path = "../../../programs/capstone/tests/common/mod.rs"]
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use capstone::{AgreementStatus, Escrow};
+use capstone_client::{
+    instruction::{self, ListingTerms},
+    pda,
+    state::{self, RentDue},
+};
+use common::*;
+use litesvm::LiteSVM;
+use solana_signer::Signer;
+
+fn account_data(svm: &LiteSVM, address: &Pubkey) -> Vec<u8> {
+    svm.get_account(address).expect("account missing").data
+}
+
+#[test]
+fn builders_drive_a_lease_from_take_to_move_out() {
+    let mut svm = setup();
+    let landlord = funded_keypair(&mut svm);
+    let renter = funded_keypair(&mut svm);
+    let payment_mint = Pubkey::new_unique();
+    set_mint(&mut svm, payment_mint, landlord.pubkey(), 6, u64::MAX / 2);
+    set_scoring_config(&mut svm, Pubkey::new_unique(), scoring_model());
+    set_token_account(&mut svm, renter.pubkey(), payment_mint, WALLET_BALANCE);
+    warp_to(&mut svm, START_DATE);
+
+    send(
+        &mut svm,
+        instruction::init_renter(&renter.pubkey()),
+        &renter,
+        &[],
+    )
+    .unwrap();
+    let profile = state::renter(&account_data(&svm, &pda::renter(&renter.pubkey()))).unwrap();
+    assert_eq!(profile.score, scoring_model().start_score);
+
+    let listing = Listing::new(&mut svm, &landlord.pubkey(), payment_mint, 1);
+    assert_eq!(pda::escrow(&listing.edition_mint), listing.escrow);
+    let escrow = state::escrow(&account_data(&svm, &listing.escrow)).unwrap();
+    send(
+        &mut svm,
+        instruction::take_escrow(
+            &renter.pubkey(),
+            &listing.edition_mint,
+            &escrow,
+            &anchor_spl::token::ID,
+        ),
+        &renter,
+        &[],
+    )
+    .unwrap();
+
+    let agreement_address = pda::agreement(&listing.edition_mint);
+    let agreement = state::agreement(&account_data(&svm, &agreement_address)).unwrap();
+    let deposit_vault =
+        pda::deposit_vault(&agreement_address, &payment_mint, &anchor_spl::token::ID);
+    assert_eq!(
+        state::deposit_balance(&agreement, &account_data(&svm, &deposit_vault)).unwrap(),
+        DEPOSIT
+    );
+    assert_eq!(
+        state::next_rent_due(&agreement).unwrap(),
+        Some(RentDue {
+            period: 0,
+            due_date: START_DATE,
+            late_after: START_DATE + 3 * DAY,
+            amount: RENT,
+        })
+    );
+
+    send(
+        &mut svm,
+        instruction::pay_rent(&renter.pubkey(), &agreement, &anchor_spl::token::ID, false),
+        &renter,
+        &[],
+    )
+    .unwrap();
+    let agreement = state::agreement(&account_data(&svm, &agreement_address)).unwrap();
+    let due = state::next_rent_due(&agreement).unwrap().unwrap();
+    assert_eq!(due.period, 1);
+    assert_eq!(due.due_date, START_DATE + MONTH);
+
+    warp_to(&mut svm, START_DATE + MONTH + DAY);
+    send(
+        &mut svm,
+        instruction::close_agreement(&renter.pubkey(), &agreement, &anchor_spl::token::ID, false),
+        &renter,
+        &[],
+    )
+    .unwrap();
+    let agreement = state::agreement(&account_data(&svm, &agreement_address)).unwrap();
+    assert_eq!(agreement.status, AgreementStatus::MoveOut);
+    assert_eq!(
+        token_balance(
+            &svm,
+            &pda::nft_vault(&landlord.pubkey(), &listing.edition_mint)
+        ),
+        1
+    );
+}
+
+#[test]
+fn update_escrow_builder_changes_listing_terms() {
+    let mut svm = setup();
+    let landlord = funded_keypair(&mut svm);
+    let payment_mint = Pubkey::new_unique();
+    set_mint(&mut svm, payment_mint, landlord.pubkey(), 6, u64::MAX / 2);
+    let listing = Listing::new(&mut svm, &landlord.pubkey(), payment_mint, 1);
+
+    let escrow = state::escrow(&account_data(&svm, &listing.escrow)).unwrap();
+    let terms = ListingTerms {
+        monthly_rent: 2 * RENT,
+        months: 6,
+        ..ListingTerms::from(&escrow)
+    };
+    send(
+        &mut svm,
+        instruction::update_escrow(&landlord.pubkey(), &listing.edition_mint, &terms, None),
+        &landlord,
+        &[],
+    )
+    .unwrap();
+
+    let escrow: Escrow = state::decode(&account_data(&svm, &listing.escrow)).unwrap();
+    assert_eq!(ListingTerms::from(&escrow), terms);
+    assert!(state::agreement(&account_data(&svm, &listing.escrow)).is_err());
+}
+
+#[test]
+fn deposit_builders_charge_and_refill_a_missed_period() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let agreement = state::agreement(&account_data(&svm, &lease.agreement)).unwrap();
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+
+    send(
+        &mut svm,
+        instruction::pay_from_deposit(&agreement, &anchor_spl::token::ID),
+        &lease.landlord,
+        &[],
+    )
+    .unwrap();
+    let agreement = state::agreement(&account_data(&svm, &lease.agreement)).unwrap();
+    let drawn = RENT + RENT / 10;
+    assert_eq!(
+        state::deposit_balance(&agreement, &account_data(&svm, &lease.deposit_vault)).unwrap(),
+        DEPOSIT - drawn
+    );
+
+    send(
+        &mut svm,
+        instruction::top_up_deposit(&lease.renter.pubkey(), &agreement, &anchor_spl::token::ID),
+        &lease.renter,
+        &[],
+    )
+    .unwrap();
+    assert_eq!(
+        state::deposit_balance(&agreement, &account_data(&svm, &lease.deposit_vault)).unwrap(),
+        DEPOSIT
+    );
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE - drawn
+    );
+}
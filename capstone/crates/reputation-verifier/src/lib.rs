@@ -0,0 +1,207 @@
+//! Reads and checks the renter reputation attestations written by the capstone program.
+//!
+//! A renter publishes their reputation with `attest_reputation`, which copies their score and
+//! payment record into an `Attestation` PDA at `[b"attestation", renter]`. Only the capstone
+//! program can write that account, so another program can trust it once the owner, address and
+//! discriminator check out. Programs that want a fresh snapshot can CPI
+//! [`cpi::attest_reputation`] before reading it, paying for the refresh themselves; the renter
+//! does not have to sign.
+
+use anchor_lang::prelude::*;
+
+pub use capstone::{cpi, Attestation, ID as ATTESTER};
+
+#[error_code(offset = 9000)]
+pub enum VerifyError {
+    #[msg("Attestation is not owned by the capstone program!")]
+    AttestationOwnerMismatch,
+    #[msg("Attestation is not the renter's attestation account!")]
+    AttestationAddressMismatch,
+    #[msg("Attestation is older than the verifier accepts!")]
+    AttestationExpired,
+    #[msg("Attested score is too low!")]
+    ScoreTooLow,
+    #[msg("Attested late payments are too high!")]
+    TooManyLatePayments,
+    #[msg("Not enough completed leases attested!")]
+    TooFewCompletedLeases,
+}
+
+/// Address of the attestation account for `renter`.
+pub fn attestation_address(renter: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"attestation", renter.as_ref()], &ATTESTER)
+}
+
+/// What a consuming program asks of a renter's attested reputation.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Requirements {
+    pub min_score: i16,
+    pub max_late_payment_percent: Option<u8>,
+    pub min_completed_leases: u32,
+    /// Oldest attestation accepted, in seconds before `now`.
+    pub max_age: Option<i64>,
+}
+
+impl Requirements {
+    pub fn check(&self, attestation: &Attestation, now: i64) -> Result<()> {
+        if let Some(max_age) = self.max_age {
+            require!(
+                now.saturating_sub(attestation.attested_at) <= max_age,
+                VerifyError::AttestationExpired
+            );
+        }
+        require!(
+            attestation.score >= self.min_score,
+            VerifyError::ScoreTooLow
+        );
+        if let Some(max_late_percent) = self.max_late_payment_percent {
+            require!(
+                attestation.late_payment_percent() <= u64::from(max_late_percent),
+                VerifyError::TooManyLatePayments
+            );
+        }
+        require!(
+            attestation.completed_leases >= self.min_completed_leases,
+            VerifyError::TooFewCompletedLeases
+        );
+        Ok(())
+    }
+}
+
+/// Deserializes `info` as the capstone attestation of `renter`, checking owner, discriminator,
+/// address and attester.
+pub fn load(info: &AccountInfo, renter: &Pubkey) -> Result<Attestation> {
+    require_keys_eq!(*info.owner, ATTESTER, VerifyError::AttestationOwnerMismatch);
+    let attestation = Attestation::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+    let address = Pubkey::create_program_address(
+        &[b"attestation", renter.as_ref(), &[attestation.bump]],
+        &ATTESTER,
+    )
+    .map_err(|_| VerifyError::AttestationAddressMismatch)?;
+    require_keys_eq!(info.key(), address, VerifyError::AttestationAddressMismatch);
+    require_keys_eq!(
+        attestation.renter,
+        *renter,
+        VerifyError::AttestationAddressMismatch
+    );
+    require_keys_eq!(
+        attestation.attester,
+        ATTESTER,
+        VerifyError::AttestationOwnerMismatch
+    );
+    Ok(attestation)
+}
+
+/// Loads the attestation of `renter` and checks it against `requirements` at `now`.
+pub fn verify(
+    info: &AccountInfo,
+    renter: &Pubkey,
+    requirements: &Requirements,
+    now: i64,
+) -> Result<Attestation> {
+    let attestation = load(info, renter)?;
+    requirements.check(&attestation, now)?;
+    Ok(attestation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_750_000_000;
+
+    fn attestation(renter: Pubkey, bump: u8) -> Attestation {
+        Attestation {
+            renter,
+            attester: ATTESTER,
+            score: 24,
+            total_payments: 10,
+            late_payments: 1,
+            completed_leases: 2,
+            attested_at: NOW - 100,
+            bump,
+        }
+    }
+
+    fn account_data(attestation: &Attestation) -> Vec<u8> {
+        let mut data = Vec::new();
+        attestation.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    fn error_code(result: Result<Attestation>) -> u32 {
+        match result.map(|_| ()) {
+            Err(Error::AnchorError(error)) => error.error_code_number,
+            other => panic!("expected an anchor error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn accepts_a_matching_attestation_and_rejects_unmet_requirements() {
+        let renter = Pubkey::new_unique();
+        let (address, bump) = attestation_address(&renter);
+        let mut data = account_data(&attestation(renter, bump));
+        let mut lamports = 1;
+        let info = AccountInfo::new(
+            &address,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &ATTESTER,
+            false,
+            0,
+        );
+
+        let requirements = Requirements {
+            min_score: 20,
+            max_late_payment_percent: Some(10),
+            min_completed_leases: 2,
+            max_age: Some(3600),
+        };
+        assert_eq!(
+            verify(&info, &renter, &requirements, NOW).unwrap().score,
+            24
+        );
+
+        let stricter = Requirements {
+            max_late_payment_percent: Some(5),
+            ..requirements
+        };
+        assert_eq!(
+            error_code(verify(&info, &renter, &stricter, NOW)),
+            u32::from(VerifyError::TooManyLatePayments)
+        );
+        assert_eq!(
+            error_code(verify(&info, &renter, &requirements, NOW + 3600)),
+            u32::from(VerifyError::AttestationExpired)
+        );
+        assert_eq!(
+            error_code(verify(&info, &Pubkey::new_unique(), &requirements, NOW)),
+            u32::from(VerifyError::AttestationAddressMismatch)
+        );
+    }
+
+    #[test]
+    fn rejects_accounts_the_attester_does_not_own() {
+        let renter = Pubkey::new_unique();
+        let (address, bump) = attestation_address(&renter);
+        let mut data = account_data(&attestation(renter, bump));
+        let mut lamports = 1;
+        let impostor = Pubkey::new_unique();
+        let info = AccountInfo::new(
+            &address,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &impostor,
+            false,
+            0,
+        );
+        assert_eq!(
+            error_code(load(&info, &renter)),
+            u32::from(VerifyError::AttestationOwnerMismatch)
+        );
+    }
+}
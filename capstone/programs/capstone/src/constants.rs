@@ -2,3 +2,42 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const SEED: &str = "anchor";
+
+// how far a renewal may move the rent, in percent of the current rent
+#[constant]
+pub const MAX_RENT_CHANGE_PERCENT: u64 = 10;
+
+// how long the renter has to accept a renewal proposal, in seconds
+#[constant]
+pub const RENEWAL_WINDOW: i64 = 14 * 24 * 60 * 60;
+
+// missed rent periods after which the landlord may terminate the agreement
+#[constant]
+pub const MAX_MISSED_PAYMENTS: u16 = 2;
+
+// least a landlord can put toward a repair, in percent of the monthly rent, so a token budget
+// can't stop the repair SLA clock
+#[constant]
+pub const MIN_REPAIR_BUDGET_PERCENT: u64 = 10;
+
+// tip the renter pays the keeper that cranked an auto-pay collection, in basis points of the rent
+#[constant]
+pub const KEEPER_TIP_BPS: u64 = 10;
+
+// how long after move-out landlord and renter can rate each other, in seconds
+#[constant]
+pub const REVIEW_WINDOW: i64 = 30 * 24 * 60 * 60;
+
+// ratings run from 1 to MAX_RATING
+#[constant]
+pub const MAX_RATING: u8 = 5;
+
+// most renters, the lead renter included, that can share one agreement
+#[constant]
+pub const MAX_TENANTS: u8 = 4;
+
+// whether the NFTs are Token-2022 mints rather than Metaplex ones, the listing contexts differ
+pub const TOKEN_2022_NFT: bool = cfg!(feature = "token-2022-nft");
+
+// owner of the program's ProgramData account, whose upgrade authority may create the configs
+pub const BPF_LOADER_UPGRADEABLE: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
@@ -8,4 +8,118 @@ pub enum ErrorCode {
     Unauthorized,
     #[msg("Unsufficient funds in deposit!")]
     DepositFundsLow,
+    #[msg("Invalid rent schedule!")]
+    InvalidSchedule,
+    #[msg("Rent for this period is not due yet!")]
+    RentNotDue,
+    #[msg("All rent for this agreement is already paid!")]
+    RentFullyPaid,
+    #[msg("Rent is still within its grace period!")]
+    PaymentNotMissed,
+    #[msg("Invalid escrow terms!")]
+    InvalidEscrowTerms,
+    #[msg("Renter score is below the listing minimum!")]
+    RenterScoreTooLow,
+    #[msg("Renter has too many late payments for this listing!")]
+    TooManyLatePayments,
+    #[msg("Agreement is not active!")]
+    AgreementNotActive,
+    #[msg("Agreement is not in move-out!")]
+    NotInMoveOut,
+    #[msg("Deduction claim window has closed!")]
+    ClaimWindowClosed,
+    #[msg("Deduction claim window is still open!")]
+    ClaimWindowOpen,
+    #[msg("Deduction claim is not in the expected state!")]
+    InvalidClaimStatus,
+    #[msg("Deduction exceeds the deposit!")]
+    DeductionTooLarge,
+    #[msg("Evidence URI is too long!")]
+    EvidenceUriTooLong,
+    #[msg("Deadline has passed!")]
+    DeadlinePassed,
+    #[msg("Deduction claim is still awaiting a decision!")]
+    ClaimPending,
+    #[msg("Deduction claim account is required!")]
+    ClaimAccountMissing,
+    #[msg("Invalid marketplace config!")]
+    InvalidConfig,
+    #[msg("Invalid renewal terms!")]
+    InvalidRenewalTerms,
+    #[msg("Rent change exceeds the renewal cap!")]
+    RentChangeTooLarge,
+    #[msg("Renewal terms do not match the proposal!")]
+    RenewalTermsChanged,
+    #[msg("Renter is not far enough behind on rent to terminate!")]
+    TerminationNotAllowed,
+    #[msg("Invalid scoring model!")]
+    InvalidScoringModel,
+    #[msg("Deposit yield is not enabled for this agreement!")]
+    YieldNotEnabled,
+    #[msg("Only SOL deposits can be staked!")]
+    StakingRequiresNativeMint,
+    #[msg("Deposit is staked, withdraw the stake first!")]
+    DepositStaked,
+    #[msg("Deposit is not staked!")]
+    DepositNotStaked,
+    #[msg("Stake account is required!")]
+    StakeAccountMissing,
+    #[msg("Payment amount must be greater than zero!")]
+    InvalidPaymentAmount,
+    #[msg("Settle the outstanding rent first!")]
+    ArrearsOutstanding,
+    #[msg("Auto-pay is not enabled for this agreement!")]
+    AutopayNotEnabled,
+    #[msg("Auto-pay allowance or balance is too low and the grace period has not ended!")]
+    RentNotCollectable,
+    #[msg("Agreement is shared, each renter pays their own share!")]
+    SharedAgreement,
+    #[msg("Agreement already has the maximum number of renters!")]
+    TooManyTenants,
+    #[msg("Invalid rent share!")]
+    InvalidShare,
+    #[msg("Signer is not a renter on this agreement!")]
+    NotATenant,
+    #[msg("Renter is already on this agreement!")]
+    AlreadyATenant,
+    #[msg("Share for this period is already paid!")]
+    ShareAlreadyPaid,
+    #[msg("Renter accounts do not match the agreement!")]
+    TenantAccountMismatch,
+    #[msg("Turn auto-pay off first!")]
+    AutopayEnabled,
+    #[msg("Renter was not nominated to take over this agreement!")]
+    NotNominated,
+    #[msg("Invalid successor!")]
+    InvalidSuccessor,
+    #[msg("Tenancy token was already issued to the renter!")]
+    TenancyTokenIssued,
+    #[msg("Tenancy token accounts are required!")]
+    TenancyTokenAccountsMissing,
+    #[msg("Invalid property details!")]
+    InvalidPropertyDetails,
+    #[msg("Rating must be between 1 and 5!")]
+    InvalidRating,
+    #[msg("Review window has closed!")]
+    ReviewWindowClosed,
+    #[msg("Already rated this agreement!")]
+    AlreadyRated,
+    #[msg("Metadata accounts are required to update the NFT uri!")]
+    MetadataAccountsMissing,
+    #[msg("Maintenance ticket is not in the expected state!")]
+    InvalidTicketStatus,
+    #[msg("Config account is required to withhold rent!")]
+    ConfigAccountMissing,
+    #[msg("Deposit is already at its full amount!")]
+    DepositNotDepleted,
+    #[msg("Signer is not the program's upgrade authority!")]
+    NotUpgradeAuthority,
+    #[msg("Ticket vault is required to withhold rent!")]
+    TicketVaultMissing,
+    #[msg("Renewal proposal has expired!")]
+    RenewalExpired,
+    #[msg("Payment account already auto-pays another agreement, revoke that approval first!")]
+    AutopayDelegateTaken,
+    #[msg("Repair budget is below the minimum!")]
+    RepairBudgetTooLow,
 }
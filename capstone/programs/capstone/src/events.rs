@@ -0,0 +1,320 @@
+use anchor_lang::prelude::*;
+
+use crate::Urgency;
+
+#[event]
+pub struct ListingCreated {
+    pub escrow: Pubkey,
+    pub landlord: Pubkey,
+    pub edition_mint: Pubkey,
+    pub property_id: u64,
+    pub payment_mint: Pubkey,
+    pub monthly_rent: u64,
+    pub deposit_amount: u64,
+    pub months: u8,
+}
+
+#[event]
+pub struct ListingUpdated {
+    pub escrow: Pubkey,
+    pub landlord: Pubkey,
+    pub edition_mint: Pubkey,
+    pub monthly_rent: u64,
+    pub deposit_amount: u64,
+    pub months: u8,
+    pub uri_updated: bool,
+}
+
+#[event]
+pub struct ListingClosed {
+    pub escrow: Pubkey,
+    pub landlord: Pubkey,
+    pub edition_mint: Pubkey,
+}
+
+#[event]
+pub struct AgreementTaken {
+    pub agreement: Pubkey,
+    pub landlord: Pubkey,
+    pub renter: Pubkey,
+    pub edition_mint: Pubkey,
+    pub start_date: i64,
+    pub end_date: i64,
+    pub rent_amount: u64,
+    pub deposit_amount: u64,
+}
+
+#[event]
+pub struct TenancyTokenIssued {
+    pub agreement: Pubkey,
+    pub renter: Pubkey,
+    pub tenancy_mint: Pubkey,
+}
+
+#[event]
+pub struct RentPaid {
+    pub agreement: Pubkey,
+    pub renter: Pubkey,
+    pub period: u16,
+    // everything paid toward the period, late fee and rent withheld for a repair included
+    pub amount: u64,
+    pub late_fee: u64,
+    pub late: bool,
+    // late rent is taken from the deposit instead of the renter's wallet
+    pub from_deposit: bool,
+}
+
+#[event]
+pub struct PartialPaymentApplied {
+    pub agreement: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub periods_settled: u16,
+    pub period_paid: u64,
+    pub late_fees_owed: u64,
+    pub amount_owed: u64,
+}
+
+#[event]
+pub struct AutopayChanged {
+    pub agreement: Pubkey,
+    pub renter: Pubkey,
+    pub enabled: bool,
+    pub allowance: u64,
+}
+
+#[event]
+pub struct KeeperTipPaid {
+    pub agreement: Pubkey,
+    pub keeper: Pubkey,
+    pub period: u16,
+    pub amount: u64,
+}
+
+#[event]
+pub struct CoRenterAdded {
+    pub agreement: Pubkey,
+    pub co_renter: Pubkey,
+    pub share_percent: u8,
+    pub deposit_contribution: u64,
+}
+
+#[event]
+pub struct SharePaid {
+    pub agreement: Pubkey,
+    pub renter: Pubkey,
+    pub period: u16,
+    pub amount: u64,
+    pub late: bool,
+}
+
+#[event]
+pub struct DepositDrawdown {
+    pub agreement: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct DepositToppedUp {
+    pub agreement: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+}
+
+#[event]
+pub struct ScoreChanged {
+    pub renter: Pubkey,
+    pub previous_score: i16,
+    pub new_score: i16,
+}
+
+#[event]
+pub struct RatingLeft {
+    pub agreement: Pubkey,
+    pub rater: Pubkey,
+    pub rated: Pubkey,
+    pub rating: u8,
+    pub review_hash: [u8; 32],
+}
+
+#[event]
+pub struct ReputationAttested {
+    pub renter: Pubkey,
+    pub attestation: Pubkey,
+    pub score: i16,
+    pub total_payments: u32,
+    pub late_payments: u32,
+    pub completed_leases: u32,
+    pub attested_at: i64,
+}
+
+#[event]
+pub struct MoveOutStarted {
+    pub agreement: Pubkey,
+    pub renter: Pubkey,
+    pub move_out_date: i64,
+    pub cancel_penalty: u64,
+}
+
+#[event]
+pub struct AgreementClosed {
+    pub agreement: Pubkey,
+    pub landlord: Pubkey,
+    pub renter: Pubkey,
+    pub edition_mint: Pubkey,
+    pub terminated: bool,
+}
+
+#[event]
+pub struct DeductionProposed {
+    pub agreement: Pubkey,
+    pub landlord: Pubkey,
+    pub amount: u64,
+    pub evidence_uri: String,
+    pub respond_by: i64,
+}
+
+#[event]
+pub struct DeductionAccepted {
+    pub agreement: Pubkey,
+    pub renter: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DeductionDisputed {
+    pub agreement: Pubkey,
+    pub renter: Pubkey,
+    pub amount: u64,
+    pub arbitrate_by: i64,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub agreement: Pubkey,
+    pub arbiter: Pubkey,
+    pub claimed_amount: u64,
+    pub awarded_amount: u64,
+}
+
+#[event]
+pub struct DepositSettled {
+    pub agreement: Pubkey,
+    pub landlord_amount: u64,
+    pub renter_amount: u64,
+    // true when a deadline ran out and the program decided the outcome
+    pub timed_out: bool,
+}
+
+#[event]
+pub struct RenewalProposed {
+    pub agreement: Pubkey,
+    pub landlord: Pubkey,
+    pub extra_periods: u8,
+    pub rent_amount: u64,
+    pub deposit_amount: u64,
+}
+
+#[event]
+pub struct RenewalAccepted {
+    pub agreement: Pubkey,
+    pub renter: Pubkey,
+    pub end_date: i64,
+    pub rent_amount: u64,
+    pub deposit_amount: u64,
+}
+
+#[event]
+pub struct SuccessorNominated {
+    pub agreement: Pubkey,
+    pub renter: Pubkey,
+    // None withdraws an earlier nomination
+    pub successor: Option<Pubkey>,
+}
+
+#[event]
+pub struct AgreementTransferred {
+    pub agreement: Pubkey,
+    pub landlord: Pubkey,
+    pub previous_renter: Pubkey,
+    pub new_renter: Pubkey,
+    pub deposit_refunded: u64,
+    pub deposit_amount: u64,
+}
+
+#[event]
+pub struct AgreementTerminated {
+    pub agreement: Pubkey,
+    pub landlord: Pubkey,
+    pub renter: Pubkey,
+    pub unpaid_periods: u16,
+    pub arrears_paid: u64,
+    pub deposit_returned: u64,
+}
+
+#[event]
+pub struct DepositStaked {
+    pub agreement: Pubkey,
+    pub stake_account: Pubkey,
+    pub vote_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct StakeDeactivated {
+    pub agreement: Pubkey,
+    pub stake_account: Pubkey,
+}
+
+#[event]
+pub struct YieldDistributed {
+    pub agreement: Pubkey,
+    pub principal: u64,
+    pub renter_reward: u64,
+    pub landlord_reward: u64,
+}
+
+#[event]
+pub struct TicketOpened {
+    pub agreement: Pubkey,
+    pub ticket: Pubkey,
+    pub renter: Pubkey,
+    pub description_hash: [u8; 32],
+    pub urgency: Urgency,
+}
+
+#[event]
+pub struct RepairFunded {
+    pub agreement: Pubkey,
+    pub ticket: Pubkey,
+    pub contractor: Pubkey,
+    pub budget: u64,
+}
+
+#[event]
+pub struct RepairConfirmed {
+    pub agreement: Pubkey,
+    pub ticket: Pubkey,
+    pub contractor: Pubkey,
+    pub amount: u64,
+    // rent held back while the ticket was overdue, released to the landlord
+    pub withheld: u64,
+}
+
+#[event]
+pub struct TicketSettled {
+    pub agreement: Pubkey,
+    pub ticket: Pubkey,
+    // repair budget and withheld rent returned to the landlord when the lease ended
+    pub amount: u64,
+}
+
+#[event]
+pub struct RentWithheld {
+    pub agreement: Pubkey,
+    pub ticket: Pubkey,
+    pub period: u16,
+    pub amount: u64,
+}
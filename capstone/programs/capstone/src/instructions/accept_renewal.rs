@@ -0,0 +1,192 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::ErrorCode, events::RenewalAccepted, update_tenancy_terms, Agreement, AgreementStatus,
+    Renewal, RENEWAL_WINDOW,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptRenewal<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+
+    #[account(mut)]
+    pub landlord: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one=renter,
+        has_one=landlord,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        // the deposit is topped up or released by the lead renter alone, not per contribution
+        constraint=!agreement.is_shared() @ ErrorCode::SharedAgreement,
+        constraint=!agreement.deposit_staked() @ ErrorCode::DepositStaked,
+        // a half-paid period can't be repriced under the renter
+        constraint=agreement.period_paid==0 && agreement.late_fees_owed==0 @ ErrorCode::ArrearsOutstanding,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        close=landlord,
+        has_one=agreement,
+        seeds=[b"renewal",agreement.key().as_ref()],
+        bump=renewal.bump
+    )]
+    pub renewal: Account<'info, Renewal>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
+    )]
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=renter,
+        associated_token::token_program=payment_token_program
+    )]
+    pub renter_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // only needed once a tenancy token was issued, so its term and rent follow the renewal
+    /// CHECK: Tenancy token mint, checked by its seeds
+    #[account(
+        mut,
+        seeds=[b"tenancy",agreement.key().as_ref()],
+        bump
+    )]
+    pub tenancy_mint: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AcceptRenewal<'info> {
+    pub fn accept_renewal(
+        &mut self,
+        extra_periods: u8,
+        rent_amount: u64,
+        deposit_amount: u64,
+    ) -> Result<RenewalAccepted> {
+        // the renter signs the terms they saw, so a replaced proposal can't slip through
+        require!(
+            self.renewal.extra_periods == extra_periods
+                && self.renewal.rent_amount == rent_amount
+                && self.renewal.deposit_amount == deposit_amount,
+            ErrorCode::RenewalTermsChanged
+        );
+        // a stale proposal has to be made again, the landlord may not stand by it any more
+        let expires_at = self
+            .renewal
+            .proposed_at
+            .checked_add(RENEWAL_WINDOW)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(
+            Clock::get()?.unix_timestamp <= expires_at,
+            ErrorCode::RenewalExpired
+        );
+
+        let periods = self
+            .agreement
+            .periods
+            .checked_add(extra_periods)
+            .ok_or(ErrorCode::InvalidRenewalTerms)?;
+        let end_date = self
+            .agreement
+            .schedule
+            .due_date(self.agreement.start_date, u16::from(periods) - 1)?;
+
+        self.agreement.periods = periods;
+        self.agreement.end_date = end_date;
+        self.agreement.rent_amount = rent_amount;
+        self.agreement.deposit_amount = deposit_amount;
+
+        self.rebalance_deposit()?;
+        self.update_tenancy_token()?;
+
+        Ok(RenewalAccepted {
+            agreement: self.agreement.key(),
+            renter: self.renter.key(),
+            end_date,
+            rent_amount,
+            deposit_amount,
+        })
+    }
+
+    pub fn update_tenancy_token(&mut self) -> Result<()> {
+        if !self.agreement.tenancy_token {
+            return Ok(());
+        }
+        let (Some(tenancy_mint), Some(token_2022_program)) =
+            (&self.tenancy_mint, &self.token_2022_program)
+        else {
+            return err!(ErrorCode::TenancyTokenAccountsMissing);
+        };
+        update_tenancy_terms(
+            &self.agreement,
+            &tenancy_mint.to_account_info(),
+            &self.renter.to_account_info(),
+            &token_2022_program.to_account_info(),
+            &self.system_program.to_account_info(),
+        )
+    }
+
+    // the vault may have been drawn down by missed rent, so top up or release against its balance
+    pub fn rebalance_deposit(&mut self) -> Result<()> {
+        let held = self.deposit_vault.amount;
+        let required = self.agreement.deposit_amount;
+
+        if required > held {
+            let transfer_accounts = TransferChecked {
+                authority: self.renter.to_account_info(),
+                from: self.renter_payment_ata.to_account_info(),
+                to: self.deposit_vault.to_account_info(),
+                mint: self.payment_mint.to_account_info(),
+            };
+            let transfer_cpi = CpiContext::new(
+                self.payment_token_program.to_account_info(),
+                transfer_accounts,
+            );
+            transfer_checked(transfer_cpi, required - held, self.payment_mint.decimals)?;
+            msg!("Topped up deposit by {}", required - held);
+        } else if held > required {
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"agreement",
+                self.agreement.edition_mint.as_ref(),
+                &[self.agreement.bump],
+            ]];
+            let transfer_accounts = TransferChecked {
+                authority: self.agreement.to_account_info(),
+                from: self.deposit_vault.to_account_info(),
+                to: self.renter_payment_ata.to_account_info(),
+                mint: self.payment_mint.to_account_info(),
+            };
+            let transfer_cpi = CpiContext::new_with_signer(
+                self.payment_token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
+            transfer_checked(transfer_cpi, held - required, self.payment_mint.decimals)?;
+            msg!("Released {} of the deposit", held - required);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    error::ErrorCode, events::CoRenterAdded, Agreement, AgreementStatus, Renter, Tenant,
+    MAX_TENANTS,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AddCoRenter<'info> {
+    // the landlord approves who moves in, the lead renter gives up part of their share
+    pub landlord: Signer<'info>,
+
+    pub renter: Signer<'info>,
+
+    #[account(mut)]
+    pub co_renter: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=renter,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=agreement.period_paid==0 && agreement.late_fees_owed==0 @ ErrorCode::ArrearsOutstanding,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    // co-renters need their own score account so their payments can be scored
+    #[account(
+        seeds=[b"renter",co_renter.key().as_ref()],
+        bump=co_renter_account.bump
+    )]
+    pub co_renter_account: Account<'info, Renter>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=co_renter,
+        associated_token::token_program=payment_token_program
+    )]
+    pub co_renter_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=renter,
+        associated_token::token_program=payment_token_program
+    )]
+    pub renter_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub payment_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> AddCoRenter<'info> {
+    pub fn add_co_renter(&mut self, share_percent: u8) -> Result<CoRenterAdded> {
+        require!(!self.agreement.autopay, ErrorCode::AutopayEnabled);
+        require!(
+            self.agreement.tenant_index(&self.co_renter.key()).is_none()
+                && self.co_renter.key() != self.agreement.renter,
+            ErrorCode::AlreadyATenant
+        );
+
+        // the first co-renter turns a sole tenancy into a shared one with the lead renter first
+        if !self.agreement.is_shared() {
+            let lead = Tenant {
+                wallet: self.agreement.renter,
+                share_percent: 100,
+                deposit_contribution: self.agreement.deposit_amount,
+                share_paid: 0,
+                share_settled: false,
+            };
+            self.agreement.tenants.push(lead);
+        }
+        require!(
+            self.agreement.tenants.len() < usize::from(MAX_TENANTS),
+            ErrorCode::TooManyTenants
+        );
+        // the lead renter has to keep a share of their own
+        require!(
+            share_percent > 0 && share_percent < self.agreement.tenants[0].share_percent,
+            ErrorCode::InvalidShare
+        );
+
+        // the deposit is already in the vault, the co-renter buys their part from the lead renter
+        let deposit_contribution = self
+            .agreement
+            .deposit_amount
+            .checked_mul(u64::from(share_percent))
+            .and_then(|v| v.checked_div(100))
+            .ok_or(ErrorCode::Overflow)?;
+        self.reimburse_lead_renter(deposit_contribution)?;
+
+        let lead = &mut self.agreement.tenants[0];
+        lead.share_percent -= share_percent;
+        lead.deposit_contribution = lead.deposit_contribution.saturating_sub(deposit_contribution);
+        self.agreement.tenants.push(Tenant {
+            wallet: self.co_renter.key(),
+            share_percent,
+            deposit_contribution,
+            share_paid: 0,
+            share_settled: false,
+        });
+        msg!("Added co-renter with a {}% share", share_percent);
+
+        Ok(CoRenterAdded {
+            agreement: self.agreement.key(),
+            co_renter: self.co_renter.key(),
+            share_percent,
+            deposit_contribution,
+        })
+    }
+
+    pub fn reimburse_lead_renter(&mut self, amount: u64) -> Result<()> {
+        let transfer_accounts = TransferChecked {
+            authority: self.co_renter.to_account_info(),
+            from: self.co_renter_payment_ata.to_account_info(),
+            to: self.renter_payment_ata.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+        };
+        let transfer_cpi = CpiContext::new(
+            self.payment_token_program.to_account_info(),
+            transfer_accounts,
+        );
+        transfer_checked(transfer_cpi, amount, self.payment_mint.decimals)
+    }
+}
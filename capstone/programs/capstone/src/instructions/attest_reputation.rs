@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::ReputationAttested, Attestation, Renter};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AttestReputation<'info> {
+    // anyone can refresh a renter's attestation, it only copies what the renter account says
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub renter: SystemAccount<'info>,
+
+    #[account(
+        seeds=[b"renter",renter.key().as_ref()],
+        bump=renter_account.bump
+    )]
+    pub renter_account: Account<'info, Renter>,
+
+    // refreshed in place, verifiers decide how old an attestation they accept
+    #[account(
+        init_if_needed,
+        payer=payer,
+        space=8+Attestation::INIT_SPACE,
+        seeds=[b"attestation",renter.key().as_ref()],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> AttestReputation<'info> {
+    pub fn attest_reputation(
+        &mut self,
+        bumps: &AttestReputationBumps,
+    ) -> Result<ReputationAttested> {
+        let now = Clock::get()?.unix_timestamp;
+        self.attestation.record(
+            self.renter.key(),
+            &self.renter_account,
+            now,
+            bumps.attestation,
+        );
+        msg!("Reputation attested at {}", now);
+
+        Ok(ReputationAttested {
+            renter: self.renter.key(),
+            attestation: self.attestation.key(),
+            score: self.attestation.score,
+            total_payments: self.attestation.total_payments,
+            late_payments: self.attestation.late_payments,
+            completed_leases: self.attestation.completed_leases,
+            attested_at: now,
+        })
+    }
+}
@@ -1,15 +1,23 @@
-use anchor_lang::{
-    prelude::*,
-    system_program::{transfer, Transfer},
-};
+use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{transfer_checked, TransferChecked},
-    token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface},
+    stake::{deactivate_stake, DeactivateStake, Stake},
+    token_2022::Token2022,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
 };
 
-use crate::{error::ErrorCode, Agreement, Renter};
+use crate::{
+    burn_tenancy_token,
+    error::ErrorCode,
+    events::{DepositDrawdown, MoveOutStarted, ScoreChanged, StakeDeactivated, TicketSettled},
+    settle_ticket, Agreement, AgreementStatus, Landlord, Property, PropertyStatus, Renter, Review,
+    ScoringConfig, REVIEW_WINDOW,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CloseAgreement<'info> {
     #[account(mut)]
@@ -17,22 +25,62 @@ pub struct CloseAgreement<'info> {
 
     #[account(
         mut,
-        seeds=[b"deposit",agreement.key().as_ref()],
-        bump=agreement.deposit_bump
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
     )]
-    pub deposit_vault: SystemAccount<'info>,
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut,
-    close=landlord
-)]
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=payment_mint,
+        has_one=edition_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
     pub agreement: Account<'info, Agreement>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds=[b"renter",agreement.renter.as_ref()],
+        bump=renter.bump
+    )]
     pub renter: Account<'info, Renter>,
 
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
     #[account(mut)]
     pub landlord: SystemAccount<'info>,
 
+    #[account(
+        mut,
+        seeds=[b"landlord",landlord.key().as_ref()],
+        bump=landlord_account.bump
+    )]
+    pub landlord_account: Account<'info, Landlord>,
+
+    #[account(
+        mut,
+        seeds=[b"property",agreement.edition_mint.as_ref()],
+        bump=property.bump
+    )]
+    pub property: Account<'info, Property>,
+
+    #[account(
+        init,
+        payer=signer,
+        space=8+Review::INIT_SPACE,
+        seeds=[b"review",agreement.key().as_ref()],
+        bump
+    )]
+    pub review: Account<'info, Review>,
+
     #[account(
         init_if_needed,
         payer=signer,
@@ -55,158 +103,333 @@ pub struct CloseAgreement<'info> {
     )]
     pub nft_vault: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // a repair ticket still open when the lease ends is settled to the landlord
+    /// CHECK: Maintenance ticket, checked by its seeds and settled only if one was opened
+    #[account(
+        mut,
+        seeds=[b"ticket",agreement.key().as_ref()],
+        bump
+    )]
+    pub ticket: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub ticket_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // only needed when the deposit is staked, so the stake can start cooling down at move-out
+    /// CHECK: Native stake account the agreement delegated the deposit to
+    #[account(
+        mut,
+        seeds=[b"stake",agreement.key().as_ref()],
+        bump
+    )]
+    pub stake_account: Option<UncheckedAccount<'info>>,
+
+    pub clock: Option<Sysvar<'info, Clock>>,
+    pub stake_program: Option<Program<'info, Stake>>,
+
+    // only needed once a tenancy token was issued, so it can be burned from the renter's wallet
+    /// CHECK: Tenancy token mint, checked by its seeds
+    #[account(
+        mut,
+        seeds=[b"tenancy",agreement.key().as_ref()],
+        bump
+    )]
+    pub tenancy_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Renter's tenancy token account, the token program checks it holds the token
+    #[account(mut)]
+    pub renter_tenancy_ata: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> CloseAgreement<'info> {
-    pub fn close_agreement(&mut self) -> Result<()> {
+    #[allow(clippy::type_complexity)]
+    pub fn close_agreement(
+        &mut self,
+        bumps: &CloseAgreementBumps,
+    ) -> Result<(
+        MoveOutStarted,
+        Option<DepositDrawdown>,
+        Option<ScoreChanged>,
+        Option<StakeDeactivated>,
+        Option<TicketSettled>,
+    )> {
         require!(
             self.signer.key() == self.agreement.renter,
             ErrorCode::Unauthorized
         );
 
-        self.transfer_item_nft()?;
-        self.transfer_deposit_fund()?;
-        self.close_nft_vault()?;
-        Ok(())
+        transfer_item_nft(
+            &self.agreement,
+            &self.nft_vault,
+            &self.edition_mint,
+            &self.landlord_ata,
+            &self.token_program,
+        )?;
+        close_nft_vault(
+            &self.agreement,
+            &self.nft_vault,
+            self.landlord.to_account_info(),
+            &self.token_program,
+        )?;
+        let drawdown = self.transfer_deposit_fund()?;
+        let score_changed = self.update_score(drawdown.is_some())?;
+        self.release_property(drawdown.is_some())?;
+        let stake_deactivated = self.deactivate_stake()?;
+        let ticket_settled = settle_ticket(
+            &self.ticket,
+            &self.ticket_vault,
+            &self.payment_mint,
+            &self.payment_token_program,
+            self.landlord_payment_ata.to_account_info(),
+            self.signer.to_account_info(),
+        )?;
+        burn_tenancy_token(
+            &mut self.agreement,
+            &self.tenancy_mint,
+            &self.renter_tenancy_ata,
+            &self.token_2022_program,
+        )?;
+        self.start_move_out()?;
+        self.open_review(bumps.review)?;
+
+        let move_out = MoveOutStarted {
+            agreement: self.agreement.key(),
+            renter: self.agreement.renter,
+            move_out_date: self.agreement.move_out_date,
+            cancel_penalty: drawdown.as_ref().map_or(0, |d| d.amount),
+        };
+        Ok((
+            move_out,
+            drawdown,
+            score_changed,
+            stake_deactivated,
+            ticket_settled,
+        ))
     }
 
-    pub fn transfer_item_nft(&mut self) -> Result<()> {
+    pub fn deactivate_stake(&mut self) -> Result<Option<StakeDeactivated>> {
+        if !self.agreement.deposit_staked() {
+            return Ok(None);
+        }
+        let (Some(stake_account), Some(clock), Some(stake_program)) =
+            (&self.stake_account, &self.clock, &self.stake_program)
+        else {
+            return err!(ErrorCode::StakeAccountMissing);
+        };
+
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"agreement",
-            self.agreement.renter.as_ref(),
-            self.agreement.landlord.as_ref(),
+            self.agreement.edition_mint.as_ref(),
             &[self.agreement.bump],
         ]];
-        let transfer_nft_accounts = TransferChecked {
-            authority: self.agreement.to_account_info(),
-            from: self.nft_vault.to_account_info(),
-            mint: self.edition_mint.to_account_info(),
-            to: self.landlord_ata.to_account_info(),
+        let deactivate_accounts = DeactivateStake {
+            stake: stake_account.to_account_info(),
+            staker: self.agreement.to_account_info(),
+            clock: clock.to_account_info(),
         };
-        let transfer_nft_cpi = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            transfer_nft_accounts,
+        let deactivate_cpi = CpiContext::new_with_signer(
+            stake_program.to_account_info(),
+            deactivate_accounts,
             signer_seeds,
         );
-        transfer_checked(transfer_nft_cpi, 1, self.edition_mint.decimals)?;
-        msg!("Transferred NFT");
-        Ok(())
+        deactivate_stake(deactivate_cpi)?;
+        msg!("Deposit stake deactivating, withdraw it after the cooldown");
+        Ok(Some(StakeDeactivated {
+            agreement: self.agreement.key(),
+            stake_account: stake_account.key(),
+        }))
     }
 
-    pub fn transfer_deposit_fund(&mut self) -> Result<()> {
-        let clock = Clock::get()?;
-        let now = clock.unix_timestamp;
-        let seconds_in_month: i64 = 30 * 24 * 60 * 60; // 2_592_000 seconds
-        let month_offset: i64 = i64::from(self.agreement.cancel_allowed_after)
-            .checked_mul(seconds_in_month)
-            .ok_or(ErrorCode::Overflow)?;
-        let canceled_allowed_after = self
-            .agreement
-            .start_date
-            .checked_add(month_offset)
-            .ok_or(ErrorCode::Overflow)?;
-
-        if now >= canceled_allowed_after {
-            self.transfer_deposit()?;
+    pub fn update_score(&mut self, left_early: bool) -> Result<Option<ScoreChanged>> {
+        let previous_score = self.renter.score;
+        let model = self.scoring_config.model;
+        if left_early {
+            self.renter.score = model.early_termination(previous_score);
+        } else if self.agreement.payments_made >= u16::from(self.agreement.periods) {
+            self.renter.score = model.completion(previous_score);
+            self.renter.completed_leases = self
+                .renter
+                .completed_leases
+                .checked_add(1)
+                .ok_or(ErrorCode::Overflow)?;
         } else {
-            self.transfer_deposit_penalty()?;
+            return Ok(None);
         }
+        Ok(Some(ScoreChanged {
+            renter: self.agreement.renter,
+            previous_score,
+            new_score: self.renter.score,
+        }))
+    }
 
-        Ok(())
+    pub fn release_property(&mut self, left_early: bool) -> Result<()> {
+        let completed =
+            !left_early && self.agreement.payments_made >= u16::from(self.agreement.periods);
+        self.property.status = PropertyStatus::Delisted;
+        self.landlord_account.end_agreement(completed)
     }
 
-    pub fn transfer_deposit(&mut self) -> Result<()> {
-        let agreement_address = self.agreement.key();
-        let deposit_signer_seeds: &[&[&[u8]]] = &[&[
-            b"deposit",
-            agreement_address.as_ref(),
-            &[self.agreement.deposit_bump],
-        ]];
+    pub fn transfer_deposit_fund(&mut self) -> Result<Option<DepositDrawdown>> {
+        let now = Clock::get()?.unix_timestamp;
+        let canceled_allowed_after = self
+            .agreement
+            .schedule
+            .period_start(self.agreement.start_date, self.agreement.cancel_allowed_after)?;
 
-        let transfer_deposit_fund_accounts = Transfer {
-            from: self.deposit_vault.to_account_info(),
-            to: self.signer.to_account_info(),
-        };
-        let transfer_deposit_fund_cpi = CpiContext::new_with_signer(
-            self.system_program.to_account_info(),
-            transfer_deposit_fund_accounts,
-            deposit_signer_seeds,
-        );
-        transfer(transfer_deposit_fund_cpi, self.deposit_vault.lamports())?;
-        msg!("Transferred deposit SOL!");
-        Ok(())
+        // the rest of the deposit stays in the vault until the move-out claim window is settled
+        if now < canceled_allowed_after {
+            // the penalty is paid out of the vault, which is empty while the deposit is staked
+            require!(!self.agreement.deposit_staked(), ErrorCode::DepositStaked);
+            return Ok(Some(self.transfer_deposit_penalty()?));
+        }
+
+        Ok(None)
     }
 
-    pub fn transfer_deposit_penalty(&mut self) -> Result<()> {
-        let agreement_address = self.agreement.key();
-        let deposit_signer_seeds: &[&[&[u8]]] = &[&[
-            b"deposit",
-            agreement_address.as_ref(),
-            &[self.agreement.deposit_bump],
+    pub fn transfer_deposit_penalty(&mut self) -> Result<DepositDrawdown> {
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"agreement",
+            self.agreement.edition_mint.as_ref(),
+            &[self.agreement.bump],
         ]];
 
-        let transfer_deposit_fund_accounts = Transfer {
-            from: self.deposit_vault.to_account_info(),
-            to: self.signer.to_account_info(),
-        };
-        let transfer_deposit_fund_cpi = CpiContext::new_with_signer(
-            self.system_program.to_account_info(),
-            transfer_deposit_fund_accounts,
-            deposit_signer_seeds,
-        );
         let fine_fee = self
             .agreement
             .deposit_amount
             .checked_mul(u64::from(self.agreement.cancel_penalty_percent))
             .and_then(|v| v.checked_div(100))
             .ok_or(ErrorCode::Overflow)?;
-        let renter_deposit_amount = self
-            .deposit_vault
-            .lamports()
-            .checked_sub(fine_fee)
-            .ok_or(ErrorCode::DepositFundsLow)?;
-        transfer(transfer_deposit_fund_cpi, renter_deposit_amount)?;
-        msg!("Transferred deposit SOL to renter!");
-
-        let transfer_deposit_to_landlord_accounts = Transfer {
+        require!(
+            self.deposit_vault.amount >= fine_fee,
+            ErrorCode::DepositFundsLow
+        );
+
+        let transfer_deposit_to_landlord_accounts = TransferChecked {
+            authority: self.agreement.to_account_info(),
             from: self.deposit_vault.to_account_info(),
-            to: self.landlord.to_account_info(),
+            to: self.landlord_payment_ata.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
         };
         let transfer_deposit_to_landlord_cpi = CpiContext::new_with_signer(
-            self.system_program.to_account_info(),
+            self.payment_token_program.to_account_info(),
             transfer_deposit_to_landlord_accounts,
-            deposit_signer_seeds,
+            signer_seeds,
         );
-        transfer(
+        transfer_checked(
             transfer_deposit_to_landlord_cpi,
-            self.deposit_vault.lamports(),
+            fine_fee,
+            self.payment_mint.decimals,
         )?;
-        msg!("Transferred deposit SOL to landlord!");
+        msg!("Transferred deposit penalty to landlord!");
+        Ok(DepositDrawdown {
+            agreement: self.agreement.key(),
+            amount: fine_fee,
+            remaining: self.deposit_vault.amount - fine_fee,
+        })
+    }
+
+    pub fn start_move_out(&mut self) -> Result<()> {
+        self.agreement.status = AgreementStatus::MoveOut;
+        self.agreement.move_out_date = Clock::get()?.unix_timestamp;
+        msg!("Move-out started, deposit held for the claim window");
         Ok(())
     }
 
-    pub fn close_nft_vault(&mut self) -> Result<()> {
-        let signer_seeds: &[&[&[u8]]] = &[&[
-            b"agreement",
-            self.agreement.renter.as_ref(),
-            self.agreement.landlord.as_ref(),
-            &[self.agreement.bump],
-        ]];
-        let close_accounts = CloseAccount {
-            account: self.nft_vault.to_account_info(),
-            authority: self.agreement.to_account_info(),
-            destination: self.landlord.to_account_info(),
-        };
-        let close_acc_cpi = CpiContext::new_with_signer(
-            self.token_program.to_account_info(),
-            close_accounts,
-            signer_seeds,
-        );
-        close_account(close_acc_cpi)?;
-        msg!("Close Account!");
+    pub fn open_review(&mut self, bump: u8) -> Result<()> {
+        let review_by = self
+            .agreement
+            .move_out_date
+            .checked_add(REVIEW_WINDOW)
+            .ok_or(ErrorCode::Overflow)?;
+        self.review.set_inner(Review {
+            agreement: self.agreement.key(),
+            landlord: self.agreement.landlord,
+            renter: self.agreement.renter,
+            review_by,
+            landlord_rating: None,
+            landlord_review_hash: [0; 32],
+            renter_rating: None,
+            renter_review_hash: [0; 32],
+            bump,
+        });
         Ok(())
     }
 }
+
+/// Hands the listing NFT held by the agreement back to the landlord. Closing and terminating an
+/// agreement both return it this way.
+pub fn transfer_item_nft<'info>(
+    agreement: &Account<'info, Agreement>,
+    nft_vault: &InterfaceAccount<'info, TokenAccount>,
+    edition_mint: &InterfaceAccount<'info, Mint>,
+    landlord_ata: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"agreement",
+        agreement.edition_mint.as_ref(),
+        &[agreement.bump],
+    ]];
+    let transfer_nft_accounts = TransferChecked {
+        authority: agreement.to_account_info(),
+        from: nft_vault.to_account_info(),
+        mint: edition_mint.to_account_info(),
+        to: landlord_ata.to_account_info(),
+    };
+    let transfer_nft_cpi = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        transfer_nft_accounts,
+        signer_seeds,
+    );
+    transfer_checked(transfer_nft_cpi, 1, edition_mint.decimals)?;
+    msg!("Transferred NFT back to landlord");
+    Ok(())
+}
+
+/// Closes the emptied NFT vault, returning its rent to `destination`.
+pub fn close_nft_vault<'info>(
+    agreement: &Account<'info, Agreement>,
+    nft_vault: &InterfaceAccount<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"agreement",
+        agreement.edition_mint.as_ref(),
+        &[agreement.bump],
+    ]];
+    let close_accounts = CloseAccount {
+        account: nft_vault.to_account_info(),
+        authority: agreement.to_account_info(),
+        destination,
+    };
+    let close_acc_cpi = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        close_accounts,
+        signer_seeds,
+    );
+    close_account(close_acc_cpi)?;
+    msg!("Closed NFT vault");
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    draw_from_deposit,
+    error::ErrorCode,
+    events::{DepositDrawdown, KeeperTipPaid, RentPaid, ScoreChanged},
+    Agreement, AgreementStatus, Renter, ScoringConfig, KEEPER_TIP_BPS,
+};
+
+/// The keeper's cut of a collection. The renter pays it on top of the rent, so the landlord is
+/// paid in full however the rent is collected.
+pub fn keeper_tip(amount: u64) -> Result<u64> {
+    amount
+        .checked_mul(KEEPER_TIP_BPS)
+        .and_then(|v| v.checked_div(10_000))
+        .ok_or(error!(ErrorCode::Overflow))
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CollectRent<'info> {
+    // any keeper can collect due rent and earns a tip on top of it
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    pub landlord: SystemAccount<'info>,
+
+    pub renter: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=renter,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.is_shared() @ ErrorCode::SharedAgreement,
+        constraint=agreement.autopay @ ErrorCode::AutopayNotEnabled,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        seeds=[b"renter",renter.key().as_ref()],
+        bump=renter_account.bump
+    )]
+    pub renter_account: Account<'info, Renter>,
+
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
+    )]
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=renter,
+        associated_token::token_program=payment_token_program
+    )]
+    pub renter_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=keeper,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=keeper,
+        associated_token::mint=payment_mint,
+        associated_token::authority=keeper,
+        associated_token::token_program=payment_token_program
+    )]
+    pub keeper_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CollectRent<'info> {
+    pub fn collect_rent(
+        &mut self,
+    ) -> Result<(RentPaid, Option<DepositDrawdown>, ScoreChanged, KeeperTipPaid)> {
+        let now = Clock::get()?.unix_timestamp;
+        let period = self.agreement.current_period()?;
+        let schedule = self.agreement.schedule;
+        let start_date = self.agreement.start_date;
+        let due_date = schedule.due_date(start_date, period)?;
+        require!(now >= due_date, ErrorCode::RentNotDue);
+
+        let late = now > schedule.late_after(start_date, period)?;
+        self.agreement.accrue_late_fees(now)?;
+        let balance = self.agreement.period_balance()?;
+        let tip = keeper_tip(balance)?;
+        let charge = balance.checked_add(tip).ok_or(ErrorCode::Overflow)?;
+
+        let drawdown = if self.autopay_covers(charge) {
+            self.pull_from_renter(self.landlord_payment_ata.to_account_info(), balance)?;
+            self.pull_from_renter(self.keeper_payment_ata.to_account_info(), tip)?;
+            None
+        } else {
+            // a revoked or drained allowance falls back on the deposit once the grace period is over
+            require!(late, ErrorCode::RentNotCollectable);
+            let drawdown = draw_from_deposit(
+                &mut self.agreement,
+                &self.deposit_vault,
+                &self.payment_mint,
+                &self.payment_token_program,
+                &[
+                    (self.landlord_payment_ata.to_account_info(), balance),
+                    (self.keeper_payment_ata.to_account_info(), tip),
+                ],
+            )?;
+            msg!("Auto-pay unavailable, rent drawn from deposit");
+            Some(drawdown)
+        };
+
+        let late_fee = if late { self.agreement.late_fee()? } else { 0 };
+        let paid = self
+            .agreement
+            .period_paid
+            .checked_add(balance)
+            .ok_or(ErrorCode::Overflow)?;
+        self.agreement.settle_period()?;
+        let score_changed = self.renter_account.record_payment(
+            self.agreement.renter,
+            &self.scoring_config.model,
+            late.then_some(now - due_date),
+        )?;
+        msg!("Collected rent for period {}", period);
+
+        let rent_paid = RentPaid {
+            agreement: self.agreement.key(),
+            renter: self.agreement.renter,
+            period,
+            amount: paid,
+            late_fee,
+            late,
+            from_deposit: drawdown.is_some(),
+        };
+        let tip_paid = KeeperTipPaid {
+            agreement: self.agreement.key(),
+            keeper: self.keeper.key(),
+            period,
+            amount: tip,
+        };
+        Ok((rent_paid, drawdown, score_changed, tip_paid))
+    }
+
+    pub fn autopay_covers(&self, charge: u64) -> bool {
+        self.renter_payment_ata.delegate == Some(self.agreement.key()).into()
+            && self.renter_payment_ata.delegated_amount >= charge
+            && self.renter_payment_ata.amount >= charge
+    }
+
+    pub fn pull_from_renter(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"agreement",
+            self.agreement.edition_mint.as_ref(),
+            &[self.agreement.bump],
+        ]];
+        let transfer_accounts = TransferChecked {
+            authority: self.agreement.to_account_info(),
+            from: self.renter_payment_ata.to_account_info(),
+            to,
+            mint: self.payment_mint.to_account_info(),
+        };
+        let transfer_cpi = CpiContext::new_with_signer(
+            self.payment_token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        transfer_checked(transfer_cpi, amount, self.payment_mint.decimals)
+    }
+}
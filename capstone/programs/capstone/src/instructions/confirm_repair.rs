@@ -0,0 +1,215 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{
+    error::ErrorCode,
+    events::{RepairConfirmed, TicketSettled},
+    MaintenanceTicket, TicketStatus,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ConfirmRepair<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+
+    pub landlord: SystemAccount<'info>,
+
+    #[account(
+        constraint=ticket.contractor==Some(contractor.key()) @ ErrorCode::InvalidTicketStatus
+    )]
+    pub contractor: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close=renter,
+        has_one=renter,
+        has_one=landlord,
+        has_one=payment_mint,
+        constraint=ticket.status==TicketStatus::Funded @ ErrorCode::InvalidTicketStatus,
+        seeds=[b"ticket",ticket.agreement.as_ref()],
+        bump=ticket.bump
+    )]
+    pub ticket: Account<'info, MaintenanceTicket>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=ticket,
+        associated_token::token_program=payment_token_program
+    )]
+    pub ticket_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=renter,
+        associated_token::mint=payment_mint,
+        associated_token::authority=contractor,
+        associated_token::token_program=payment_token_program
+    )]
+    pub contractor_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // receives the rent withheld while the ticket was overdue
+    #[account(
+        init_if_needed,
+        payer=renter,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ConfirmRepair<'info> {
+    pub fn confirm_repair(&mut self) -> Result<RepairConfirmed> {
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"ticket",
+            self.ticket.agreement.as_ref(),
+            &[self.ticket.bump],
+        ]];
+        // the contractor gets the budget, the landlord whatever else sits in the vault: the
+        // withheld rent, and anything sent there by mistake so nothing is stranded
+        let amount = self.ticket.budget;
+        let released = self
+            .ticket_vault
+            .amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        for (to, amount) in [
+            (self.contractor_payment_ata.to_account_info(), amount),
+            (self.landlord_payment_ata.to_account_info(), released),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+            let transfer_accounts = TransferChecked {
+                authority: self.ticket.to_account_info(),
+                from: self.ticket_vault.to_account_info(),
+                to,
+                mint: self.payment_mint.to_account_info(),
+            };
+            let transfer_cpi = CpiContext::new_with_signer(
+                self.payment_token_program.to_account_info(),
+                transfer_accounts,
+                signer_seeds,
+            );
+            transfer_checked(transfer_cpi, amount, self.payment_mint.decimals)?;
+        }
+
+        // the renter paid for the vault when opening the ticket
+        let close_accounts = CloseAccount {
+            account: self.ticket_vault.to_account_info(),
+            authority: self.ticket.to_account_info(),
+            destination: self.renter.to_account_info(),
+        };
+        let close_acc_cpi = CpiContext::new_with_signer(
+            self.payment_token_program.to_account_info(),
+            close_accounts,
+            signer_seeds,
+        );
+        close_account(close_acc_cpi)?;
+        msg!("Paid contractor {} for the repair", self.contractor.key());
+
+        Ok(RepairConfirmed {
+            agreement: self.ticket.agreement,
+            ticket: self.ticket.key(),
+            contractor: self.contractor.key(),
+            amount,
+            withheld: self.ticket.withheld,
+        })
+    }
+}
+
+/// Settles the agreement's ticket when the lease ends before the repair was confirmed. The landlord
+/// gets back the budget and any withheld rent, and the renter the rent of the ticket and its vault.
+/// Does nothing when no ticket is open.
+pub fn settle_ticket<'info>(
+    ticket: &UncheckedAccount<'info>,
+    ticket_vault: &Option<InterfaceAccount<'info, TokenAccount>>,
+    payment_mint: &InterfaceAccount<'info, Mint>,
+    payment_token_program: &Interface<'info, TokenInterface>,
+    landlord_payment_ata: AccountInfo<'info>,
+    renter: AccountInfo<'info>,
+) -> Result<Option<TicketSettled>> {
+    if ticket.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*ticket.owner, crate::ID, ErrorCode::InvalidTicketStatus);
+    let settled = MaintenanceTicket::try_deserialize(&mut &ticket.try_borrow_data()?[..])?;
+    let Some(ticket_vault) = ticket_vault else {
+        return err!(ErrorCode::TicketVaultMissing);
+    };
+    require_keys_eq!(
+        ticket_vault.key(),
+        get_associated_token_address_with_program_id(
+            &ticket.key(),
+            &payment_mint.key(),
+            &payment_token_program.key(),
+        ),
+        ErrorCode::TicketVaultMissing
+    );
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"ticket",
+        settled.agreement.as_ref(),
+        &[settled.bump],
+    ]];
+    let amount = ticket_vault.amount;
+    if amount > 0 {
+        let transfer_accounts = TransferChecked {
+            authority: ticket.to_account_info(),
+            from: ticket_vault.to_account_info(),
+            to: landlord_payment_ata,
+            mint: payment_mint.to_account_info(),
+        };
+        let transfer_cpi = CpiContext::new_with_signer(
+            payment_token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        transfer_checked(transfer_cpi, amount, payment_mint.decimals)?;
+    }
+    let close_accounts = CloseAccount {
+        account: ticket_vault.to_account_info(),
+        authority: ticket.to_account_info(),
+        destination: renter.clone(),
+    };
+    let close_acc_cpi = CpiContext::new_with_signer(
+        payment_token_program.to_account_info(),
+        close_accounts,
+        signer_seeds,
+    );
+    close_account(close_acc_cpi)?;
+
+    // the ticket was only checked by its seeds, so it is closed by hand
+    let lamports = renter
+        .lamports()
+        .checked_add(ticket.lamports())
+        .ok_or(ErrorCode::Overflow)?;
+    **renter.try_borrow_mut_lamports()? = lamports;
+    **ticket.try_borrow_mut_lamports()? = 0;
+    ticket.assign(&System::id());
+    ticket.resize(0)?;
+    msg!("Settled open maintenance ticket {} to the landlord", ticket.key());
+
+    Ok(Some(TicketSettled {
+        agreement: settled.agreement,
+        ticket: ticket.key(),
+        amount,
+    }))
+}
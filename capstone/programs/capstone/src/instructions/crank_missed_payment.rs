@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    draw_from_deposit,
+    error::ErrorCode,
+    events::{DepositDrawdown, RentPaid, ScoreChanged},
+    Agreement, AgreementStatus, Renter, ScoringConfig,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CrankMissedPayment<'info> {
+    // anyone can crank a missed payment, they only pay for the landlord ATA if it is missing
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub landlord: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.deposit_staked() @ ErrorCode::DepositStaked,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        seeds=[b"renter",agreement.renter.as_ref()],
+        bump=renter.bump
+    )]
+    pub renter: Account<'info, Renter>,
+
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
+    )]
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CrankMissedPayment<'info> {
+    pub fn crank_missed_payment(
+        &mut self,
+        co_renter_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<(RentPaid, DepositDrawdown, Vec<ScoreChanged>)> {
+        let now = Clock::get()?.unix_timestamp;
+        let period = self.agreement.current_period()?;
+        let late_after = self
+            .agreement
+            .schedule
+            .late_after(self.agreement.start_date, period)?;
+        require!(now > late_after, ErrorCode::PaymentNotMissed);
+        self.agreement.accrue_late_fees(now)?;
+
+        let balance = self.agreement.period_balance()?;
+        let paid = self
+            .agreement
+            .period_paid
+            .checked_add(balance)
+            .ok_or(ErrorCode::Overflow)?;
+        let drawdown = draw_from_deposit(
+            &mut self.agreement,
+            &self.deposit_vault,
+            &self.payment_mint,
+            &self.payment_token_program,
+            &[(self.landlord_payment_ata.to_account_info(), balance)],
+        )?;
+        let due_date = self
+            .agreement
+            .schedule
+            .due_date(self.agreement.start_date, period)?;
+        let score_changed = self.record_missed_payment(now - due_date, co_renter_accounts)?;
+        msg!("Charged missed rent for period {} from deposit", period);
+
+        let rent_paid = RentPaid {
+            agreement: self.agreement.key(),
+            renter: self.agreement.renter,
+            period,
+            amount: paid,
+            late_fee: self.agreement.late_fee()?,
+            late: true,
+            from_deposit: true,
+        };
+        Ok((rent_paid, drawdown, score_changed))
+    }
+
+    /// Scores everyone whose share was still missing: the lead renter through `renter`, and
+    /// co-renters through their `Renter` accounts passed in tenant order as remaining accounts.
+    pub fn record_missed_payment(
+        &mut self,
+        seconds_late: i64,
+        co_renter_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Vec<ScoreChanged>> {
+        let model = self.scoring_config.model;
+        let mut score_changes = Vec::new();
+        let lead_missed = !self
+            .agreement
+            .tenants
+            .first()
+            .is_some_and(|lead| lead.share_settled);
+        if lead_missed {
+            score_changes.push(self.renter.record_payment(
+                self.agreement.renter,
+                &model,
+                Some(seconds_late),
+            )?);
+        }
+
+        let mut co_renter_accounts = co_renter_accounts.iter();
+        for tenant in self.agreement.tenants.iter().skip(1) {
+            if tenant.share_settled {
+                continue;
+            }
+            let info = co_renter_accounts
+                .next()
+                .ok_or(ErrorCode::TenantAccountMismatch)?;
+            let mut renter = Renter::load_tenant(info, &tenant.wallet)?;
+            score_changes.push(renter.record_payment(tenant.wallet, &model, Some(seconds_late))?);
+            renter.exit(&crate::ID)?;
+        }
+
+        self.agreement.settle_period()?;
+        Ok(score_changes)
+    }
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::ErrorCode, events::RepairFunded, Agreement, MaintenanceTicket, TicketStatus,
+    MIN_REPAIR_BUDGET_PERCENT,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FundRepair<'info> {
+    #[account(mut)]
+    pub landlord: Signer<'info>,
+
+    // the minimum budget is a share of this agreement's rent
+    #[account(
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=agreement,
+        has_one=payment_mint,
+        constraint=ticket.status==TicketStatus::Open @ ErrorCode::InvalidTicketStatus,
+        seeds=[b"ticket",ticket.agreement.as_ref()],
+        bump=ticket.bump
+    )]
+    pub ticket: Account<'info, MaintenanceTicket>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=ticket,
+        associated_token::token_program=payment_token_program
+    )]
+    pub ticket_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub payment_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> FundRepair<'info> {
+    pub fn fund_repair(&mut self, contractor: Pubkey, budget: u64) -> Result<RepairFunded> {
+        require!(budget > 0, ErrorCode::InvalidPaymentAmount);
+        // funding stops the repair SLA clock, so it has to be a real budget
+        let min_budget = self
+            .agreement
+            .rent_amount
+            .checked_mul(MIN_REPAIR_BUDGET_PERCENT)
+            .ok_or(ErrorCode::Overflow)?
+            / 100;
+        require!(budget >= min_budget, ErrorCode::RepairBudgetTooLow);
+        let transfer_accounts = TransferChecked {
+            authority: self.landlord.to_account_info(),
+            from: self.landlord_payment_ata.to_account_info(),
+            to: self.ticket_vault.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+        };
+        let transfer_cpi = CpiContext::new(
+            self.payment_token_program.to_account_info(),
+            transfer_accounts,
+        );
+        transfer_checked(transfer_cpi, budget, self.payment_mint.decimals)?;
+
+        self.ticket.status = TicketStatus::Funded;
+        self.ticket.contractor = Some(contractor);
+        self.ticket.budget = budget;
+        msg!(
+            "Funded repair with {} for contractor {}",
+            budget,
+            contractor
+        );
+
+        Ok(RepairFunded {
+            agreement: self.ticket.agreement,
+            ticket: self.ticket.key(),
+            contractor,
+            budget,
+        })
+    }
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, Config, BPF_LOADER_UPGRADEABLE};
+
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer=admin,
+        space=8+Config::INIT_SPACE,
+        seeds=[b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // the arbiter and the claim and repair windows are set by whoever deployed the program
+    #[account(
+        seeds=[crate::ID.as_ref()],
+        bump,
+        seeds::program=BPF_LOADER_UPGRADEABLE,
+        constraint=program_data.upgrade_authority_address==Some(admin.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitConfig<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_config(
+        &mut self,
+        bumps: &InitConfigBumps,
+        arbiter: Pubkey,
+        claim_window: i64,
+        response_window: i64,
+        arbitration_window: i64,
+        repair_sla: i64,
+        rent_withhold_percent: u8,
+    ) -> Result<()> {
+        require!(
+            claim_window > 0 && response_window > 0 && arbitration_window > 0,
+            ErrorCode::InvalidConfig
+        );
+        require!(
+            repair_sla > 0 && rent_withhold_percent <= 100,
+            ErrorCode::InvalidConfig
+        );
+        self.config.set_inner(Config {
+            admin: self.admin.key(),
+            arbiter,
+            claim_window,
+            response_window,
+            arbitration_window,
+            repair_sla,
+            rent_withhold_percent,
+            bump: bumps.config,
+        });
+        Ok(())
+    }
+}
@@ -9,11 +9,22 @@ use anchor_spl::{
     token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
 };
 
+use crate::Landlord;
+
 #[derive(Accounts)]
 pub struct InitLandlord<'info> {
     #[account(mut)]
     pub landlord: Signer<'info>,
 
+    #[account(
+        init,
+        payer=landlord,
+        space=8+Landlord::INIT_SPACE,
+        seeds=[b"landlord",landlord.key().as_ref()],
+        bump
+    )]
+    pub landlord_account: Account<'info, Landlord>,
+
     #[account(
         init,
         payer=landlord,
@@ -60,6 +71,12 @@ pub struct InitLandlord<'info> {
 }
 
 impl<'info> InitLandlord<'info> {
+    pub fn init_landlord_account(&mut self, bumps: &InitLandlordBumps) -> Result<()> {
+        self.landlord_account
+            .set_inner(Landlord::new(bumps.landlord_account));
+        Ok(())
+    }
+
     pub fn mint_master_edition_nft(
         &mut self,
         name: String,
@@ -90,9 +107,9 @@ impl<'info> InitLandlord<'info> {
             system_program: self.system_program.to_account_info(),
         };
         let data_v2 = DataV2 {
-            name: name,
-            symbol: symbol,
-            uri: uri,
+            name,
+            symbol,
+            uri,
             seller_fee_basis_points: 0,
             creators: Some(vec![Creator {
                 address: self.landlord.key(),
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::Renter;
+use crate::{Renter, ScoringConfig};
 
 #[derive(Accounts)]
 pub struct InitRenter<'info> {
@@ -16,15 +16,24 @@ pub struct InitRenter<'info> {
     )]
     pub renter: Account<'info, Renter>,
 
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> InitRenter<'info> {
     pub fn init_renter(&mut self, bumps: &InitRenterBumps) -> Result<()> {
         self.renter.set_inner(Renter {
-            score: 20,
+            score: self.scoring_config.model.start_score,
             total_payments: 0,
             late_payments: 0,
+            completed_leases: 0,
+            rating_total: 0,
+            rating_count: 0,
             bump: bumps.renter,
         });
         Ok(())
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, ScoringConfig, ScoringModel, BPF_LOADER_UPGRADEABLE};
+
+#[derive(Accounts)]
+pub struct InitScoringConfig<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer=admin,
+        space=8+ScoringConfig::INIT_SPACE,
+        seeds=[b"scoring_config"],
+        bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    // only whoever deployed the program can set the model every renter is scored by
+    #[account(
+        seeds=[crate::ID.as_ref()],
+        bump,
+        seeds::program=BPF_LOADER_UPGRADEABLE,
+        constraint=program_data.upgrade_authority_address==Some(admin.key()) @ ErrorCode::NotUpgradeAuthority
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitScoringConfig<'info> {
+    pub fn init_scoring_config(
+        &mut self,
+        bumps: &InitScoringConfigBumps,
+        model: ScoringModel,
+    ) -> Result<()> {
+        model.validate()?;
+        self.scoring_config.set_inner(ScoringConfig {
+            admin: self.admin.key(),
+            model,
+            bump: bumps.scoring_config,
+        });
+        Ok(())
+    }
+}
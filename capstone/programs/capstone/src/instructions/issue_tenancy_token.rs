@@ -0,0 +1,421 @@
+use anchor_lang::{
+    prelude::*,
+    system_program::{create_account, transfer, CreateAccount, Transfer},
+};
+use anchor_spl::{
+    associated_token::{create_idempotent, AssociatedToken, Create},
+    token_2022::{
+        burn, initialize_mint2, mint_to,
+        spl_token_2022::{
+            extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+            state::Mint as Token2022Mint,
+        },
+        Burn, InitializeMint2, MintTo, Token2022,
+    },
+    token_2022_extensions::{
+        metadata_pointer_initialize, non_transferable_mint_initialize,
+        permanent_delegate_initialize,
+        spl_pod::optional_keys::OptionalNonZeroPubkey,
+        spl_token_metadata_interface::state::{Field, TokenMetadata},
+        token_metadata_initialize, token_metadata_update_field, MetadataPointerInitialize,
+        NonTransferableMintInitialize, PermanentDelegateInitialize, TokenMetadataInitialize,
+        TokenMetadataUpdateField,
+    },
+    token_interface::Mint,
+};
+
+#[cfg(not(feature = "token-2022-nft"))]
+use anchor_spl::metadata::{Metadata, MetadataAccount};
+
+use crate::{error::ErrorCode, events::TenancyTokenIssued, Agreement, AgreementStatus};
+
+/// Metadata keys of the lease terms a renewal can change, kept current on the tenancy token.
+pub const END_DATE_FIELD: &str = "end_date";
+pub const RENT_AMOUNT_FIELD: &str = "rent_amount";
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct IssueTenancyToken<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=renter,
+        has_one=edition_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.tenancy_token @ ErrorCode::TenancyTokenIssued,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    pub edition_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: The property NFT's metadata, read by `read_edition_metadata`; its name and uri describe the unit on the tenancy token too
+    #[account(address=edition_metadata_address(&edition_mint.key()))]
+    pub edition_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Token-2022 mint, created on first issue with the NonTransferable, PermanentDelegate and MetadataPointer extensions
+    #[account(
+        mut,
+        seeds=[b"tenancy",agreement.key().as_ref()],
+        bump
+    )]
+    pub tenancy_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Renter's Token-2022 associated token account, created by the associated token program once the mint exists
+    #[account(mut)]
+    pub renter_tenancy_ata: UncheckedAccount<'info>,
+
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> IssueTenancyToken<'info> {
+    pub fn issue_tenancy_token(
+        &mut self,
+        bumps: &IssueTenancyTokenBumps,
+    ) -> Result<TenancyTokenIssued> {
+        // the mint outlives a transfer, the next renter gets a fresh token on the current terms
+        if self.tenancy_mint.data_is_empty() {
+            self.create_tenancy_mint(bumps.tenancy_mint)?;
+        } else {
+            update_tenancy_terms(
+                &self.agreement,
+                &self.tenancy_mint.to_account_info(),
+                &self.renter.to_account_info(),
+                &self.token_2022_program.to_account_info(),
+                &self.system_program.to_account_info(),
+            )?;
+        }
+
+        let create_ata_accounts = Create {
+            payer: self.renter.to_account_info(),
+            associated_token: self.renter_tenancy_ata.to_account_info(),
+            authority: self.renter.to_account_info(),
+            mint: self.tenancy_mint.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            token_program: self.token_2022_program.to_account_info(),
+        };
+        create_idempotent(CpiContext::new(
+            self.associated_token_program.to_account_info(),
+            create_ata_accounts,
+        ))?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"agreement",
+            self.agreement.edition_mint.as_ref(),
+            &[self.agreement.bump],
+        ]];
+        let mint_accounts = MintTo {
+            mint: self.tenancy_mint.to_account_info(),
+            to: self.renter_tenancy_ata.to_account_info(),
+            authority: self.agreement.to_account_info(),
+        };
+        let mint_cpi = CpiContext::new_with_signer(
+            self.token_2022_program.to_account_info(),
+            mint_accounts,
+            signer_seeds,
+        );
+        mint_to(mint_cpi, 1)?;
+        self.agreement.tenancy_token = true;
+        msg!("Issued tenancy token to {}", self.renter.key());
+
+        Ok(TenancyTokenIssued {
+            agreement: self.agreement.key(),
+            renter: self.renter.key(),
+            tenancy_mint: self.tenancy_mint.key(),
+        })
+    }
+
+    pub fn create_tenancy_mint(&mut self, mint_bump: u8) -> Result<()> {
+        let agreement_key = self.agreement.key();
+        let mint_key = self.tenancy_mint.key();
+        let (name, symbol, uri) = read_edition_metadata(&self.edition_metadata)?;
+        let mut fields = vec![
+            ("agreement".to_string(), agreement_key.to_string()),
+            (
+                "start_date".to_string(),
+                self.agreement.start_date.to_string(),
+            ),
+            (
+                "payment_mint".to_string(),
+                self.agreement.payment_mint.to_string(),
+            ),
+        ];
+        fields.extend(tenancy_terms(&self.agreement));
+
+        // the metadata lives in the mint and grows it, so fund the final size up front
+        let token_metadata = TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::try_from(Some(agreement_key))?,
+            mint: mint_key,
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            additional_metadata: fields.clone(),
+        };
+        let mint_len = ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+            ExtensionType::NonTransferable,
+            ExtensionType::PermanentDelegate,
+            ExtensionType::MetadataPointer,
+        ])?;
+        let lamports = Rent::get()?.minimum_balance(mint_len + token_metadata.tlv_size_of()?);
+
+        let mint_seeds: &[&[&[u8]]] = &[&[b"tenancy", agreement_key.as_ref(), &[mint_bump]]];
+        let create_accounts = CreateAccount {
+            from: self.renter.to_account_info(),
+            to: self.tenancy_mint.to_account_info(),
+        };
+        let create_cpi = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            create_accounts,
+            mint_seeds,
+        );
+        create_account(
+            create_cpi,
+            lamports,
+            mint_len as u64,
+            self.token_2022_program.key,
+        )?;
+
+        let token_program = self.token_2022_program.to_account_info();
+        let mint = self.tenancy_mint.to_account_info();
+        non_transferable_mint_initialize(CpiContext::new(
+            token_program.clone(),
+            NonTransferableMintInitialize {
+                token_program_id: token_program.clone(),
+                mint: mint.clone(),
+            },
+        ))?;
+        // the agreement can burn the token from the renter's wallet when the tenancy ends
+        permanent_delegate_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                PermanentDelegateInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            &agreement_key,
+        )?;
+        metadata_pointer_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                MetadataPointerInitialize {
+                    token_program_id: token_program.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            Some(agreement_key),
+            Some(mint_key),
+        )?;
+        initialize_mint2(
+            CpiContext::new(
+                token_program.clone(),
+                InitializeMint2 { mint: mint.clone() },
+            ),
+            0,
+            &agreement_key,
+            Some(&agreement_key),
+        )?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"agreement",
+            self.agreement.edition_mint.as_ref(),
+            &[self.agreement.bump],
+        ]];
+        token_metadata_initialize(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataInitialize {
+                    program_id: token_program.clone(),
+                    metadata: mint.clone(),
+                    update_authority: self.agreement.to_account_info(),
+                    mint_authority: self.agreement.to_account_info(),
+                    mint: mint.clone(),
+                },
+                signer_seeds,
+            ),
+            name,
+            symbol,
+            uri,
+        )?;
+        for (key, value) in fields {
+            token_metadata_update_field(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    TokenMetadataUpdateField {
+                        program_id: token_program.clone(),
+                        metadata: mint.clone(),
+                        update_authority: self.agreement.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                Field::Key(key),
+                value,
+            )?;
+        }
+        msg!("Created tenancy token mint");
+        Ok(())
+    }
+}
+
+pub fn tenancy_terms(agreement: &Agreement) -> Vec<(String, String)> {
+    vec![
+        (END_DATE_FIELD.to_string(), agreement.end_date.to_string()),
+        (
+            RENT_AMOUNT_FIELD.to_string(),
+            agreement.rent_amount.to_string(),
+        ),
+    ]
+}
+
+/// Rewrites the term and rent on an existing tenancy mint, with `payer` covering any growth.
+pub fn update_tenancy_terms<'info>(
+    agreement: &Account<'info, Agreement>,
+    tenancy_mint: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    token_2022_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let fields = tenancy_terms(agreement);
+    let mut token_metadata = {
+        let data = tenancy_mint.try_borrow_data()?;
+        StateWithExtensions::<Token2022Mint>::unpack(&data)?
+            .get_variable_len_extension::<TokenMetadata>()?
+    };
+    let current_size = token_metadata.tlv_size_of()?;
+    for (key, value) in fields.iter().cloned() {
+        token_metadata.update(Field::Key(key), value);
+    }
+    let len = tenancy_mint.data_len() - current_size + token_metadata.tlv_size_of()?;
+    fund_mint(tenancy_mint, payer, system_program, len)?;
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"agreement",
+        agreement.edition_mint.as_ref(),
+        &[agreement.bump],
+    ]];
+    for (key, value) in fields {
+        token_metadata_update_field(
+            CpiContext::new_with_signer(
+                token_2022_program.clone(),
+                TokenMetadataUpdateField {
+                    program_id: token_2022_program.clone(),
+                    metadata: tenancy_mint.clone(),
+                    update_authority: agreement.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            Field::Key(key),
+            value,
+        )?;
+    }
+    msg!("Updated tenancy token terms");
+    Ok(())
+}
+
+/// Tops `mint` up to rent exemption at `len` bytes. The token program reallocates a mint when
+/// metadata or group data is written into it, but leaves paying for the space to the caller.
+pub fn fund_mint<'info>(
+    mint: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    len: usize,
+) -> Result<()> {
+    let missing = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(mint.lamports());
+    if missing > 0 {
+        let transfer_accounts = Transfer {
+            from: payer.clone(),
+            to: mint.clone(),
+        };
+        transfer(
+            CpiContext::new(system_program.clone(), transfer_accounts),
+            missing,
+        )?;
+    }
+    Ok(())
+}
+
+/// Burns the renter's tenancy token if one was issued. The agreement is the mint's permanent
+/// delegate, so the renter does not have to sign when their tenancy ends.
+pub fn burn_tenancy_token<'info>(
+    agreement: &mut Account<'info, Agreement>,
+    tenancy_mint: &Option<UncheckedAccount<'info>>,
+    renter_tenancy_ata: &Option<UncheckedAccount<'info>>,
+    token_2022_program: &Option<Program<'info, Token2022>>,
+) -> Result<()> {
+    if !agreement.tenancy_token {
+        return Ok(());
+    }
+    let (Some(tenancy_mint), Some(renter_tenancy_ata), Some(token_2022_program)) =
+        (tenancy_mint, renter_tenancy_ata, token_2022_program)
+    else {
+        return err!(ErrorCode::TenancyTokenAccountsMissing);
+    };
+
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"agreement",
+        agreement.edition_mint.as_ref(),
+        &[agreement.bump],
+    ]];
+    let burn_accounts = Burn {
+        mint: tenancy_mint.to_account_info(),
+        from: renter_tenancy_ata.to_account_info(),
+        authority: agreement.to_account_info(),
+    };
+    let burn_cpi = CpiContext::new_with_signer(
+        token_2022_program.to_account_info(),
+        burn_accounts,
+        signer_seeds,
+    );
+    burn(burn_cpi, 1)?;
+    agreement.tenancy_token = false;
+    msg!("Burned tenancy token");
+    Ok(())
+}
+
+/// The Metaplex metadata PDA of the edition NFT.
+#[cfg(not(feature = "token-2022-nft"))]
+pub fn edition_metadata_address(edition_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", Metadata::id().as_ref(), edition_mint.as_ref()],
+        &Metadata::id(),
+    )
+    .0
+}
+
+/// Token-2022 edition NFTs keep their metadata in the mint itself.
+#[cfg(feature = "token-2022-nft")]
+pub fn edition_metadata_address(edition_mint: &Pubkey) -> Pubkey {
+    *edition_mint
+}
+
+/// Returns the edition NFT's name, symbol and uri.
+#[cfg(not(feature = "token-2022-nft"))]
+pub fn read_edition_metadata(account: &AccountInfo) -> Result<(String, String, String)> {
+    require_keys_eq!(
+        *account.owner,
+        Metadata::id(),
+        anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+    );
+    let metadata = MetadataAccount::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    Ok((
+        metadata.name.trim_end_matches('\0').to_string(),
+        metadata.symbol.trim_end_matches('\0').to_string(),
+        metadata.uri.trim_end_matches('\0').to_string(),
+    ))
+}
+
+/// Returns the edition NFT's name, symbol and uri.
+#[cfg(feature = "token-2022-nft")]
+pub fn read_edition_metadata(account: &AccountInfo) -> Result<(String, String, String)> {
+    let data = account.try_borrow_data()?;
+    let metadata = StateWithExtensions::<Token2022Mint>::unpack(&data)?
+        .get_variable_len_extension::<TokenMetadata>()?;
+    Ok((metadata.name, metadata.symbol, metadata.uri))
+}
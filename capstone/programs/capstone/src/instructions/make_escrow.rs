@@ -9,9 +9,14 @@ use anchor_spl::{
     token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
 };
 
-use crate::Escrow;
+use crate::{
+    events::ListingCreated, Escrow, Landlord, PaymentSchedule, Property, PropertyDetails,
+    ScoringConfig,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
+#[instruction(property_id: u64)]
 pub struct MakeEscrow<'info> {
     #[account(mut)]
     pub landlord: Signer<'info>,
@@ -25,6 +30,22 @@ pub struct MakeEscrow<'info> {
     )]
     pub escrow: Account<'info, Escrow>,
 
+    #[account(
+        mut,
+        seeds=[b"landlord",landlord.key().as_ref()],
+        bump=landlord_account.bump
+    )]
+    pub landlord_account: Account<'info, Landlord>,
+
+    #[account(
+        init,
+        payer=landlord,
+        space=8+Property::INIT_SPACE,
+        seeds=[b"property",edition_mint.key().as_ref()],
+        bump
+    )]
+    pub property: Account<'info, Property>,
+
     #[account(
         init,
         payer=landlord,
@@ -32,7 +53,7 @@ pub struct MakeEscrow<'info> {
         mint::authority=landlord,
         mint::freeze_authority=landlord,
         mint::token_program=token_program,
-        seeds=[b"edition",collection_mint.key().as_ref()],
+        seeds=[b"edition",collection_mint.key().as_ref(),property_id.to_le_bytes().as_ref()],
         bump
     )]
     pub edition_mint: InterfaceAccount<'info, Mint>,
@@ -89,10 +110,23 @@ pub struct MakeEscrow<'info> {
     )]
     pub collection_master_edition: UncheckedAccount<'info>,
 
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    // the listing can't ask for a score the scoring model never reaches
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_metadata_program: Program<'info, Metadata>,
     pub token_program: Interface<'info, TokenInterface>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
     pub rent: Sysvar<'info, Rent>,
 }
 
@@ -128,9 +162,9 @@ impl<'info> MakeEscrow<'info> {
             system_program: self.system_program.to_account_info(),
         };
         let data_v2 = DataV2 {
-            name: name,
-            symbol: symbol,
-            uri: uri,
+            name,
+            symbol,
+            uri,
             seller_fee_basis_points: 0,
             creators: None,
             collection: None,
@@ -181,29 +215,50 @@ impl<'info> MakeEscrow<'info> {
         Ok(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn init_escrow(
         &mut self,
         bumps: &MakeEscrowBumps,
+        property_id: u64,
         monthly_rent: u64,
         deposit_amount: u64,
         late_fee_percent: u8,
         min_renter_score: u16,
+        max_late_payment_percent: Option<u8>,
         cancel_allowed_after: u16,
         cancel_penalty_percent: u8,
         months: u8,
-    ) -> Result<()> {
-        self.escrow.set_inner(Escrow {
-            landlord: *self.landlord.key,
+        schedule: PaymentSchedule,
+        yield_renter_share_percent: Option<u8>,
+        details: PropertyDetails,
+    ) -> Result<ListingCreated> {
+        self.escrow.open(
+            self.landlord.key(),
+            property_id,
+            self.payment_mint.key(),
+            bumps.escrow,
+            bumps.edition_mint,
             monthly_rent,
             deposit_amount,
             late_fee_percent,
             min_renter_score,
+            max_late_payment_percent,
             cancel_allowed_after,
             cancel_penalty_percent,
-            bump: bumps.escrow,
-            edition_mint_bump: bumps.edition_mint,
             months,
-        });
-        Ok(())
+            schedule,
+            yield_renter_share_percent,
+            &self.scoring_config.model,
+        )?;
+        self.landlord_account.list_property(
+            &mut self.property,
+            self.landlord.key(),
+            self.edition_mint.key(),
+            details,
+            bumps.property,
+        )?;
+        Ok(self
+            .escrow
+            .listing_created(self.escrow.key(), self.edition_mint.key()))
     }
 }
@@ -4,23 +4,148 @@ pub use initialize::*;
 pub mod init_renter;
 pub use init_renter::*;
 
+pub mod attest_reputation;
+pub use attest_reputation::*;
+
+// the NFT contexts come from the Metaplex files by default, or from token_2022/ behind the feature
+#[cfg(feature = "token-2022-nft")]
+pub mod token_2022;
+
+#[cfg(not(feature = "token-2022-nft"))]
 pub mod init_landlord;
+#[cfg(feature = "token-2022-nft")]
+pub use token_2022::init_landlord;
 pub use init_landlord::*;
 
+#[cfg(not(feature = "token-2022-nft"))]
 pub mod make_escrow;
+#[cfg(feature = "token-2022-nft")]
+pub use token_2022::make_escrow;
 pub use make_escrow::*;
 
+#[cfg(not(feature = "token-2022-nft"))]
+pub mod update_escrow;
+#[cfg(feature = "token-2022-nft")]
+pub use token_2022::update_escrow;
+pub use update_escrow::*;
+
+#[cfg(not(feature = "token-2022-nft"))]
 pub mod refund_escrow;
+#[cfg(feature = "token-2022-nft")]
+pub use token_2022::refund_escrow;
 pub use refund_escrow::*;
 
+/// Expands to the `metaplex` or the `token_2022` expression, whichever NFT backend this crate was
+/// built with. Clients filling in the listing contexts use it to follow the program's feature
+/// rather than declaring their own.
+#[cfg(not(feature = "token-2022-nft"))]
+#[macro_export]
+macro_rules! nft_backend {
+    (metaplex => $metaplex:expr, token_2022 => $token_2022:expr $(,)?) => {
+        $metaplex
+    };
+}
+
+#[cfg(feature = "token-2022-nft")]
+#[macro_export]
+macro_rules! nft_backend {
+    (metaplex => $metaplex:expr, token_2022 => $token_2022:expr $(,)?) => {
+        $token_2022
+    };
+}
+
 pub mod take_escrow;
 pub use take_escrow::*;
 
+pub mod issue_tenancy_token;
+pub use issue_tenancy_token::*;
+
 pub mod renter_monthly_payment;
 pub use renter_monthly_payment::*;
 
+pub mod pay_partial;
+pub use pay_partial::*;
+
+pub mod add_co_renter;
+pub use add_co_renter::*;
+
+pub mod pay_share;
+pub use pay_share::*;
+
+pub mod set_autopay;
+pub use set_autopay::*;
+
+pub mod collect_rent;
+pub use collect_rent::*;
+
 pub mod pay_from_deposit;
 pub use pay_from_deposit::*;
 
+pub mod top_up_deposit;
+pub use top_up_deposit::*;
+
 pub mod close_agreement;
 pub use close_agreement::*;
+
+pub mod crank_missed_payment;
+pub use crank_missed_payment::*;
+
+pub mod init_config;
+pub use init_config::*;
+
+pub mod update_config;
+pub use update_config::*;
+
+pub mod init_scoring_config;
+pub use init_scoring_config::*;
+
+pub mod update_scoring_config;
+pub use update_scoring_config::*;
+
+pub mod propose_deduction;
+pub use propose_deduction::*;
+
+pub mod respond_to_claim;
+pub use respond_to_claim::*;
+
+pub mod resolve_dispute;
+pub use resolve_dispute::*;
+
+pub mod settle_deposit;
+pub use settle_deposit::*;
+
+pub mod rate_counterparty;
+pub use rate_counterparty::*;
+
+pub mod open_ticket;
+pub use open_ticket::*;
+
+pub mod fund_repair;
+pub use fund_repair::*;
+
+pub mod confirm_repair;
+pub use confirm_repair::*;
+
+pub mod propose_renewal;
+pub use propose_renewal::*;
+
+pub mod accept_renewal;
+pub use accept_renewal::*;
+
+pub mod nominate_successor;
+pub use nominate_successor::*;
+
+pub mod transfer_agreement;
+pub use transfer_agreement::*;
+
+pub mod terminate_agreement;
+pub use terminate_agreement::*;
+
+pub mod stake_deposit;
+pub use stake_deposit::*;
+
+pub mod unstake_deposit;
+pub use unstake_deposit::*;
+
+pub mod withdraw_stake;
+pub use withdraw_stake::*;
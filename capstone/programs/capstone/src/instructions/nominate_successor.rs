@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::SuccessorNominated, Agreement, AgreementStatus};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct NominateSuccessor<'info> {
+    pub renter: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=renter,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.is_shared() @ ErrorCode::SharedAgreement,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+}
+
+impl<'info> NominateSuccessor<'info> {
+    pub fn nominate_successor(&mut self, successor: Option<Pubkey>) -> Result<SuccessorNominated> {
+        if let Some(successor) = successor {
+            require!(
+                successor != self.renter.key() && successor != self.agreement.landlord,
+                ErrorCode::InvalidSuccessor
+            );
+        }
+        self.agreement.successor = successor;
+        msg!("Successor nominated: {:?}", successor);
+
+        Ok(SuccessorNominated {
+            agreement: self.agreement.key(),
+            renter: self.renter.key(),
+            successor,
+        })
+    }
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    error::ErrorCode, events::TicketOpened, Agreement, AgreementStatus, MaintenanceTicket,
+    TicketStatus, Urgency,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OpenTicket<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+
+    #[account(
+        has_one=renter,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    // one open ticket per agreement, confirming the repair closes it
+    #[account(
+        init,
+        payer=renter,
+        space=8+MaintenanceTicket::INIT_SPACE,
+        seeds=[b"ticket",agreement.key().as_ref()],
+        bump
+    )]
+    pub ticket: Account<'info, MaintenanceTicket>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    // holds the repair budget and any rent withheld while the ticket is overdue
+    #[account(
+        init,
+        payer=renter,
+        associated_token::mint=payment_mint,
+        associated_token::authority=ticket,
+        associated_token::token_program=payment_token_program
+    )]
+    pub ticket_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenTicket<'info> {
+    pub fn open_ticket(
+        &mut self,
+        bumps: &OpenTicketBumps,
+        description_hash: [u8; 32],
+        urgency: Urgency,
+    ) -> Result<TicketOpened> {
+        let now = Clock::get()?.unix_timestamp;
+        self.ticket.set_inner(MaintenanceTicket {
+            agreement: self.agreement.key(),
+            landlord: self.agreement.landlord,
+            renter: self.renter.key(),
+            payment_mint: self.agreement.payment_mint,
+            description_hash,
+            urgency,
+            status: TicketStatus::Open,
+            opened_at: now,
+            contractor: None,
+            budget: 0,
+            withheld: 0,
+            bump: bumps.ticket,
+        });
+        msg!("Opened maintenance ticket {}", self.ticket.key());
+
+        Ok(TicketOpened {
+            agreement: self.agreement.key(),
+            ticket: self.ticket.key(),
+            renter: self.renter.key(),
+            description_hash,
+            urgency,
+        })
+    }
+}
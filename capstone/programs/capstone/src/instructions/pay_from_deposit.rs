@@ -1,85 +1,256 @@
-use anchor_lang::{
-    prelude::*,
-    system_program::{transfer, Transfer},
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
 };
 
-use crate::{error::ErrorCode, Agreement, Renter};
+use crate::{
+    error::ErrorCode,
+    events::{DepositDrawdown, RentPaid, ScoreChanged},
+    Agreement, AgreementStatus, Renter, ScoringConfig,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct PayFromDeposit<'info> {
+    // the landlord settles a missed period from the deposit without waiting for a crank
     #[account(mut)]
-    pub signer: Signer<'info>,
+    pub landlord: Signer<'info>,
 
     #[account(
         mut,
-        seeds=[b"deposit",agreement.key().as_ref()],
-        bump=agreement.deposit_bump
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
     )]
-    pub deposit_vault: SystemAccount<'info>,
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.is_shared() @ ErrorCode::SharedAgreement,
+        constraint=!agreement.deposit_staked() @ ErrorCode::DepositStaked,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
     pub agreement: Account<'info, Agreement>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        seeds=[b"renter",agreement.renter.as_ref()],
+        bump=renter.bump
+    )]
     pub renter: Account<'info, Renter>,
 
-    #[account(mut)]
-    pub landlord: SystemAccount<'info>,
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer=landlord,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
 
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> PayFromDeposit<'info> {
-    pub fn pay_from_deposit(&mut self) -> Result<()> {
-        let agreement_key = self.agreement.key();
-        let signer_seeds: &[&[&[u8]]] = &[&[
-            b"deposit",
-            agreement_key.as_ref(),
-            &[self.agreement.deposit_bump],
-        ]];
-        let transfer_accounts = Transfer {
-            from: self.deposit_vault.to_account_info(),
-            to: self.landlord.to_account_info(),
-        };
-        let transfer_cpi = CpiContext::new_with_signer(
-            self.system_program.to_account_info(),
-            transfer_accounts,
-            signer_seeds,
+    pub fn pay_from_deposit(&mut self) -> Result<(RentPaid, DepositDrawdown, ScoreChanged)> {
+        let now = Clock::get()?.unix_timestamp;
+        let period = self.agreement.current_period()?;
+        let schedule = self.agreement.schedule;
+        let start_date = self.agreement.start_date;
+        require!(
+            now > schedule.late_after(start_date, period)?,
+            ErrorCode::PaymentNotMissed
         );
-        let late_rent_fine_fee = self
-            .agreement
-            .rent_amount
-            .checked_mul(u64::from(self.agreement.late_fee_percent))
-            .and_then(|v| v.checked_div(100))
-            .ok_or(ErrorCode::Overflow)?;
-        let total_rent = self
-            .agreement
-            .rent_amount
-            .checked_add(late_rent_fine_fee)
-            .ok_or(ErrorCode::Overflow)?;
-        transfer(transfer_cpi, total_rent)?;
+        self.agreement.accrue_late_fees(now)?;
 
-        self.agreement.payments_made = self
+        let balance = self.agreement.period_balance()?;
+        let drawdown = draw_from_deposit(
+            &mut self.agreement,
+            &self.deposit_vault,
+            &self.payment_mint,
+            &self.payment_token_program,
+            &[(self.landlord_payment_ata.to_account_info(), balance)],
+        )?;
+        let late_fee = self.agreement.late_fee()?;
+        let paid = self
             .agreement
-            .payments_made
-            .checked_add(1)
+            .period_paid
+            .checked_add(balance)
             .ok_or(ErrorCode::Overflow)?;
+        self.agreement.settle_period()?;
+        let score_changed = self.renter.record_payment(
+            self.agreement.renter,
+            &self.scoring_config.model,
+            Some(now - schedule.due_date(start_date, period)?),
+        )?;
+        msg!("Landlord charged missed rent for period {} from deposit", period);
 
-        self.renter.score = self
-            .renter
-            .score
-            .checked_sub(2)
-            .ok_or(ErrorCode::Overflow)?;
-        self.renter.total_payments = self
-            .renter
-            .total_payments
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
-        self.renter.late_payments = self
-            .renter
-            .late_payments
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
-        Ok(())
+        let rent_paid = RentPaid {
+            agreement: self.agreement.key(),
+            renter: self.agreement.renter,
+            period,
+            amount: paid,
+            late_fee,
+            late: true,
+            from_deposit: true,
+        };
+        Ok((rent_paid, drawdown, score_changed))
+    }
+}
+
+/// Pays `payouts` out of the deposit vault with the agreement signing, and counts the period as
+/// missed. Every path that falls back on the deposit goes through here.
+pub fn draw_from_deposit<'info>(
+    agreement: &mut Account<'info, Agreement>,
+    deposit_vault: &InterfaceAccount<'info, TokenAccount>,
+    payment_mint: &InterfaceAccount<'info, Mint>,
+    payment_token_program: &Interface<'info, TokenInterface>,
+    payouts: &[(AccountInfo<'info>, u64)],
+) -> Result<DepositDrawdown> {
+    require!(!agreement.deposit_staked(), ErrorCode::DepositStaked);
+    let mut total = 0u64;
+    for (_, amount) in payouts {
+        total = total.checked_add(*amount).ok_or(ErrorCode::Overflow)?;
+    }
+    require!(deposit_vault.amount >= total, ErrorCode::DepositFundsLow);
+
+    for (to, amount) in payouts {
+        pay_out(
+            agreement,
+            deposit_vault,
+            payment_mint,
+            payment_token_program,
+            to.clone(),
+            *amount,
+        )?;
+    }
+
+    agreement.missed_payments = agreement
+        .missed_payments
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+    Ok(DepositDrawdown {
+        agreement: agreement.key(),
+        amount: total,
+        remaining: deposit_vault.amount - total,
+    })
+}
+
+/// Transfers `amount` out of the deposit vault to `to`, with the agreement signing.
+pub fn pay_out<'info>(
+    agreement: &Account<'info, Agreement>,
+    deposit_vault: &InterfaceAccount<'info, TokenAccount>,
+    payment_mint: &InterfaceAccount<'info, Mint>,
+    payment_token_program: &Interface<'info, TokenInterface>,
+    to: AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"agreement",
+        agreement.edition_mint.as_ref(),
+        &[agreement.bump],
+    ]];
+    let transfer_accounts = TransferChecked {
+        authority: agreement.to_account_info(),
+        from: deposit_vault.to_account_info(),
+        to,
+        mint: payment_mint.to_account_info(),
+    };
+    let transfer_cpi = CpiContext::new_with_signer(
+        payment_token_program.to_account_info(),
+        transfer_accounts,
+        signer_seeds,
+    );
+    transfer_checked(transfer_cpi, amount, payment_mint.decimals)
+}
+
+/// Pays each co-renter their part of the refund into their token accounts, passed as remaining
+/// accounts in tenant order, and returns what is left for the lead renter.
+pub fn refund_co_renters<'info>(
+    agreement: &Account<'info, Agreement>,
+    deposit_vault: &InterfaceAccount<'info, TokenAccount>,
+    payment_mint: &InterfaceAccount<'info, Mint>,
+    payment_token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+    co_renter_atas: &'info [AccountInfo<'info>],
+) -> Result<u64> {
+    let refunds = agreement.co_renter_refunds(amount)?;
+    require!(
+        co_renter_atas.len() >= refunds.len(),
+        ErrorCode::TenantAccountMismatch
+    );
+    let mut lead_amount = amount;
+    for ((tenant, refund), info) in agreement
+        .tenants
+        .iter()
+        .skip(1)
+        .zip(refunds)
+        .zip(co_renter_atas)
+    {
+        let ata = InterfaceAccount::<TokenAccount>::try_from(info)?;
+        require!(
+            ata.owner == tenant.wallet && ata.mint == payment_mint.key(),
+            ErrorCode::TenantAccountMismatch
+        );
+        if refund > 0 {
+            pay_out(
+                agreement,
+                deposit_vault,
+                payment_mint,
+                payment_token_program,
+                info.clone(),
+                refund,
+            )?;
+        }
+        lead_amount -= refund;
     }
+    Ok(lead_amount)
+}
+
+/// Closes the emptied deposit vault, returning its rent to `destination`.
+pub fn close_deposit_vault<'info>(
+    agreement: &Account<'info, Agreement>,
+    deposit_vault: &InterfaceAccount<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    payment_token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        b"agreement",
+        agreement.edition_mint.as_ref(),
+        &[agreement.bump],
+    ]];
+    let close_accounts = CloseAccount {
+        account: deposit_vault.to_account_info(),
+        authority: agreement.to_account_info(),
+        destination,
+    };
+    let close_acc_cpi = CpiContext::new_with_signer(
+        payment_token_program.to_account_info(),
+        close_accounts,
+        signer_seeds,
+    );
+    close_account(close_acc_cpi)?;
+    msg!("Closed deposit vault!");
+    Ok(())
 }
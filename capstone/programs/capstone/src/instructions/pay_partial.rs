@@ -0,0 +1,181 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::ErrorCode,
+    events::{PartialPaymentApplied, RentPaid, ScoreChanged},
+    Agreement, AgreementStatus, Renter, ScoringConfig,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PayPartial<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub landlord: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.is_shared() @ ErrorCode::SharedAgreement,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        seeds=[b"renter",agreement.renter.as_ref()],
+        bump=renter.bump
+    )]
+    pub renter: Account<'info, Renter>,
+
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=signer,
+        associated_token::token_program=payment_token_program
+    )]
+    pub signer_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PayPartial<'info> {
+    pub fn pay_partial(
+        &mut self,
+        amount: u64,
+    ) -> Result<(PartialPaymentApplied, Vec<(RentPaid, ScoreChanged)>)> {
+        require!(amount > 0, ErrorCode::InvalidPaymentAmount);
+        let now = Clock::get()?.unix_timestamp;
+        let first_period = self.agreement.current_period()?;
+        require!(
+            now >= self
+                .agreement
+                .schedule
+                .period_start(self.agreement.start_date, first_period)?,
+            ErrorCode::RentNotDue
+        );
+        self.agreement.accrue_late_fees(now)?;
+
+        // oldest period first; anything beyond the periods that have started is not taken
+        let mut remaining = amount;
+        let mut settled = Vec::new();
+        while remaining > 0 && self.agreement.payments_made < u16::from(self.agreement.periods) {
+            let period = self.agreement.payments_made;
+            if now
+                < self
+                    .agreement
+                    .schedule
+                    .period_start(self.agreement.start_date, period)?
+            {
+                break;
+            }
+            let balance = self.agreement.period_balance()?;
+            let applied = remaining.min(balance);
+            remaining -= applied;
+            self.agreement.period_paid = self
+                .agreement
+                .period_paid
+                .checked_add(applied)
+                .ok_or(ErrorCode::Overflow)?;
+            if applied == balance {
+                settled.push(self.settle_period(period, now)?);
+            }
+        }
+
+        let paid = amount - remaining;
+        self.transfer_payment(paid)?;
+        msg!("Applied {} to rent, {} periods settled", paid, settled.len());
+
+        let applied = PartialPaymentApplied {
+            agreement: self.agreement.key(),
+            payer: self.signer.key(),
+            amount: paid,
+            periods_settled: settled.len() as u16,
+            period_paid: self.agreement.period_paid,
+            late_fees_owed: self.agreement.late_fees_owed,
+            amount_owed: self.agreement.amount_owed(now)?,
+        };
+        Ok((applied, settled))
+    }
+
+    pub fn transfer_payment(&mut self, amount: u64) -> Result<()> {
+        let payment_accounts = TransferChecked {
+            authority: self.signer.to_account_info(),
+            from: self.signer_payment_ata.to_account_info(),
+            to: self.landlord_payment_ata.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+        };
+        let payment_cpi = CpiContext::new(
+            self.payment_token_program.to_account_info(),
+            payment_accounts,
+        );
+        transfer_checked(payment_cpi, amount, self.payment_mint.decimals)
+    }
+
+    pub fn settle_period(&mut self, period: u16, now: i64) -> Result<(RentPaid, ScoreChanged)> {
+        let late = self.agreement.current_period_late();
+        let late_fee = if late { self.agreement.late_fee()? } else { 0 };
+        // the installments applied so far, this one included
+        let paid = self.agreement.period_paid;
+        self.agreement.settle_period()?;
+
+        let seconds_late = if late {
+            let due_date = self
+                .agreement
+                .schedule
+                .due_date(self.agreement.start_date, period)?;
+            Some(now - due_date)
+        } else {
+            None
+        };
+        let score_changed = self.renter.record_payment(
+            self.agreement.renter,
+            &self.scoring_config.model,
+            seconds_late,
+        )?;
+
+        Ok((
+            RentPaid {
+                agreement: self.agreement.key(),
+                renter: self.agreement.renter,
+                period,
+                amount: paid,
+                late_fee,
+                late,
+                from_deposit: false,
+            },
+            score_changed,
+        ))
+    }
+}
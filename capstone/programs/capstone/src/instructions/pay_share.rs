@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    error::ErrorCode,
+    events::{RentPaid, ScoreChanged, SharePaid},
+    Agreement, AgreementStatus, Renter, ScoringConfig,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct PayShare<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub landlord: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=agreement.tenant_index(&signer.key()).is_some() @ ErrorCode::NotATenant,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    // only the paying renter's score moves
+    #[account(
+        mut,
+        seeds=[b"renter",signer.key().as_ref()],
+        bump=renter.bump
+    )]
+    pub renter: Account<'info, Renter>,
+
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=signer,
+        associated_token::token_program=payment_token_program
+    )]
+    pub signer_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> PayShare<'info> {
+    pub fn pay_share(&mut self) -> Result<(SharePaid, ScoreChanged, Option<RentPaid>)> {
+        let now = Clock::get()?.unix_timestamp;
+        let period = self.agreement.current_period()?;
+        let schedule = self.agreement.schedule;
+        let start_date = self.agreement.start_date;
+        require!(
+            now >= schedule.period_start(start_date, period)?,
+            ErrorCode::RentNotDue
+        );
+
+        let index = self
+            .agreement
+            .tenant_index(&self.signer.key())
+            .ok_or(ErrorCode::NotATenant)?;
+        require!(
+            !self.agreement.tenants[index].share_settled,
+            ErrorCode::ShareAlreadyPaid
+        );
+        let late = now > schedule.late_after(start_date, period)?;
+        self.agreement.accrue_late_fees(now)?;
+        let amount = self.agreement.tenant_balance(index, late)?;
+        self.transfer_share(amount)?;
+
+        let tenant = &mut self.agreement.tenants[index];
+        tenant.share_paid = tenant
+            .share_paid
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        tenant.share_settled = true;
+        self.agreement.period_paid = self
+            .agreement
+            .period_paid
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let seconds_late = late.then_some(now - schedule.due_date(start_date, period)?);
+        let score_changed = self.renter.record_payment(
+            self.signer.key(),
+            &self.scoring_config.model,
+            seconds_late,
+        )?;
+        msg!("Paid share {} of period {}", index, period);
+
+        // the period only counts as paid once every renter's share is in
+        let rent_paid = if self
+            .agreement
+            .tenants
+            .iter()
+            .all(|tenant| tenant.share_settled)
+        {
+            let period_late = self.agreement.current_period_late();
+            let late_fee = if period_late {
+                self.agreement.late_fee()?
+            } else {
+                0
+            };
+            let paid = self.agreement.period_paid;
+            self.agreement.settle_period()?;
+            Some(RentPaid {
+                agreement: self.agreement.key(),
+                renter: self.agreement.renter,
+                period,
+                amount: paid,
+                late_fee,
+                late: period_late,
+                from_deposit: false,
+            })
+        } else {
+            None
+        };
+
+        let share_paid = SharePaid {
+            agreement: self.agreement.key(),
+            renter: self.signer.key(),
+            period,
+            amount,
+            late,
+        };
+        Ok((share_paid, score_changed, rent_paid))
+    }
+
+    pub fn transfer_share(&mut self, amount: u64) -> Result<()> {
+        let payment_accounts = TransferChecked {
+            authority: self.signer.to_account_info(),
+            from: self.signer_payment_ata.to_account_info(),
+            to: self.landlord_payment_ata.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+        };
+        let payment_cpi = CpiContext::new(
+            self.payment_token_program.to_account_info(),
+            payment_accounts,
+        );
+        transfer_checked(payment_cpi, amount, self.payment_mint.decimals)
+    }
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    error::ErrorCode, events::DeductionProposed, Agreement, AgreementStatus, ClaimStatus, Config,
+    DepositClaim,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeDeduction<'info> {
+    #[account(mut)]
+    pub landlord: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::MoveOut @ ErrorCode::NotInMoveOut,
+        constraint=!agreement.deposit_staked() @ ErrorCode::DepositStaked,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        init,
+        payer=landlord,
+        space=8+DepositClaim::INIT_SPACE,
+        seeds=[b"claim",agreement.key().as_ref()],
+        bump
+    )]
+    pub claim: Account<'info, DepositClaim>,
+
+    #[account(
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
+    )]
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds=[b"config"],
+        bump=config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ProposeDeduction<'info> {
+    pub fn propose_deduction(
+        &mut self,
+        bumps: &ProposeDeductionBumps,
+        amount: u64,
+        evidence_uri: String,
+    ) -> Result<DeductionProposed> {
+        let now = Clock::get()?.unix_timestamp;
+        let claim_deadline = self
+            .agreement
+            .move_out_date
+            .checked_add(self.config.claim_window)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(now <= claim_deadline, ErrorCode::ClaimWindowClosed);
+        require!(
+            amount > 0 && amount <= self.deposit_vault.amount,
+            ErrorCode::DeductionTooLarge
+        );
+        require!(evidence_uri.len() <= 200, ErrorCode::EvidenceUriTooLong);
+
+        let respond_by = now
+            .checked_add(self.config.response_window)
+            .ok_or(ErrorCode::Overflow)?;
+        self.claim.set_inner(DepositClaim {
+            agreement: self.agreement.key(),
+            amount,
+            evidence_uri: evidence_uri.clone(),
+            status: ClaimStatus::Proposed,
+            proposed_at: now,
+            respond_by,
+            arbitrate_by: 0,
+            awarded_amount: 0,
+            bump: bumps.claim,
+        });
+        self.agreement.status = AgreementStatus::DepositClaimed;
+
+        Ok(DeductionProposed {
+            agreement: self.agreement.key(),
+            landlord: self.landlord.key(),
+            amount,
+            evidence_uri,
+            respond_by,
+        })
+    }
+}
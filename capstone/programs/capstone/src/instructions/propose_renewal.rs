@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ErrorCode, events::RenewalProposed, Agreement, AgreementStatus, Renewal,
+    MAX_RENT_CHANGE_PERCENT,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeRenewal<'info> {
+    #[account(mut)]
+    pub landlord: Signer<'info>,
+
+    #[account(
+        has_one=landlord,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    // a new proposal replaces one the renter has not accepted yet
+    #[account(
+        init_if_needed,
+        payer=landlord,
+        space=8+Renewal::INIT_SPACE,
+        seeds=[b"renewal",agreement.key().as_ref()],
+        bump
+    )]
+    pub renewal: Account<'info, Renewal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ProposeRenewal<'info> {
+    pub fn propose_renewal(
+        &mut self,
+        bumps: &ProposeRenewalBumps,
+        extra_periods: u8,
+        rent_amount: u64,
+        deposit_amount: u64,
+    ) -> Result<RenewalProposed> {
+        require!(extra_periods > 0, ErrorCode::InvalidRenewalTerms);
+        self.agreement
+            .periods
+            .checked_add(extra_periods)
+            .ok_or(ErrorCode::InvalidRenewalTerms)?;
+
+        let current_rent = self.agreement.rent_amount;
+        let max_change = current_rent
+            .checked_mul(MAX_RENT_CHANGE_PERCENT)
+            .and_then(|v| v.checked_div(100))
+            .ok_or(ErrorCode::Overflow)?;
+        require!(
+            rent_amount.abs_diff(current_rent) <= max_change,
+            ErrorCode::RentChangeTooLarge
+        );
+
+        self.renewal.set_inner(Renewal {
+            agreement: self.agreement.key(),
+            extra_periods,
+            rent_amount,
+            deposit_amount,
+            proposed_at: Clock::get()?.unix_timestamp,
+            bump: bumps.renewal,
+        });
+
+        Ok(RenewalProposed {
+            agreement: self.agreement.key(),
+            landlord: self.landlord.key(),
+            extra_periods,
+            rent_amount,
+            deposit_amount,
+        })
+    }
+}
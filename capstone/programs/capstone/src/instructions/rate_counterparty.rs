@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, events::RatingLeft, Landlord, Renter, Review, MAX_RATING};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RateCounterparty<'info> {
+    // either party of the agreement, each rates the other once
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint=signer.key()==review.landlord || signer.key()==review.renter @ ErrorCode::Unauthorized,
+        seeds=[b"review",review.agreement.as_ref()],
+        bump=review.bump
+    )]
+    pub review: Account<'info, Review>,
+
+    #[account(
+        mut,
+        seeds=[b"landlord",review.landlord.as_ref()],
+        bump=landlord_account.bump
+    )]
+    pub landlord_account: Account<'info, Landlord>,
+
+    #[account(
+        mut,
+        seeds=[b"renter",review.renter.as_ref()],
+        bump=renter_account.bump
+    )]
+    pub renter_account: Account<'info, Renter>,
+}
+
+impl<'info> RateCounterparty<'info> {
+    pub fn rate_counterparty(&mut self, rating: u8, review_hash: [u8; 32]) -> Result<RatingLeft> {
+        require!((1..=MAX_RATING).contains(&rating), ErrorCode::InvalidRating);
+        require!(
+            Clock::get()?.unix_timestamp <= self.review.review_by,
+            ErrorCode::ReviewWindowClosed
+        );
+
+        let rated = if self.signer.key() == self.review.renter {
+            require!(
+                self.review.landlord_rating.is_none(),
+                ErrorCode::AlreadyRated
+            );
+            self.review.landlord_rating = Some(rating);
+            self.review.landlord_review_hash = review_hash;
+            self.landlord_account.add_rating(rating)?;
+            self.review.landlord
+        } else {
+            require!(self.review.renter_rating.is_none(), ErrorCode::AlreadyRated);
+            self.review.renter_rating = Some(rating);
+            self.review.renter_review_hash = review_hash;
+            self.renter_account.add_rating(rating)?;
+            self.review.renter
+        };
+        msg!("Rated {} with {}", rated, rating);
+
+        Ok(RatingLeft {
+            agreement: self.review.agreement,
+            rater: self.signer.key(),
+            rated,
+            rating,
+            review_hash,
+        })
+    }
+}
@@ -5,8 +5,9 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::Escrow;
+use crate::{events::ListingClosed, Escrow, Landlord, Property, PropertyStatus};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Refund<'info> {
     #[account(mut)]
@@ -20,10 +21,25 @@ pub struct Refund<'info> {
     )]
     pub escrow: Account<'info, Escrow>,
 
+    #[account(
+        mut,
+        seeds=[b"landlord",landlord.key().as_ref()],
+        bump=landlord_account.bump
+    )]
+    pub landlord_account: Account<'info, Landlord>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        seeds=[b"property",edition_mint.key().as_ref()],
+        bump=property.bump
+    )]
+    pub property: Account<'info, Property>,
+
     #[account(
         mut,
         mint::token_program=token_program,
-        seeds=[b"edition",collection_mint.key().as_ref()],
+        seeds=[b"edition",collection_mint.key().as_ref(),escrow.property_id.to_le_bytes().as_ref()],
         bump=escrow.edition_mint_bump
     )]
     pub edition_mint: InterfaceAccount<'info, Mint>,
@@ -77,7 +93,7 @@ pub struct Refund<'info> {
 }
 
 impl<'info> Refund<'info> {
-    pub fn burn_nft_and_close_vault(&mut self) -> Result<()> {
+    pub fn burn_nft_and_close_vault(&mut self) -> Result<ListingClosed> {
         let edition_mint_key = self.edition_mint.key();
         let signer_seeds: &[&[&[u8]]] =
             &[&[b"escrow", edition_mint_key.as_ref(), &[self.escrow.bump]]];
@@ -99,6 +115,13 @@ impl<'info> Refund<'info> {
         .with_remaining_accounts(vec![self.collection_metadata.to_account_info()]);
 
         burn_nft(burn_nft_cpi, Some(*self.collection_metadata.key))?;
-        Ok(())
+
+        self.property.status = PropertyStatus::Delisted;
+        self.landlord_account.close_listing()?;
+        Ok(ListingClosed {
+            escrow: self.escrow.key(),
+            landlord: self.landlord.key(),
+            edition_mint: self.edition_mint.key(),
+        })
     }
 }
@@ -1,10 +1,17 @@
-use anchor_lang::{
-    prelude::*,
-    system_program::{transfer, Transfer},
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use crate::{error::ErrorCode, Agreement, Renter};
+use crate::{
+    draw_from_deposit,
+    error::ErrorCode,
+    events::{DepositDrawdown, RentPaid, RentWithheld, ScoreChanged},
+    Agreement, AgreementStatus, Config, MaintenanceTicket, Renter, ScoringConfig,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct MonthlyRent<'info> {
     #[account(mut)]
@@ -15,129 +22,216 @@ pub struct MonthlyRent<'info> {
 
     #[account(
         mut,
-        seeds=[b"deposit",agreement.key().as_ref()],
-        bump=agreement.deposit_bump
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
     )]
-    pub deposit_vault: SystemAccount<'info>,
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.is_shared() @ ErrorCode::SharedAgreement,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
     pub agreement: Account<'info, Agreement>,
-    #[account(mut)]
+
+    #[account(
+        mut,
+        seeds=[b"renter",agreement.renter.as_ref()],
+        bump=renter.bump
+    )]
     pub renter: Account<'info, Renter>,
 
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    // an urgent ticket left unfunded past the repair SLA lets the renter hold back part of the rent
+    #[account(
+        mut,
+        seeds=[b"ticket",agreement.key().as_ref()],
+        bump=ticket.bump
+    )]
+    pub ticket: Option<Account<'info, MaintenanceTicket>>,
+
+    // holds the withheld rent until the repair is confirmed
+    #[account(mut)]
+    pub ticket_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds=[b"config"],
+        bump=config.bump
+    )]
+    pub config: Option<Account<'info, Config>>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=signer,
+        associated_token::token_program=payment_token_program
+    )]
+    pub signer_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> MonthlyRent<'info> {
-    pub fn monthly_rent(&mut self) -> Result<()> {
-        let clock = Clock::get()?;
-        let now = clock.unix_timestamp;
-        let seconds_in_month: i64 = 30 * 24 * 60 * 60; // 2_592_000 seconds
-        let month_offset = i64::from(self.agreement.payments_made)
-            .checked_add(1)
-            .and_then(|v| v.checked_mul(seconds_in_month))
-            .ok_or(ErrorCode::Overflow)?;
-        let rent_due_date = self
-            .agreement
-            .start_date
-            .checked_add(month_offset)
-            .ok_or(ErrorCode::Overflow)?;
+    pub fn monthly_rent(
+        &mut self,
+    ) -> Result<(
+        RentPaid,
+        Option<DepositDrawdown>,
+        ScoreChanged,
+        Option<RentWithheld>,
+    )> {
+        let now = Clock::get()?.unix_timestamp;
+        let period = self.agreement.current_period()?;
+        let schedule = self.agreement.schedule;
+        let start_date = self.agreement.start_date;
+
+        // rent can only be paid once its period has started, no prepaying future months
+        require!(
+            now >= schedule.period_start(start_date, period)?,
+            ErrorCode::RentNotDue
+        );
 
-        if now > rent_due_date {
-            self.pay_from_deposit()?;
-            self.add_record_and_decrement_score()?;
+        let late = now > schedule.late_after(start_date, period)?;
+        self.agreement.accrue_late_fees(now)?;
+        let balance = self.agreement.period_balance()?;
+        let escrow = self.withhold_rent(now, balance)?;
+        let withheld = escrow.as_ref().map_or(0, |(_, amount)| *amount);
+        let mut payouts = vec![(self.landlord_payment_ata.to_account_info(), balance - withheld)];
+        payouts.extend(escrow);
+        // the wallet pays whenever it can, the deposit only covers a late period the renter's own
+        // wallet cannot, so someone paying on the renter's behalf can't spend their deposit
+        let renter_signed = self.signer.key() == self.agreement.renter;
+        let drawdown = if late && renter_signed && self.signer_payment_ata.amount < balance {
+            Some(draw_from_deposit(
+                &mut self.agreement,
+                &self.deposit_vault,
+                &self.payment_mint,
+                &self.payment_token_program,
+                &payouts,
+            )?)
         } else {
-            self.pay_rent()?;
-            self.add_record_and_increment_score()?;
-        }
-        Ok(())
-    }
-    pub fn pay_rent(&mut self) -> Result<()> {
-        let payment_accounts = Transfer {
-            from: self.signer.to_account_info(),
-            to: self.landlord.to_account_info(),
+            for (to, amount) in payouts {
+                self.pay_rent(to, amount)?;
+            }
+            None
         };
-
-        let pay_rent_cpi = CpiContext::new(self.system_program.to_account_info(), payment_accounts);
-
-        transfer(pay_rent_cpi, self.agreement.rent_amount)?;
-        Ok(())
+        let late_fee = if late { self.agreement.late_fee()? } else { 0 };
+        let paid = self
+            .agreement
+            .period_paid
+            .checked_add(balance)
+            .ok_or(ErrorCode::Overflow)?;
+        self.agreement.settle_period()?;
+        let seconds_late = late.then_some(now - schedule.due_date(start_date, period)?);
+        let score_changed = self.renter.record_payment(
+            self.agreement.renter,
+            &self.scoring_config.model,
+            seconds_late,
+        )?;
+
+        let rent_paid = RentPaid {
+            agreement: self.agreement.key(),
+            renter: self.agreement.renter,
+            period,
+            amount: paid,
+            late_fee,
+            late,
+            from_deposit: drawdown.is_some(),
+        };
+        let rent_withheld = match &self.ticket {
+            Some(ticket) if withheld > 0 => Some(RentWithheld {
+                agreement: self.agreement.key(),
+                ticket: ticket.key(),
+                period,
+                amount: withheld,
+            }),
+            _ => None,
+        };
+        Ok((rent_paid, drawdown, score_changed, rent_withheld))
     }
 
-    pub fn pay_from_deposit(&mut self) -> Result<()> {
-        let agreement_key = self.agreement.key();
-        let signer_seeds: &[&[&[u8]]] = &[&[
-            b"deposit",
-            agreement_key.as_ref(),
-            &[self.agreement.deposit_bump],
-        ]];
-        let transfer_accounts = Transfer {
-            from: self.deposit_vault.to_account_info(),
-            to: self.landlord.to_account_info(),
+    /// Returns the ticket vault and the part of `balance` the renter holds back in it for an
+    /// overdue urgent ticket. Rent is withheld once per ticket, confirming the repair releases it
+    /// to the landlord.
+    pub fn withhold_rent(
+        &mut self,
+        now: i64,
+        balance: u64,
+    ) -> Result<Option<(AccountInfo<'info>, u64)>> {
+        let Some(ticket) = self.ticket.as_mut() else {
+            return Ok(None);
+        };
+        let Some(config) = self.config.as_ref() else {
+            return err!(ErrorCode::ConfigAccountMissing);
         };
-        let transfer_cpi = CpiContext::new_with_signer(
-            self.system_program.to_account_info(),
-            transfer_accounts,
-            signer_seeds,
+        if ticket.withheld > 0 || !ticket.overdue(now, config.repair_sla)? {
+            return Ok(None);
+        }
+        let withheld = balance
+            .checked_mul(config.rent_withhold_percent as u64)
+            .ok_or(ErrorCode::Overflow)?
+            / 100;
+        if withheld == 0 {
+            return Ok(None);
+        }
+        let Some(ticket_vault) = self.ticket_vault.as_ref() else {
+            return err!(ErrorCode::TicketVaultMissing);
+        };
+        require_keys_eq!(
+            ticket_vault.key(),
+            get_associated_token_address_with_program_id(
+                &ticket.key(),
+                &self.payment_mint.key(),
+                &self.payment_token_program.key(),
+            ),
+            ErrorCode::TicketVaultMissing
         );
-        let late_rent_fine_fee = self
-            .agreement
-            .rent_amount
-            .checked_mul(u64::from(self.agreement.late_fee_percent))
-            .and_then(|v| v.checked_div(100))
-            .ok_or(ErrorCode::Overflow)?;
-        let total_rent = self
-            .agreement
-            .rent_amount
-            .checked_add(late_rent_fine_fee)
-            .ok_or(ErrorCode::Overflow)?;
-        transfer(transfer_cpi, total_rent)?;
-
-        Ok(())
+        ticket.withheld = withheld;
+        msg!("Withheld {} of the rent for an overdue repair", withheld);
+        Ok(Some((ticket_vault.to_account_info(), withheld)))
     }
 
-    pub fn add_record_and_decrement_score(&mut self) -> Result<()> {
-        self.agreement.payments_made = self
-            .agreement
-            .payments_made
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
-
-        self.renter.score = self
-            .renter
-            .score
-            .checked_sub(2)
-            .ok_or(ErrorCode::Overflow)?;
-        self.renter.total_payments = self
-            .renter
-            .total_payments
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
-        self.renter.late_payments = self
-            .renter
-            .late_payments
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
-        Ok(())
-    }
+    pub fn pay_rent(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let payment_accounts = TransferChecked {
+            authority: self.signer.to_account_info(),
+            from: self.signer_payment_ata.to_account_info(),
+            to,
+            mint: self.payment_mint.to_account_info(),
+        };
 
-    pub fn add_record_and_increment_score(&mut self) -> Result<()> {
-        self.agreement.payments_made = self
-            .agreement
-            .payments_made
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
+        let pay_rent_cpi = CpiContext::new(
+            self.payment_token_program.to_account_info(),
+            payment_accounts,
+        );
 
-        self.renter.score = self
-            .renter
-            .score
-            .checked_add(2)
-            .ok_or(ErrorCode::Overflow)?;
-        self.renter.total_payments = self
-            .renter
-            .total_payments
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
+        // whatever was paid in installments toward this period is not charged again
+        transfer_checked(pay_rent_cpi, amount, self.payment_mint.decimals)?;
         Ok(())
     }
 }
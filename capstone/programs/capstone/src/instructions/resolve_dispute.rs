@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ErrorCode, events::DisputeResolved, Agreement, AgreementStatus, ClaimStatus, Config,
+    DepositClaim,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    pub arbiter: Signer<'info>,
+
+    #[account(
+        has_one=arbiter,
+        seeds=[b"config"],
+        bump=config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint=agreement.status==AgreementStatus::DepositClaimed @ ErrorCode::InvalidClaimStatus,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        seeds=[b"claim",agreement.key().as_ref()],
+        bump=claim.bump
+    )]
+    pub claim: Account<'info, DepositClaim>,
+}
+
+impl<'info> ResolveDispute<'info> {
+    pub fn resolve_dispute(&mut self, landlord_award: u64) -> Result<DisputeResolved> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            self.claim.status == ClaimStatus::Disputed,
+            ErrorCode::InvalidClaimStatus
+        );
+        require!(now <= self.claim.arbitrate_by, ErrorCode::DeadlinePassed);
+        require!(
+            landlord_award <= self.claim.amount,
+            ErrorCode::DeductionTooLarge
+        );
+
+        self.claim.status = ClaimStatus::Resolved;
+        self.claim.awarded_amount = landlord_award;
+        Ok(DisputeResolved {
+            agreement: self.agreement.key(),
+            arbiter: self.arbiter.key(),
+            claimed_amount: self.claim.amount,
+            awarded_amount: landlord_award,
+        })
+    }
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::ErrorCode,
+    events::{DeductionAccepted, DeductionDisputed},
+    Agreement, AgreementStatus, ClaimStatus, Config, DepositClaim,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RespondToClaim<'info> {
+    pub renter: Signer<'info>,
+
+    #[account(
+        has_one=renter,
+        constraint=agreement.status==AgreementStatus::DepositClaimed @ ErrorCode::InvalidClaimStatus,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        seeds=[b"claim",agreement.key().as_ref()],
+        bump=claim.bump
+    )]
+    pub claim: Account<'info, DepositClaim>,
+
+    #[account(
+        seeds=[b"config"],
+        bump=config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> RespondToClaim<'info> {
+    pub fn check_claim_open(&self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            self.claim.status == ClaimStatus::Proposed,
+            ErrorCode::InvalidClaimStatus
+        );
+        require!(now <= self.claim.respond_by, ErrorCode::DeadlinePassed);
+        Ok(())
+    }
+
+    pub fn accept_claim(&mut self) -> Result<DeductionAccepted> {
+        self.claim.status = ClaimStatus::Accepted;
+        self.claim.awarded_amount = self.claim.amount;
+        Ok(DeductionAccepted {
+            agreement: self.agreement.key(),
+            renter: self.renter.key(),
+            amount: self.claim.amount,
+        })
+    }
+
+    pub fn dispute_claim(&mut self) -> Result<DeductionDisputed> {
+        let arbitrate_by = Clock::get()?
+            .unix_timestamp
+            .checked_add(self.config.arbitration_window)
+            .ok_or(ErrorCode::Overflow)?;
+        self.claim.status = ClaimStatus::Disputed;
+        self.claim.arbitrate_by = arbitrate_by;
+        Ok(DeductionDisputed {
+            agreement: self.agreement.key(),
+            renter: self.renter.key(),
+            amount: self.claim.amount,
+            arbitrate_by,
+        })
+    }
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    approve, revoke, Approve, Mint, Revoke, TokenAccount, TokenInterface,
+};
+
+use crate::{error::ErrorCode, events::AutopayChanged, keeper_tip, Agreement, AgreementStatus};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetAutopay<'info> {
+    pub renter: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=renter,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.is_shared() @ ErrorCode::SharedAgreement,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=renter,
+        associated_token::token_program=payment_token_program
+    )]
+    pub renter_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub payment_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> SetAutopay<'info> {
+    pub fn set_autopay(&mut self, enabled: bool) -> Result<AutopayChanged> {
+        // a token account has a single delegate, approving this agreement would silently revoke
+        // auto-pay on another one
+        let delegate = self.renter_payment_ata.delegate;
+        let delegated_here = delegate == Some(self.agreement.key()).into();
+        let allowance = if enabled {
+            require!(
+                delegate.is_none() || delegated_here,
+                ErrorCode::AutopayDelegateTaken
+            );
+            // approving again after a renewal resets the allowance to the new remaining rent,
+            // plus the keeper tips the renter pays on top of it
+            let rent = self.agreement.remaining_rent()?;
+            let allowance = rent
+                .checked_add(keeper_tip(rent)?)
+                .ok_or(ErrorCode::Overflow)?;
+            let approve_accounts = Approve {
+                to: self.renter_payment_ata.to_account_info(),
+                delegate: self.agreement.to_account_info(),
+                authority: self.renter.to_account_info(),
+            };
+            let approve_cpi = CpiContext::new(
+                self.payment_token_program.to_account_info(),
+                approve_accounts,
+            );
+            approve(approve_cpi, allowance)?;
+            msg!("Auto-pay approved for {}", allowance);
+            allowance
+        } else if delegated_here {
+            let revoke_accounts = Revoke {
+                source: self.renter_payment_ata.to_account_info(),
+                authority: self.renter.to_account_info(),
+            };
+            let revoke_cpi = CpiContext::new(
+                self.payment_token_program.to_account_info(),
+                revoke_accounts,
+            );
+            revoke(revoke_cpi)?;
+            msg!("Auto-pay revoked");
+            0
+        } else {
+            0
+        };
+        self.agreement.autopay = enabled;
+
+        Ok(AutopayChanged {
+            agreement: self.agreement.key(),
+            renter: self.renter.key(),
+            enabled,
+            allowance,
+        })
+    }
+}
@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    close_deposit_vault,
+    error::ErrorCode,
+    events::{AgreementClosed, DepositSettled},
+    pay_out, refund_co_renters, Agreement, AgreementStatus, ClaimStatus, Config, DepositClaim,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SettleDeposit<'info> {
+    // anyone can settle once the outcome is decided or a deadline ran out
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub landlord: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub renter: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close=landlord,
+        has_one=landlord,
+        has_one=renter,
+        has_one=payment_mint,
+        constraint=!agreement.deposit_staked() @ ErrorCode::DepositStaked,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        close=landlord,
+        seeds=[b"claim",agreement.key().as_ref()],
+        bump=claim.bump
+    )]
+    pub claim: Option<Account<'info, DepositClaim>>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
+    )]
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=payment_mint,
+        associated_token::authority=renter,
+        associated_token::token_program=payment_token_program
+    )]
+    pub renter_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=signer,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds=[b"config"],
+        bump=config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SettleDeposit<'info> {
+    pub fn settle_deposit(
+        &mut self,
+        co_renter_atas: &'info [AccountInfo<'info>],
+    ) -> Result<(DepositSettled, AgreementClosed)> {
+        let now = Clock::get()?.unix_timestamp;
+
+        let (claimed_amount, timed_out) = match self.agreement.status {
+            AgreementStatus::Active => return err!(ErrorCode::NotInMoveOut),
+            AgreementStatus::MoveOut => {
+                let claim_deadline = self
+                    .agreement
+                    .move_out_date
+                    .checked_add(self.config.claim_window)
+                    .ok_or(ErrorCode::Overflow)?;
+                require!(now > claim_deadline, ErrorCode::ClaimWindowOpen);
+                (0, false)
+            }
+            AgreementStatus::DepositClaimed => {
+                let claim = self.claim.as_ref().ok_or(ErrorCode::ClaimAccountMissing)?;
+                match claim.status {
+                    // renter never answered, the claim stands
+                    ClaimStatus::Proposed => {
+                        require!(now > claim.respond_by, ErrorCode::ClaimPending);
+                        (claim.amount, true)
+                    }
+                    // arbiter never ruled, the deposit goes back to the renter
+                    ClaimStatus::Disputed => {
+                        require!(now > claim.arbitrate_by, ErrorCode::ClaimPending);
+                        (0, true)
+                    }
+                    ClaimStatus::Accepted | ClaimStatus::Resolved => (claim.awarded_amount, false),
+                }
+            }
+        };
+
+        let deposit_left = self.deposit_vault.amount;
+        let landlord_amount = claimed_amount.min(deposit_left);
+        let renter_amount = deposit_left - landlord_amount;
+
+        if landlord_amount > 0 {
+            pay_out(
+                &self.agreement,
+                &self.deposit_vault,
+                &self.payment_mint,
+                &self.payment_token_program,
+                self.landlord_payment_ata.to_account_info(),
+                landlord_amount,
+            )?;
+        }
+        let lead_amount = refund_co_renters(
+            &self.agreement,
+            &self.deposit_vault,
+            &self.payment_mint,
+            &self.payment_token_program,
+            renter_amount,
+            co_renter_atas,
+        )?;
+        if lead_amount > 0 {
+            pay_out(
+                &self.agreement,
+                &self.deposit_vault,
+                &self.payment_mint,
+                &self.payment_token_program,
+                self.renter_payment_ata.to_account_info(),
+                lead_amount,
+            )?;
+        }
+        close_deposit_vault(
+            &self.agreement,
+            &self.deposit_vault,
+            self.renter.to_account_info(),
+            &self.payment_token_program,
+        )?;
+
+        Ok((
+            DepositSettled {
+                agreement: self.agreement.key(),
+                landlord_amount,
+                renter_amount,
+                timed_out,
+            },
+            AgreementClosed {
+                agreement: self.agreement.key(),
+                landlord: self.landlord.key(),
+                renter: self.renter.key(),
+                edition_mint: self.agreement.edition_mint,
+                terminated: false,
+            },
+        ))
+    }
+}
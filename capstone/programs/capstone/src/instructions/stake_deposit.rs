@@ -0,0 +1,218 @@
+// the stake module moved to solana-stake-interface, which anchor-spl 0.31 does not depend on yet
+#[allow(deprecated)]
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program::invoke_signed,
+        stake::{
+            self,
+            state::{Authorized, Lockup, StakeStateV2},
+        },
+        sysvar,
+    },
+    system_program::{create_account, CreateAccount},
+};
+use anchor_spl::{
+    stake::Stake,
+    token::spl_token::native_mint,
+    token_interface::{
+        close_account, transfer_checked, CloseAccount, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
+};
+
+use crate::{error::ErrorCode, events::DepositStaked, Agreement, AgreementStatus};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct StakeDeposit<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=renter,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=agreement.yield_renter_share_percent.is_some() @ ErrorCode::YieldNotEnabled,
+        constraint=!agreement.deposit_staked() @ ErrorCode::DepositStaked,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
+    )]
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    // wrapped SOL can only be turned back into lamports by closing its account,
+    // so the deposit passes through here on its way into the stake account
+    #[account(
+        init,
+        payer=renter,
+        token::mint=payment_mint,
+        token::authority=agreement,
+        token::token_program=payment_token_program,
+        seeds=[b"unwrap",agreement.key().as_ref()],
+        bump
+    )]
+    pub unwrap_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Created and initialized here as a native stake account owned by the stake program
+    #[account(
+        mut,
+        seeds=[b"stake",agreement.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// CHECK: The stake program checks this is a vote account when delegating
+    pub vote_account: UncheckedAccount<'info>,
+
+    #[account(
+        address=native_mint::ID @ ErrorCode::StakingRequiresNativeMint,
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Stake history sysvar
+    #[account(address=sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    /// CHECK: Stake config account, still required by DelegateStake
+    #[account(address=stake::config::ID)]
+    pub stake_config: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub rent: Sysvar<'info, Rent>,
+    pub stake_program: Program<'info, Stake>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> StakeDeposit<'info> {
+    pub fn stake_deposit(&mut self, bumps: &StakeDepositBumps) -> Result<DepositStaked> {
+        let amount = self.deposit_vault.amount;
+        require!(amount > 0, ErrorCode::DepositFundsLow);
+
+        self.create_stake_account(bumps)?;
+        self.unwrap_deposit(amount)?;
+        self.delegate_stake()?;
+
+        self.agreement.staked_amount = amount;
+        self.agreement.stake_reserve = self
+            .stake_account
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        self.agreement.stake_funder = self.renter.key();
+        msg!("Staked {} of the deposit", amount);
+
+        Ok(DepositStaked {
+            agreement: self.agreement.key(),
+            stake_account: self.stake_account.key(),
+            vote_account: self.vote_account.key(),
+            amount,
+        })
+    }
+
+    pub fn create_stake_account(&mut self, bumps: &StakeDepositBumps) -> Result<()> {
+        let agreement_key = self.agreement.key();
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[b"stake", agreement_key.as_ref(), &[bumps.stake_account]]];
+        let space = StakeStateV2::size_of();
+        let create_accounts = CreateAccount {
+            from: self.renter.to_account_info(),
+            to: self.stake_account.to_account_info(),
+        };
+        let create_cpi = CpiContext::new_with_signer(
+            self.system_program.to_account_info(),
+            create_accounts,
+            signer_seeds,
+        );
+        create_account(
+            create_cpi,
+            self.rent.minimum_balance(space),
+            space as u64,
+            &stake::program::ID,
+        )?;
+
+        let ix = stake::instruction::initialize(
+            &self.stake_account.key(),
+            &Authorized::auto(&agreement_key),
+            &Lockup::default(),
+        );
+        invoke_signed(
+            &ix,
+            &[
+                self.stake_account.to_account_info(),
+                self.rent.to_account_info(),
+            ],
+            &[],
+        )?;
+        Ok(())
+    }
+
+    pub fn unwrap_deposit(&mut self, amount: u64) -> Result<()> {
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"agreement",
+            self.agreement.edition_mint.as_ref(),
+            &[self.agreement.bump],
+        ]];
+        let transfer_accounts = TransferChecked {
+            authority: self.agreement.to_account_info(),
+            from: self.deposit_vault.to_account_info(),
+            to: self.unwrap_account.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+        };
+        let transfer_cpi = CpiContext::new_with_signer(
+            self.payment_token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        transfer_checked(transfer_cpi, amount, self.payment_mint.decimals)?;
+
+        // closing a native account releases its balance and rent straight into the stake account
+        let close_accounts = CloseAccount {
+            account: self.unwrap_account.to_account_info(),
+            authority: self.agreement.to_account_info(),
+            destination: self.stake_account.to_account_info(),
+        };
+        let close_cpi = CpiContext::new_with_signer(
+            self.payment_token_program.to_account_info(),
+            close_accounts,
+            signer_seeds,
+        );
+        close_account(close_cpi)
+    }
+
+    pub fn delegate_stake(&mut self) -> Result<()> {
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"agreement",
+            self.agreement.edition_mint.as_ref(),
+            &[self.agreement.bump],
+        ]];
+        let ix = stake::instruction::delegate_stake(
+            &self.stake_account.key(),
+            &self.agreement.key(),
+            &self.vote_account.key(),
+        );
+        invoke_signed(
+            &ix,
+            &[
+                self.stake_account.to_account_info(),
+                self.vote_account.to_account_info(),
+                self.clock.to_account_info(),
+                self.stake_history.to_account_info(),
+                self.stake_config.to_account_info(),
+                self.agreement.to_account_info(),
+            ],
+            signer_seeds,
+        )?;
+        Ok(())
+    }
+}
@@ -1,14 +1,15 @@
-use anchor_lang::{
-    prelude::*,
-    system_program::{transfer, Transfer},
-};
+use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
-use crate::{error::ErrorCode, state::Agreement, Escrow};
+use crate::{
+    events::AgreementTaken, state::Agreement, AgreementStatus, Escrow, Landlord, Property,
+    PropertyStatus, Renter,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Take<'info> {
     #[account(mut)]
@@ -17,11 +18,17 @@ pub struct Take<'info> {
     #[account(mut)]
     pub landlord: SystemAccount<'info>,
 
+    #[account(
+        seeds=[b"renter",renter.key().as_ref()],
+        bump=renter_account.bump
+    )]
+    pub renter_account: Account<'info, Renter>,
+
     #[account(
         init,
         payer=renter,
         space=8+Agreement::INIT_SPACE,
-        seeds=[b"agreement",renter.key().as_ref(),landlord.key().as_ref()],
+        seeds=[b"agreement",edition_mint.key().as_ref()],
         bump
     )]
     pub agreement: Account<'info, Agreement>,
@@ -29,11 +36,28 @@ pub struct Take<'info> {
     #[account(
         mut,
         close=landlord,
+        has_one=landlord,
+        has_one=payment_mint,
         seeds=[b"escrow",edition_mint.key().as_ref()],
         bump=escrow.bump,
     )]
     pub escrow: Account<'info, Escrow>,
 
+    #[account(
+        mut,
+        seeds=[b"landlord",landlord.key().as_ref()],
+        bump=landlord_account.bump
+    )]
+    pub landlord_account: Account<'info, Landlord>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        seeds=[b"property",edition_mint.key().as_ref()],
+        bump=property.bump
+    )]
+    pub property: Account<'info, Property>,
+
     #[account(
         mint::token_program=token_program,
     )]
@@ -47,12 +71,27 @@ pub struct Take<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
     #[account(
         mut,
-        seeds=[b"deposit",agreement.key().as_ref()],
-        bump
+        associated_token::mint=payment_mint,
+        associated_token::authority=renter,
+        associated_token::token_program=payment_token_program
+    )]
+    pub renter_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer=renter,
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
     )]
-    pub deposit_vault: SystemAccount<'info>,
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init,
@@ -64,26 +103,29 @@ pub struct Take<'info> {
     pub nft_vault: InterfaceAccount<'info, TokenAccount>,
 
     pub token_program: Interface<'info, TokenInterface>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 impl<'info> Take<'info> {
-    pub fn init_agreement_pda(&mut self, bumps: &TakeBumps) -> Result<()> {
-        let start_date = Clock::get()?.unix_timestamp;
+    pub fn check_renter_eligibility(&self) -> Result<()> {
+        self.renter_account.check_eligibility(
+            self.escrow.min_renter_score,
+            self.escrow.max_late_payment_percent,
+        )
+    }
 
-        // Approximate months in seconds (30 days each)
-        let seconds_in_month = 30 * 24 * 60 * 60; // 2_592_000 seconds
-        let total_seconds = i64::from(self.escrow.months)
-            .checked_mul(seconds_in_month)
-            .ok_or(ErrorCode::Overflow)? as i64;
-        let end_date = start_date
-            .checked_add(total_seconds)
-            .ok_or(ErrorCode::Overflow)?;
+    pub fn init_agreement_pda(&mut self, bumps: &TakeBumps) -> Result<AgreementTaken> {
+        let start_date = Clock::get()?.unix_timestamp;
+        let schedule = self.escrow.schedule;
+        let end_date = schedule.due_date(start_date, u16::from(self.escrow.months) - 1)?;
 
         self.agreement.set_inner(Agreement {
             landlord: *self.landlord.key,
             renter: *self.renter.key,
+            payment_mint: self.payment_mint.key(),
+            edition_mint: self.edition_mint.key(),
             start_date,
             end_date,
             rent_amount: self.escrow.monthly_rent,
@@ -92,22 +134,58 @@ impl<'info> Take<'info> {
             cancel_allowed_after: self.escrow.cancel_allowed_after,
             cancel_penalty_percent: self.escrow.cancel_penalty_percent,
             payments_made: 0,
+            periods: self.escrow.months,
+            schedule,
+            missed_payments: 0,
+            status: AgreementStatus::Active,
+            move_out_date: 0,
+            yield_renter_share_percent: self.escrow.yield_renter_share_percent,
+            staked_amount: 0,
+            stake_reserve: 0,
+            stake_funder: Pubkey::default(),
+            period_paid: 0,
+            late_fees_owed: 0,
+            late_fees_assessed: 0,
+            autopay: false,
+            tenants: Vec::new(),
+            min_renter_score: self.escrow.min_renter_score,
+            max_late_payment_percent: self.escrow.max_late_payment_percent,
+            successor: None,
+            tenancy_token: false,
             bump: bumps.agreement,
-            deposit_bump: bumps.deposit_vault,
         });
-        Ok(())
+        self.property.status = PropertyStatus::Leased;
+        self.landlord_account.start_agreement()?;
+        Ok(AgreementTaken {
+            agreement: self.agreement.key(),
+            landlord: self.landlord.key(),
+            renter: self.renter.key(),
+            edition_mint: self.edition_mint.key(),
+            start_date,
+            end_date,
+            rent_amount: self.agreement.rent_amount,
+            deposit_amount: self.agreement.deposit_amount,
+        })
     }
 
     pub fn transfer_deposit(&mut self) -> Result<()> {
-        let transfer_accounts = Transfer {
-            from: self.renter.to_account_info(),
+        let transfer_accounts = TransferChecked {
+            authority: self.renter.to_account_info(),
+            from: self.renter_payment_ata.to_account_info(),
             to: self.deposit_vault.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
         };
 
-        let transfer_cpi_ctx =
-            CpiContext::new(self.system_program.to_account_info(), transfer_accounts);
+        let transfer_cpi_ctx = CpiContext::new(
+            self.payment_token_program.to_account_info(),
+            transfer_accounts,
+        );
 
-        transfer(transfer_cpi_ctx, self.agreement.deposit_amount)?;
+        transfer_checked(
+            transfer_cpi_ctx,
+            self.agreement.deposit_amount,
+            self.payment_mint.decimals,
+        )?;
         Ok(())
     }
 
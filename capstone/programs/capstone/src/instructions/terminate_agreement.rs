@@ -0,0 +1,351 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    burn_tenancy_token, close_deposit_vault, close_nft_vault,
+    error::ErrorCode,
+    events::{AgreementClosed, AgreementTerminated, ScoreChanged, TicketSettled},
+    pay_out, refund_co_renters, settle_ticket, transfer_item_nft, Agreement, AgreementStatus,
+    Landlord, Property, PropertyStatus, Renter, ScoringConfig, MAX_MISSED_PAYMENTS,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TerminateAgreement<'info> {
+    #[account(mut)]
+    pub landlord: Signer<'info>,
+
+    #[account(mut)]
+    pub renter: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        close=landlord,
+        has_one=landlord,
+        has_one=renter,
+        has_one=payment_mint,
+        has_one=edition_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.deposit_staked() @ ErrorCode::DepositStaked,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        seeds=[b"renter",renter.key().as_ref()],
+        bump=renter_account.bump
+    )]
+    pub renter_account: Account<'info, Renter>,
+
+    #[account(
+        mut,
+        seeds=[b"landlord",landlord.key().as_ref()],
+        bump=landlord_account.bump
+    )]
+    pub landlord_account: Account<'info, Landlord>,
+
+    #[account(
+        mut,
+        seeds=[b"property",edition_mint.key().as_ref()],
+        bump=property.bump
+    )]
+    pub property: Account<'info, Property>,
+
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    #[account(
+        mint::token_program=token_program,
+    )]
+    pub edition_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=edition_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=token_program
+    )]
+    pub nft_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=landlord,
+        associated_token::mint=edition_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=token_program
+    )]
+    pub landlord_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
+    )]
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer=landlord,
+        associated_token::mint=payment_mint,
+        associated_token::authority=landlord,
+        associated_token::token_program=payment_token_program
+    )]
+    pub landlord_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=landlord,
+        associated_token::mint=payment_mint,
+        associated_token::authority=renter,
+        associated_token::token_program=payment_token_program
+    )]
+    pub renter_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // a repair ticket still open when the lease ends is settled to the landlord
+    /// CHECK: Maintenance ticket, checked by its seeds and settled only if one was opened
+    #[account(
+        mut,
+        seeds=[b"ticket",agreement.key().as_ref()],
+        bump
+    )]
+    pub ticket: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub ticket_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // only needed once a tenancy token was issued, so it can be burned from the renter's wallet
+    /// CHECK: Tenancy token mint, checked by its seeds
+    #[account(
+        mut,
+        seeds=[b"tenancy",agreement.key().as_ref()],
+        bump
+    )]
+    pub tenancy_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Renter's tenancy token account, the token program checks it holds the token
+    #[account(mut)]
+    pub renter_tenancy_ata: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TerminateAgreement<'info> {
+    pub fn terminate_agreement(
+        &mut self,
+        remaining_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<(
+        AgreementTerminated,
+        Vec<ScoreChanged>,
+        Option<TicketSettled>,
+        AgreementClosed,
+    )> {
+        let overdue_periods = self.overdue_periods()?;
+        let period_charge = self
+            .agreement
+            .rent_amount
+            .checked_add(self.agreement.late_fee()?)
+            .ok_or(ErrorCode::Overflow)?;
+        let unpaid_periods = self
+            .agreement
+            .missed_payments
+            .checked_add(overdue_periods)
+            .ok_or(ErrorCode::Overflow)?;
+
+        // behind by too many periods, or behind with a deposit that can't cover another one
+        let deposit_exhausted = self.deposit_vault.amount < period_charge;
+        require!(
+            unpaid_periods >= MAX_MISSED_PAYMENTS || (overdue_periods > 0 && deposit_exhausted),
+            ErrorCode::TerminationNotAllowed
+        );
+
+        // overdue periods are exactly the ones with an assessed late fee, less any installments
+        self.agreement
+            .accrue_late_fees(Clock::get()?.unix_timestamp)?;
+        let arrears = self
+            .agreement
+            .rent_amount
+            .checked_mul(u64::from(overdue_periods))
+            .and_then(|v| v.checked_add(self.agreement.late_fees_owed))
+            .ok_or(ErrorCode::Overflow)?
+            .saturating_sub(self.agreement.period_paid);
+        let arrears_paid = arrears.min(self.deposit_vault.amount);
+        let deposit_returned = self.deposit_vault.amount - arrears_paid;
+        // co-renters' payment token accounts come first, then the Renter accounts of those behind
+        let co_renters = self.agreement.tenants.len().saturating_sub(1);
+        let (co_renter_atas, co_renter_accounts) =
+            remaining_accounts.split_at(co_renters.min(remaining_accounts.len()));
+
+        transfer_item_nft(
+            &self.agreement,
+            &self.nft_vault,
+            &self.edition_mint,
+            &self.landlord_ata,
+            &self.token_program,
+        )?;
+        close_nft_vault(
+            &self.agreement,
+            &self.nft_vault,
+            self.landlord.to_account_info(),
+            &self.token_program,
+        )?;
+        if arrears_paid > 0 {
+            pay_out(
+                &self.agreement,
+                &self.deposit_vault,
+                &self.payment_mint,
+                &self.payment_token_program,
+                self.landlord_payment_ata.to_account_info(),
+                arrears_paid,
+            )?;
+        }
+        let lead_returned = refund_co_renters(
+            &self.agreement,
+            &self.deposit_vault,
+            &self.payment_mint,
+            &self.payment_token_program,
+            deposit_returned,
+            co_renter_atas,
+        )?;
+        if lead_returned > 0 {
+            pay_out(
+                &self.agreement,
+                &self.deposit_vault,
+                &self.payment_mint,
+                &self.payment_token_program,
+                self.renter_payment_ata.to_account_info(),
+                lead_returned,
+            )?;
+        }
+        close_deposit_vault(
+            &self.agreement,
+            &self.deposit_vault,
+            self.renter.to_account_info(),
+            &self.payment_token_program,
+        )?;
+        let ticket_settled = settle_ticket(
+            &self.ticket,
+            &self.ticket_vault,
+            &self.payment_mint,
+            &self.payment_token_program,
+            self.landlord_payment_ata.to_account_info(),
+            self.renter.to_account_info(),
+        )?;
+        burn_tenancy_token(
+            &mut self.agreement,
+            &self.tenancy_mint,
+            &self.renter_tenancy_ata,
+            &self.token_2022_program,
+        )?;
+        let score_changes = self.penalize_renters(overdue_periods, co_renter_accounts)?;
+        self.property.status = PropertyStatus::Delisted;
+        self.landlord_account.end_agreement(false)?;
+
+        Ok((
+            AgreementTerminated {
+                agreement: self.agreement.key(),
+                landlord: self.landlord.key(),
+                renter: self.renter.key(),
+                unpaid_periods,
+                arrears_paid,
+                deposit_returned,
+            },
+            score_changes,
+            ticket_settled,
+            AgreementClosed {
+                agreement: self.agreement.key(),
+                landlord: self.landlord.key(),
+                renter: self.renter.key(),
+                edition_mint: self.agreement.edition_mint,
+                terminated: true,
+            },
+        ))
+    }
+
+    /// Periods past their grace deadline that were neither paid nor cranked.
+    pub fn overdue_periods(&self) -> Result<u16> {
+        let now = Clock::get()?.unix_timestamp;
+        let mut overdue = 0;
+        for period in self.agreement.payments_made..u16::from(self.agreement.periods) {
+            let late_after = self
+                .agreement
+                .schedule
+                .late_after(self.agreement.start_date, period)?;
+            if now <= late_after {
+                break;
+            }
+            overdue += 1;
+        }
+        Ok(overdue)
+    }
+
+    /// Scores everyone still behind on the arrears: the lead renter through `renter_account`, and
+    /// co-renters through their `Renter` accounts, passed in tenant order after the token accounts.
+    /// A renter whose share of the oldest overdue period is in owes one period less.
+    pub fn penalize_renters(
+        &mut self,
+        overdue_periods: u16,
+        co_renter_accounts: &'info [AccountInfo<'info>],
+    ) -> Result<Vec<ScoreChanged>> {
+        let model = self.scoring_config.model;
+        let missed_payments = self.agreement.missed_payments;
+        let owed = |share_settled: bool| {
+            let overdue = if share_settled {
+                overdue_periods.saturating_sub(1)
+            } else {
+                overdue_periods
+            };
+            (overdue, missed_payments.saturating_add(overdue))
+        };
+
+        let mut score_changes = Vec::new();
+        let lead_settled = self
+            .agreement
+            .tenants
+            .first()
+            .is_some_and(|lead| lead.share_settled);
+        let (overdue, unpaid) = owed(lead_settled);
+        if unpaid > 0 {
+            score_changes.push(self.renter_account.record_eviction(
+                self.renter.key(),
+                &model,
+                overdue,
+                unpaid,
+            )?);
+        }
+
+        let mut co_renter_accounts = co_renter_accounts.iter();
+        for tenant in self.agreement.tenants.iter().skip(1) {
+            let (overdue, unpaid) = owed(tenant.share_settled);
+            if unpaid == 0 {
+                continue;
+            }
+            let info = co_renter_accounts
+                .next()
+                .ok_or(ErrorCode::TenantAccountMismatch)?;
+            let mut renter = Renter::load_tenant(info, &tenant.wallet)?;
+            score_changes.push(renter.record_eviction(tenant.wallet, &model, overdue, unpaid)?);
+            renter.exit(&crate::ID)?;
+        }
+        Ok(score_changes)
+    }
+}
@@ -0,0 +1,164 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::{
+        set_authority,
+        spl_token_2022::{
+            extension::ExtensionType, instruction::AuthorityType, state::Mint as Token2022Mint,
+        },
+        SetAuthority, Token2022,
+    },
+    token_2022_extensions::{
+        spl_pod::optional_keys::OptionalNonZeroPubkey,
+        spl_token_metadata_interface::state::TokenMetadata, token_group_initialize,
+        token_metadata_initialize, TokenGroupInitialize, TokenMetadataInitialize,
+    },
+    token_interface::{mint_to, Mint, MintTo, TokenAccount},
+};
+
+use crate::{fund_mint, Landlord};
+
+#[derive(Accounts)]
+pub struct InitLandlord<'info> {
+    #[account(mut)]
+    pub landlord: Signer<'info>,
+
+    #[account(
+        init,
+        payer=landlord,
+        space=8+Landlord::INIT_SPACE,
+        seeds=[b"landlord",landlord.key().as_ref()],
+        bump
+    )]
+    pub landlord_account: Account<'info, Landlord>,
+
+    // the collection is a token group whose metadata lives in the mint itself
+    #[account(
+        init,
+        payer=landlord,
+        mint::decimals=0,
+        mint::authority=landlord,
+        mint::freeze_authority=landlord,
+        mint::token_program=token_program,
+        extensions::metadata_pointer::authority=landlord,
+        extensions::metadata_pointer::metadata_address=collection_mint,
+        extensions::group_pointer::authority=landlord,
+        extensions::group_pointer::group_address=collection_mint,
+        seeds=[b"collection_mint",landlord.key().as_ref()],
+        bump,
+    )]
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer=landlord,
+        associated_token::authority=landlord,
+        associated_token::mint=collection_mint,
+        associated_token::token_program=token_program,
+    )]
+    pub collection_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+impl<'info> InitLandlord<'info> {
+    pub fn init_landlord_account(&mut self, bumps: &InitLandlordBumps) -> Result<()> {
+        self.landlord_account
+            .set_inner(Landlord::new(bumps.landlord_account));
+        Ok(())
+    }
+
+    /// Mints the collection NFT and initializes its metadata and token group. Keeps the name of
+    /// the Metaplex backend's handler, where the collection is a master edition.
+    pub fn mint_master_edition_nft(
+        &mut self,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        let token_program = self.token_program.to_account_info();
+        let mint = self.collection_mint.to_account_info();
+        let landlord = self.landlord.to_account_info();
+
+        let token_metadata = TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::try_from(Some(self.landlord.key()))?,
+            mint: mint.key(),
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            additional_metadata: Vec::new(),
+        };
+        let mint_len = ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+            ExtensionType::MetadataPointer,
+            ExtensionType::GroupPointer,
+            ExtensionType::TokenGroup,
+        ])?;
+        fund_mint(
+            &mint,
+            &landlord,
+            &self.system_program.to_account_info(),
+            mint_len + token_metadata.tlv_size_of()?,
+        )?;
+
+        msg!("Creating collection metadata");
+        token_metadata_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                TokenMetadataInitialize {
+                    program_id: token_program.clone(),
+                    metadata: mint.clone(),
+                    update_authority: landlord.clone(),
+                    mint_authority: landlord.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            name,
+            symbol,
+            uri,
+        )?;
+
+        // editions join the group as members, the size grows with every listing
+        token_group_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                TokenGroupInitialize {
+                    program_id: token_program.clone(),
+                    group: mint.clone(),
+                    mint: mint.clone(),
+                    mint_authority: landlord.clone(),
+                },
+            ),
+            Some(self.landlord.key()),
+            u64::MAX,
+        )?;
+
+        mint_to(
+            CpiContext::new(
+                token_program.clone(),
+                MintTo {
+                    authority: landlord.clone(),
+                    mint: mint.clone(),
+                    to: self.collection_token_account.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+
+        // no more collection tokens, the same guarantee a Metaplex master edition gives
+        set_authority(
+            CpiContext::new(
+                token_program,
+                SetAuthority {
+                    current_authority: landlord,
+                    account_or_mint: mint,
+                },
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+        msg!("Minted collection NFT");
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::{
+        set_authority,
+        spl_token_2022::{
+            extension::ExtensionType, instruction::AuthorityType, state::Mint as Token2022Mint,
+        },
+        SetAuthority, Token2022,
+    },
+    token_2022_extensions::{
+        spl_pod::optional_keys::OptionalNonZeroPubkey,
+        spl_token_metadata_interface::state::TokenMetadata, token_member_initialize,
+        token_metadata_initialize, TokenMemberInitialize, TokenMetadataInitialize,
+    },
+    token_interface::{mint_to, Mint, MintTo, TokenAccount, TokenInterface},
+};
+
+use super::{listing_fields, update_metadata_fields};
+use crate::{
+    events::ListingCreated, fund_mint, Escrow, Landlord, PaymentSchedule, Property, PropertyDetails,
+    ScoringConfig,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(property_id: u64)]
+pub struct MakeEscrow<'info> {
+    #[account(mut)]
+    pub landlord: Signer<'info>,
+
+    #[account(
+        init,
+        payer=landlord,
+        space=8+Escrow::INIT_SPACE,
+        seeds=[b"escrow",edition_mint.key().as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds=[b"landlord",landlord.key().as_ref()],
+        bump=landlord_account.bump
+    )]
+    pub landlord_account: Account<'info, Landlord>,
+
+    #[account(
+        init,
+        payer=landlord,
+        space=8+Property::INIT_SPACE,
+        seeds=[b"property",edition_mint.key().as_ref()],
+        bump
+    )]
+    pub property: Account<'info, Property>,
+
+    // the landlord can close the mint again if the listing is taken down before it is taken
+    #[account(
+        init,
+        payer=landlord,
+        mint::decimals=0,
+        mint::authority=landlord,
+        mint::freeze_authority=landlord,
+        mint::token_program=token_program,
+        extensions::metadata_pointer::authority=landlord,
+        extensions::metadata_pointer::metadata_address=edition_mint,
+        extensions::group_member_pointer::authority=landlord,
+        extensions::group_member_pointer::member_address=edition_mint,
+        extensions::close_authority::authority=landlord,
+        seeds=[b"edition",collection_mint.key().as_ref(),property_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub edition_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer=landlord,
+        associated_token::mint=edition_mint,
+        associated_token::authority=escrow,
+        associated_token::token_program=token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        mint::token_program=token_program,
+        seeds=[b"collection_mint",landlord.key().as_ref()],
+        bump,
+    )]
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    // the listing can't ask for a score the scoring model never reaches
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token2022>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> MakeEscrow<'info> {
+    /// Mints the edition NFT with its metadata, the listing terms included, and adds it to the
+    /// landlord's collection. Runs after `init_escrow`, which sets the terms.
+    pub fn mint_edition_nft(
+        &mut self,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        let token_program = self.token_program.to_account_info();
+        let mint = self.edition_mint.to_account_info();
+        let landlord = self.landlord.to_account_info();
+        let fields = listing_fields(&self.escrow);
+
+        let mut token_metadata = TokenMetadata {
+            update_authority: OptionalNonZeroPubkey::try_from(Some(self.landlord.key()))?,
+            mint: mint.key(),
+            name: name.clone(),
+            symbol: symbol.clone(),
+            uri: uri.clone(),
+            additional_metadata: Vec::new(),
+        };
+        for (field, value) in fields.iter().cloned() {
+            token_metadata.update(field, value);
+        }
+        let mint_len = ExtensionType::try_calculate_account_len::<Token2022Mint>(&[
+            ExtensionType::MetadataPointer,
+            ExtensionType::GroupMemberPointer,
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::TokenGroupMember,
+        ])?;
+        fund_mint(
+            &mint,
+            &landlord,
+            &self.system_program.to_account_info(),
+            mint_len + token_metadata.tlv_size_of()?,
+        )?;
+
+        token_metadata_initialize(
+            CpiContext::new(
+                token_program.clone(),
+                TokenMetadataInitialize {
+                    program_id: token_program.clone(),
+                    metadata: mint.clone(),
+                    update_authority: landlord.clone(),
+                    mint_authority: landlord.clone(),
+                    mint: mint.clone(),
+                },
+            ),
+            name,
+            symbol,
+            uri,
+        )?;
+        update_metadata_fields(&token_program, &mint, &landlord, fields)?;
+        msg!("Added metadata to Item NFT");
+
+        token_member_initialize(CpiContext::new(
+            token_program.clone(),
+            TokenMemberInitialize {
+                program_id: token_program.clone(),
+                member: mint.clone(),
+                member_mint: mint.clone(),
+                member_mint_authority: landlord.clone(),
+                group: self.collection_mint.to_account_info(),
+                group_update_authority: landlord.clone(),
+            },
+        ))?;
+        msg!("Added Item NFT to the collection");
+
+        mint_to(
+            CpiContext::new(
+                token_program.clone(),
+                MintTo {
+                    authority: landlord.clone(),
+                    mint: mint.clone(),
+                    to: self.vault.to_account_info(),
+                },
+            ),
+            1,
+        )?;
+        set_authority(
+            CpiContext::new(
+                token_program,
+                SetAuthority {
+                    current_authority: landlord,
+                    account_or_mint: mint,
+                },
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )?;
+        msg!("Minted Item NFT");
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn init_escrow(
+        &mut self,
+        bumps: &MakeEscrowBumps,
+        property_id: u64,
+        monthly_rent: u64,
+        deposit_amount: u64,
+        late_fee_percent: u8,
+        min_renter_score: u16,
+        max_late_payment_percent: Option<u8>,
+        cancel_allowed_after: u16,
+        cancel_penalty_percent: u8,
+        months: u8,
+        schedule: PaymentSchedule,
+        yield_renter_share_percent: Option<u8>,
+        details: PropertyDetails,
+    ) -> Result<ListingCreated> {
+        self.escrow.open(
+            self.landlord.key(),
+            property_id,
+            self.payment_mint.key(),
+            bumps.escrow,
+            bumps.edition_mint,
+            monthly_rent,
+            deposit_amount,
+            late_fee_percent,
+            min_renter_score,
+            max_late_payment_percent,
+            cancel_allowed_after,
+            cancel_penalty_percent,
+            months,
+            schedule,
+            yield_renter_share_percent,
+            &self.scoring_config.model,
+        )?;
+        self.landlord_account.list_property(
+            &mut self.property,
+            self.landlord.key(),
+            self.edition_mint.key(),
+            details,
+            bumps.property,
+        )?;
+        Ok(self
+            .escrow
+            .listing_created(self.escrow.key(), self.edition_mint.key()))
+    }
+}
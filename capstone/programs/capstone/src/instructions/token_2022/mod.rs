@@ -0,0 +1,54 @@
+//! Token-2022 backend for the landlord collection and edition NFTs, compiled in place of the
+//! Metaplex contexts with the `token-2022-nft` feature. Each mint carries its own metadata through
+//! the MetadataPointer and TokenMetadata extensions, and edition mints join the landlord's
+//! collection through GroupPointer and GroupMemberPointer.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022_extensions::{
+    spl_token_metadata_interface::state::Field, token_metadata_update_field,
+    TokenMetadataUpdateField,
+};
+
+use crate::Escrow;
+
+pub mod init_landlord;
+pub mod make_escrow;
+pub mod refund_escrow;
+pub mod update_escrow;
+
+/// Metadata keys the listing terms are mirrored under on the edition NFT.
+pub const RENT_FIELD: &str = "monthly_rent";
+pub const TERM_FIELD: &str = "months";
+
+pub fn listing_fields(escrow: &Escrow) -> Vec<(Field, String)> {
+    vec![
+        (
+            Field::Key(RENT_FIELD.to_string()),
+            escrow.monthly_rent.to_string(),
+        ),
+        (Field::Key(TERM_FIELD.to_string()), escrow.months.to_string()),
+    ]
+}
+
+pub fn update_metadata_fields<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    update_authority: &AccountInfo<'info>,
+    fields: Vec<(Field, String)>,
+) -> Result<()> {
+    for (field, value) in fields {
+        token_metadata_update_field(
+            CpiContext::new(
+                token_program.clone(),
+                TokenMetadataUpdateField {
+                    program_id: token_program.clone(),
+                    metadata: mint.clone(),
+                    update_authority: update_authority.clone(),
+                },
+            ),
+            field,
+            value,
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::Token2022,
+    token_interface::{burn, close_account, Burn, CloseAccount, Mint, TokenAccount},
+};
+
+use crate::{events::ListingClosed, Escrow, Landlord, Property, PropertyStatus};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Refund<'info> {
+    #[account(mut)]
+    pub landlord: Signer<'info>,
+
+    #[account(
+        mut,
+        close=landlord,
+        seeds=[b"escrow",edition_mint.key().as_ref()],
+        bump=escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds=[b"landlord",landlord.key().as_ref()],
+        bump=landlord_account.bump
+    )]
+    pub landlord_account: Account<'info, Landlord>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        seeds=[b"property",edition_mint.key().as_ref()],
+        bump=property.bump
+    )]
+    pub property: Account<'info, Property>,
+
+    #[account(
+        mut,
+        mint::token_program=token_program,
+        seeds=[b"edition",collection_mint.key().as_ref(),escrow.property_id.to_le_bytes().as_ref()],
+        bump=escrow.edition_mint_bump
+    )]
+    pub edition_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=edition_mint,
+        associated_token::authority=escrow,
+        associated_token::token_program=token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program=token_program,
+        mint::decimals=0,
+    )]
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Refund<'info> {
+    /// Burns the edition NFT and closes the vault and the mint, which holds the metadata, back to
+    /// the landlord.
+    pub fn burn_nft_and_close_vault(&mut self) -> Result<ListingClosed> {
+        let edition_mint_key = self.edition_mint.key();
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[b"escrow", edition_mint_key.as_ref(), &[self.escrow.bump]]];
+
+        let burn_accounts = Burn {
+            mint: self.edition_mint.to_account_info(),
+            from: self.vault.to_account_info(),
+            authority: self.escrow.to_account_info(),
+        };
+        let burn_cpi = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            burn_accounts,
+            signer_seeds,
+        );
+        burn(burn_cpi, 1)?;
+
+        let close_vault_accounts = CloseAccount {
+            account: self.vault.to_account_info(),
+            destination: self.landlord.to_account_info(),
+            authority: self.escrow.to_account_info(),
+        };
+        let close_vault_cpi = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            close_vault_accounts,
+            signer_seeds,
+        );
+        close_account(close_vault_cpi)?;
+
+        let close_mint_accounts = CloseAccount {
+            account: self.edition_mint.to_account_info(),
+            destination: self.landlord.to_account_info(),
+            authority: self.landlord.to_account_info(),
+        };
+        let close_mint_cpi = CpiContext::new(
+            self.token_program.to_account_info(),
+            close_mint_accounts,
+        );
+        close_account(close_mint_cpi)?;
+        msg!("Burned Item NFT");
+
+        self.property.status = PropertyStatus::Delisted;
+        self.landlord_account.close_listing()?;
+        Ok(ListingClosed {
+            escrow: self.escrow.key(),
+            landlord: self.landlord.key(),
+            edition_mint: self.edition_mint.key(),
+        })
+    }
+}
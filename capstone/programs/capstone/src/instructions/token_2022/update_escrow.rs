@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::{
+        spl_token_2022::{
+            extension::{BaseStateWithExtensions, StateWithExtensions},
+            state::Mint as Token2022Mint,
+        },
+        Token2022,
+    },
+    token_2022_extensions::spl_token_metadata_interface::state::{Field, TokenMetadata},
+    token_interface::Mint,
+};
+
+use super::{listing_fields, update_metadata_fields};
+use crate::{events::ListingUpdated, fund_mint, Escrow, PaymentSchedule, ScoringConfig};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateEscrow<'info> {
+    // pays for the mint to grow when the metadata gets longer
+    #[account(mut)]
+    pub landlord: Signer<'info>,
+
+    // the escrow is closed once the listing is taken, so an open escrow is an untaken listing
+    #[account(
+        mut,
+        has_one=landlord,
+        seeds=[b"escrow",edition_mint.key().as_ref()],
+        bump=escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    // the edition mint holds its own metadata, the landlord is its update authority
+    #[account(
+        mut,
+        mint::token_program=token_program,
+    )]
+    pub edition_mint: InterfaceAccount<'info, Mint>,
+
+    // the listing can't ask for a score the scoring model never reaches
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> UpdateEscrow<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_escrow(
+        &mut self,
+        monthly_rent: u64,
+        deposit_amount: u64,
+        late_fee_percent: u8,
+        min_renter_score: u16,
+        max_late_payment_percent: Option<u8>,
+        cancel_allowed_after: u16,
+        cancel_penalty_percent: u8,
+        months: u8,
+        schedule: PaymentSchedule,
+        yield_renter_share_percent: Option<u8>,
+        uri: Option<String>,
+    ) -> Result<ListingUpdated> {
+        self.escrow.apply_terms(
+            monthly_rent,
+            deposit_amount,
+            late_fee_percent,
+            min_renter_score,
+            max_late_payment_percent,
+            cancel_allowed_after,
+            cancel_penalty_percent,
+            months,
+            schedule,
+            yield_renter_share_percent,
+            &self.scoring_config.model,
+        )?;
+        msg!("Listing terms updated");
+
+        let uri_updated = uri.is_some();
+        self.update_metadata(uri)?;
+
+        Ok(ListingUpdated {
+            escrow: self.escrow.key(),
+            landlord: self.landlord.key(),
+            edition_mint: self.edition_mint.key(),
+            monthly_rent,
+            deposit_amount,
+            months,
+            uri_updated,
+        })
+    }
+
+    /// Mirrors the new terms into the edition NFT's metadata, along with the uri when it changes.
+    pub fn update_metadata(&mut self, uri: Option<String>) -> Result<()> {
+        let mint = self.edition_mint.to_account_info();
+        let mut fields = listing_fields(&self.escrow);
+        if let Some(uri) = uri {
+            fields.push((Field::Uri, uri));
+        }
+
+        let mut token_metadata = {
+            let data = mint.try_borrow_data()?;
+            StateWithExtensions::<Token2022Mint>::unpack(&data)?
+                .get_variable_len_extension::<TokenMetadata>()?
+        };
+        let current_size = token_metadata.tlv_size_of()?;
+        for (field, value) in fields.iter().cloned() {
+            token_metadata.update(field, value);
+        }
+        let len = mint.data_len() - current_size + token_metadata.tlv_size_of()?;
+        fund_mint(
+            &mint,
+            &self.landlord.to_account_info(),
+            &self.system_program.to_account_info(),
+            len,
+        )?;
+
+        update_metadata_fields(
+            &self.token_program.to_account_info(),
+            &mint,
+            &self.landlord.to_account_info(),
+            fields,
+        )?;
+        msg!("Edition NFT metadata updated");
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+use crate::{error::ErrorCode, events::DepositToppedUp, Agreement, AgreementStatus};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TopUpDeposit<'info> {
+    // usually the renter, but anyone may refill the deposit after a drawdown
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.deposit_staked() @ ErrorCode::DepositStaked,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
+    )]
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=signer,
+        associated_token::token_program=payment_token_program
+    )]
+    pub signer_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub payment_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> TopUpDeposit<'info> {
+    pub fn top_up_deposit(&mut self) -> Result<DepositToppedUp> {
+        let amount = self
+            .agreement
+            .deposit_amount
+            .saturating_sub(self.deposit_vault.amount);
+        require!(amount > 0, ErrorCode::DepositNotDepleted);
+
+        let transfer_accounts = TransferChecked {
+            authority: self.signer.to_account_info(),
+            from: self.signer_payment_ata.to_account_info(),
+            to: self.deposit_vault.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+        };
+        let transfer_cpi = CpiContext::new(
+            self.payment_token_program.to_account_info(),
+            transfer_accounts,
+        );
+        transfer_checked(transfer_cpi, amount, self.payment_mint.decimals)?;
+
+        // a co-renter refilling a shared deposit gets it back pro rata at move-out
+        if let Some(index) = self.agreement.tenant_index(&self.signer.key()) {
+            let tenant = &mut self.agreement.tenants[index];
+            tenant.deposit_contribution = tenant
+                .deposit_contribution
+                .checked_add(amount)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+        msg!("Deposit topped up by {}", amount);
+
+        Ok(DepositToppedUp {
+            agreement: self.agreement.key(),
+            payer: self.signer.key(),
+            amount,
+            balance: self.agreement.deposit_amount,
+        })
+    }
+}
@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_2022::Token2022,
+    token_interface::{transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    burn_tenancy_token, error::ErrorCode, events::AgreementTransferred, Agreement,
+    AgreementStatus, Renter,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferAgreement<'info> {
+    // the nominated renter takes over, the landlord co-signs the handover
+    #[account(mut)]
+    pub incoming_renter: Signer<'info>,
+
+    pub landlord: Signer<'info>,
+
+    #[account(mut)]
+    pub renter: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=renter,
+        has_one=payment_mint,
+        constraint=agreement.status==AgreementStatus::Active @ ErrorCode::AgreementNotActive,
+        constraint=!agreement.is_shared() @ ErrorCode::SharedAgreement,
+        constraint=!agreement.deposit_staked() @ ErrorCode::DepositStaked,
+        constraint=!agreement.autopay @ ErrorCode::AutopayEnabled,
+        constraint=agreement.successor==Some(incoming_renter.key()) @ ErrorCode::NotNominated,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    #[account(
+        seeds=[b"renter",incoming_renter.key().as_ref()],
+        bump=incoming_renter_account.bump
+    )]
+    pub incoming_renter_account: Account<'info, Renter>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
+    )]
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=incoming_renter,
+        associated_token::token_program=payment_token_program
+    )]
+    pub incoming_renter_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer=incoming_renter,
+        associated_token::mint=payment_mint,
+        associated_token::authority=renter,
+        associated_token::token_program=payment_token_program
+    )]
+    pub renter_payment_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // only needed once a tenancy token was issued, so it can be burned from the renter's wallet
+    /// CHECK: Tenancy token mint, checked by its seeds
+    #[account(
+        mut,
+        seeds=[b"tenancy",agreement.key().as_ref()],
+        bump
+    )]
+    pub tenancy_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Renter's tenancy token account, the token program checks it holds the token
+    #[account(mut)]
+    pub renter_tenancy_ata: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TransferAgreement<'info> {
+    pub fn transfer_agreement(&mut self) -> Result<AgreementTransferred> {
+        self.incoming_renter_account.check_eligibility(
+            self.agreement.min_renter_score,
+            self.agreement.max_late_payment_percent,
+        )?;
+        // the outgoing renter pays every period that has started, the successor starts clean
+        let now = Clock::get()?.unix_timestamp;
+        self.agreement.accrue_late_fees(now)?;
+        require!(
+            self.agreement.amount_owed(now)? == 0,
+            ErrorCode::ArrearsOutstanding
+        );
+
+        // what is left of the outgoing deposit goes back, the incoming renter pays a full one
+        let deposit_refunded = self.deposit_vault.amount;
+        if deposit_refunded > 0 {
+            self.refund_outgoing_deposit(deposit_refunded)?;
+        }
+        self.transfer_incoming_deposit()?;
+        burn_tenancy_token(
+            &mut self.agreement,
+            &self.tenancy_mint,
+            &self.renter_tenancy_ata,
+            &self.token_2022_program,
+        )?;
+
+        // the NFT never moves, the agreement PDA that holds it is rebound to the new renter
+        let previous_renter = self.agreement.renter;
+        self.agreement.renter = self.incoming_renter.key();
+        self.agreement.successor = None;
+        msg!("Agreement transferred to {}", self.agreement.renter);
+
+        Ok(AgreementTransferred {
+            agreement: self.agreement.key(),
+            landlord: self.landlord.key(),
+            previous_renter,
+            new_renter: self.agreement.renter,
+            deposit_refunded,
+            deposit_amount: self.agreement.deposit_amount,
+        })
+    }
+
+    pub fn refund_outgoing_deposit(&mut self, amount: u64) -> Result<()> {
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"agreement",
+            self.agreement.edition_mint.as_ref(),
+            &[self.agreement.bump],
+        ]];
+        let transfer_accounts = TransferChecked {
+            authority: self.agreement.to_account_info(),
+            from: self.deposit_vault.to_account_info(),
+            to: self.renter_payment_ata.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+        };
+        let transfer_cpi = CpiContext::new_with_signer(
+            self.payment_token_program.to_account_info(),
+            transfer_accounts,
+            signer_seeds,
+        );
+        transfer_checked(transfer_cpi, amount, self.payment_mint.decimals)
+    }
+
+    pub fn transfer_incoming_deposit(&mut self) -> Result<()> {
+        let transfer_accounts = TransferChecked {
+            authority: self.incoming_renter.to_account_info(),
+            from: self.incoming_renter_payment_ata.to_account_info(),
+            to: self.deposit_vault.to_account_info(),
+            mint: self.payment_mint.to_account_info(),
+        };
+        let transfer_cpi = CpiContext::new(
+            self.payment_token_program.to_account_info(),
+            transfer_accounts,
+        );
+        transfer_checked(
+            transfer_cpi,
+            self.agreement.deposit_amount,
+            self.payment_mint.decimals,
+        )
+    }
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use anchor_spl::stake::{deactivate_stake, DeactivateStake, Stake};
+
+use crate::{error::ErrorCode, events::StakeDeactivated, Agreement};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UnstakeDeposit<'info> {
+    pub signer: Signer<'info>,
+
+    #[account(
+        constraint=agreement.deposit_staked() @ ErrorCode::DepositNotStaked,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    /// CHECK: Native stake account the agreement delegated the deposit to
+    #[account(
+        mut,
+        seeds=[b"stake",agreement.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub stake_program: Program<'info, Stake>,
+}
+
+impl<'info> UnstakeDeposit<'info> {
+    // either party can start the cooldown, e.g. the landlord before terminating
+    pub fn unstake_deposit(&mut self) -> Result<StakeDeactivated> {
+        require!(
+            self.signer.key() == self.agreement.renter
+                || self.signer.key() == self.agreement.landlord,
+            ErrorCode::Unauthorized
+        );
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"agreement",
+            self.agreement.edition_mint.as_ref(),
+            &[self.agreement.bump],
+        ]];
+        let deactivate_accounts = DeactivateStake {
+            stake: self.stake_account.to_account_info(),
+            staker: self.agreement.to_account_info(),
+            clock: self.clock.to_account_info(),
+        };
+        let deactivate_cpi = CpiContext::new_with_signer(
+            self.stake_program.to_account_info(),
+            deactivate_accounts,
+            signer_seeds,
+        );
+        deactivate_stake(deactivate_cpi)?;
+        msg!("Deposit stake deactivating");
+
+        Ok(StakeDeactivated {
+            agreement: self.agreement.key(),
+            stake_account: self.stake_account.key(),
+        })
+    }
+}
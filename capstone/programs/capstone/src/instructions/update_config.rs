@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, Config};
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=admin,
+        seeds=[b"config"],
+        bump=config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_config(
+        &mut self,
+        arbiter: Pubkey,
+        claim_window: i64,
+        response_window: i64,
+        arbitration_window: i64,
+        repair_sla: i64,
+        rent_withhold_percent: u8,
+    ) -> Result<()> {
+        require!(
+            claim_window > 0 && response_window > 0 && arbitration_window > 0,
+            ErrorCode::InvalidConfig
+        );
+        require!(
+            repair_sla > 0 && rent_withhold_percent <= 100,
+            ErrorCode::InvalidConfig
+        );
+        self.config.arbiter = arbiter;
+        self.config.claim_window = claim_window;
+        self.config.response_window = response_window;
+        self.config.arbitration_window = arbitration_window;
+        self.config.repair_sla = repair_sla;
+        self.config.rent_withhold_percent = rent_withhold_percent;
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+
+use anchor_spl::metadata::{
+    mpl_token_metadata::types::DataV2, update_metadata_accounts_v2, Metadata, MetadataAccount,
+    UpdateMetadataAccountsV2,
+};
+
+use crate::{
+    error::ErrorCode, events::ListingUpdated, Escrow, PaymentSchedule, ScoringConfig,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateEscrow<'info> {
+    pub landlord: Signer<'info>,
+
+    // the escrow is closed once the listing is taken, so an open escrow is an untaken listing
+    #[account(
+        mut,
+        has_one=landlord,
+        seeds=[b"escrow",edition_mint.key().as_ref()],
+        bump=escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// CHECK: Only used to derive the escrow and metadata addresses
+    pub edition_mint: UncheckedAccount<'info>,
+
+    // the listing can't ask for a score the scoring model never reaches
+    #[account(
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+
+    // only needed when the uri changes, the landlord is the metadata update authority
+    #[account(
+        mut,
+        seeds=[b"metadata",Metadata::id().as_ref(),edition_mint.key().as_ref()],
+        bump,
+        seeds::program=Metadata::id()
+    )]
+    pub metadata: Option<Account<'info, MetadataAccount>>,
+
+    pub token_metadata_program: Option<Program<'info, Metadata>>,
+}
+
+impl<'info> UpdateEscrow<'info> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_escrow(
+        &mut self,
+        monthly_rent: u64,
+        deposit_amount: u64,
+        late_fee_percent: u8,
+        min_renter_score: u16,
+        max_late_payment_percent: Option<u8>,
+        cancel_allowed_after: u16,
+        cancel_penalty_percent: u8,
+        months: u8,
+        schedule: PaymentSchedule,
+        yield_renter_share_percent: Option<u8>,
+        uri: Option<String>,
+    ) -> Result<ListingUpdated> {
+        self.escrow.apply_terms(
+            monthly_rent,
+            deposit_amount,
+            late_fee_percent,
+            min_renter_score,
+            max_late_payment_percent,
+            cancel_allowed_after,
+            cancel_penalty_percent,
+            months,
+            schedule,
+            yield_renter_share_percent,
+            &self.scoring_config.model,
+        )?;
+        msg!("Listing terms updated");
+
+        let uri_updated = uri.is_some();
+        if let Some(uri) = uri {
+            self.update_uri(uri)?;
+        }
+
+        Ok(ListingUpdated {
+            escrow: self.escrow.key(),
+            landlord: self.landlord.key(),
+            edition_mint: self.edition_mint.key(),
+            monthly_rent,
+            deposit_amount,
+            months,
+            uri_updated,
+        })
+    }
+
+    pub fn update_uri(&mut self, uri: String) -> Result<()> {
+        let (Some(metadata), Some(token_metadata_program)) =
+            (&self.metadata, &self.token_metadata_program)
+        else {
+            return err!(ErrorCode::MetadataAccountsMissing);
+        };
+
+        // everything but the uri is written back as it is
+        let data = DataV2 {
+            name: metadata.name.clone(),
+            symbol: metadata.symbol.clone(),
+            uri,
+            seller_fee_basis_points: metadata.seller_fee_basis_points,
+            creators: metadata.creators.clone(),
+            collection: metadata.collection.clone(),
+            uses: metadata.uses.clone(),
+        };
+        let update_accounts = UpdateMetadataAccountsV2 {
+            metadata: metadata.to_account_info(),
+            update_authority: self.landlord.to_account_info(),
+        };
+        let update_cpi = CpiContext::new(token_metadata_program.to_account_info(), update_accounts);
+        update_metadata_accounts_v2(update_cpi, None, Some(data), None, None)?;
+        msg!("Edition NFT uri updated");
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+use crate::{ScoringConfig, ScoringModel};
+
+#[derive(Accounts)]
+pub struct UpdateScoringConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one=admin,
+        seeds=[b"scoring_config"],
+        bump=scoring_config.bump
+    )]
+    pub scoring_config: Account<'info, ScoringConfig>,
+}
+
+impl<'info> UpdateScoringConfig<'info> {
+    pub fn update_scoring_config(&mut self, model: ScoringModel) -> Result<()> {
+        model.validate()?;
+        self.scoring_config.model = model;
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use anchor_lang::{prelude::*, solana_program::sysvar};
+use anchor_spl::{
+    stake::{withdraw, Stake, Withdraw},
+    token_interface::{sync_native, Mint, SyncNative, TokenAccount, TokenInterface},
+};
+
+use crate::{error::ErrorCode, events::YieldDistributed, Agreement};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    // anyone can crank the withdrawal once the stake has cooled down
+    pub signer: Signer<'info>,
+
+    #[account(mut)]
+    pub landlord: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub renter: SystemAccount<'info>,
+
+    // paid the stake reserve, which may have been before the agreement changed hands
+    #[account(mut)]
+    pub stake_funder: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        has_one=landlord,
+        has_one=renter,
+        has_one=stake_funder,
+        has_one=payment_mint,
+        constraint=agreement.deposit_staked() @ ErrorCode::DepositNotStaked,
+        seeds=[b"agreement",agreement.edition_mint.as_ref()],
+        bump=agreement.bump
+    )]
+    pub agreement: Account<'info, Agreement>,
+
+    /// CHECK: Native stake account the agreement delegated the deposit to
+    #[account(
+        mut,
+        seeds=[b"stake",agreement.key().as_ref()],
+        bump
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint=payment_mint,
+        associated_token::authority=agreement,
+        associated_token::token_program=payment_token_program
+    )]
+    pub deposit_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mint::token_program=payment_token_program,
+    )]
+    pub payment_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Stake history sysvar
+    #[account(address=sysvar::stake_history::ID)]
+    pub stake_history: UncheckedAccount<'info>,
+
+    pub clock: Sysvar<'info, Clock>,
+    pub stake_program: Program<'info, Stake>,
+    pub payment_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> WithdrawStake<'info> {
+    pub fn withdraw_stake(&mut self) -> Result<YieldDistributed> {
+        let reserve = self.agreement.stake_reserve;
+        let available = self.stake_account.lamports().saturating_sub(reserve);
+        // a slashed stake comes back short and the deposit absorbs the loss
+        let principal = available.min(self.agreement.staked_amount);
+        let rewards = available - principal;
+
+        let renter_share = self.agreement.yield_renter_share_percent.unwrap_or(0);
+        let renter_reward = rewards
+            .checked_mul(u64::from(renter_share))
+            .and_then(|v| v.checked_div(100))
+            .ok_or(ErrorCode::Overflow)?;
+        let landlord_reward = rewards - renter_reward;
+
+        if landlord_reward > 0 {
+            self.withdraw_to(self.landlord.to_account_info(), landlord_reward)?;
+        }
+        if principal > 0 {
+            self.withdraw_to(self.deposit_vault.to_account_info(), principal)?;
+            self.rewrap_deposit()?;
+        }
+        if renter_reward > 0 {
+            self.withdraw_to(self.renter.to_account_info(), renter_reward)?;
+        }
+        // whoever funded the rent reserves gets the rest and the account closes
+        let remaining = self.stake_account.lamports();
+        if remaining > 0 {
+            self.withdraw_to(self.stake_funder.to_account_info(), remaining)?;
+        }
+
+        self.agreement.staked_amount = 0;
+        self.agreement.stake_reserve = 0;
+        msg!(
+            "Returned {} to the deposit, rewards {} to renter and {} to landlord",
+            principal,
+            renter_reward,
+            landlord_reward
+        );
+
+        Ok(YieldDistributed {
+            agreement: self.agreement.key(),
+            principal,
+            renter_reward,
+            landlord_reward,
+        })
+    }
+
+    pub fn withdraw_to(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"agreement",
+            self.agreement.edition_mint.as_ref(),
+            &[self.agreement.bump],
+        ]];
+        let withdraw_accounts = Withdraw {
+            stake: self.stake_account.to_account_info(),
+            withdrawer: self.agreement.to_account_info(),
+            to,
+            clock: self.clock.to_account_info(),
+            stake_history: self.stake_history.to_account_info(),
+        };
+        let withdraw_cpi = CpiContext::new_with_signer(
+            self.stake_program.to_account_info(),
+            withdraw_accounts,
+            signer_seeds,
+        );
+        withdraw(withdraw_cpi, amount, None)
+    }
+
+    // lamports sent to a native token account only count once the token balance is synced
+    pub fn rewrap_deposit(&mut self) -> Result<()> {
+        let sync_accounts = SyncNative {
+            account: self.deposit_vault.to_account_info(),
+        };
+        let sync_cpi = CpiContext::new(
+            self.payment_token_program.to_account_info(),
+            sync_accounts,
+        );
+        sync_native(sync_cpi)
+    }
+}
@@ -1,16 +1,37 @@
+#![allow(unexpected_cfgs)]
+// the IDL instructions #[program] generates at the crate root call the deprecated
+// AccountInfo::realloc; everything written by hand opts back into the lint
+#![allow(deprecated)]
+// the CPI helpers and IDL builder it adds under cpi and idl-build repeat every instruction's
+// arguments, which runs past clippy's limit for the listing instructions
+#![cfg_attr(
+    any(feature = "cpi", feature = "idl-build"),
+    allow(clippy::too_many_arguments)
+)]
+
+#[warn(deprecated)]
 pub mod constants;
+#[warn(deprecated)]
 pub mod error;
+#[warn(deprecated)]
+pub mod events;
+#[warn(deprecated)]
 pub mod instructions;
+#[warn(deprecated)]
+pub mod scoring;
+#[warn(deprecated)]
 pub mod state;
 
 use anchor_lang::prelude::*;
 
 pub use constants::*;
 pub use instructions::*;
+pub use scoring::*;
 pub use state::*;
 
 declare_id!("3ECCL8btDKSnNYEgu15UZca4epL2PdHqDEYdY9UBvmcP");
 
+#[warn(deprecated)]
 #[program]
 pub mod capstone {
     use super::*;
@@ -21,7 +42,64 @@ pub mod capstone {
         symbol: String,
         uri: String,
     ) -> Result<()> {
-        let _ = ctx.accounts.mint_master_edition_nft(name, symbol, uri);
+        ctx.accounts.init_landlord_account(&ctx.bumps)?;
+        ctx.accounts.mint_master_edition_nft(name, symbol, uri)
+    }
+
+    pub fn init_config(
+        ctx: Context<InitConfig>,
+        arbiter: Pubkey,
+        claim_window: i64,
+        response_window: i64,
+        arbitration_window: i64,
+        repair_sla: i64,
+        rent_withhold_percent: u8,
+    ) -> Result<()> {
+        ctx.accounts.init_config(
+            &ctx.bumps,
+            arbiter,
+            claim_window,
+            response_window,
+            arbitration_window,
+            repair_sla,
+            rent_withhold_percent,
+        )?;
+        Ok(())
+    }
+
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        arbiter: Pubkey,
+        claim_window: i64,
+        response_window: i64,
+        arbitration_window: i64,
+        repair_sla: i64,
+        rent_withhold_percent: u8,
+    ) -> Result<()> {
+        ctx.accounts.update_config(
+            arbiter,
+            claim_window,
+            response_window,
+            arbitration_window,
+            repair_sla,
+            rent_withhold_percent,
+        )?;
+        Ok(())
+    }
+
+    pub fn init_scoring_config(
+        ctx: Context<InitScoringConfig>,
+        model: ScoringModel,
+    ) -> Result<()> {
+        ctx.accounts.init_scoring_config(&ctx.bumps, model)?;
+        Ok(())
+    }
+
+    pub fn update_scoring_config(
+        ctx: Context<UpdateScoringConfig>,
+        model: ScoringModel,
+    ) -> Result<()> {
+        ctx.accounts.update_scoring_config(model)?;
         Ok(())
     }
 
@@ -31,59 +109,363 @@ pub mod capstone {
         Ok(())
     }
 
+    pub fn attest_reputation(ctx: Context<AttestReputation>) -> Result<()> {
+        let attested = ctx.accounts.attest_reputation(&ctx.bumps)?;
+        emit_cpi!(attested);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_escrow(
         ctx: Context<MakeEscrow>,
+        property_id: u64,
         monthly_rent: u64,
         deposit_amount: u64,
         late_fee_percent: u8,
         min_renter_score: u16,
+        max_late_payment_percent: Option<u8>,
         cancel_allowed_after: u16,
         cancel_penalty_percent: u8,
         months: u8,
+        schedule: PaymentSchedule,
+        yield_renter_share_percent: Option<u8>,
+        property_details: PropertyDetails,
         nft_name: String,
         nft_symbol: String,
         nft_uri: String,
     ) -> Result<()> {
-        ctx.accounts.init_escrow(
+        let listing_created = ctx.accounts.init_escrow(
             &ctx.bumps,
+            property_id,
             monthly_rent,
             deposit_amount,
             late_fee_percent,
             min_renter_score,
+            max_late_payment_percent,
             cancel_allowed_after,
             cancel_penalty_percent,
             months,
+            schedule,
+            yield_renter_share_percent,
+            property_details,
         )?;
         msg!("Init Escrow PDA!");
         ctx.accounts
             .mint_edition_nft(nft_name, nft_symbol, nft_uri)?;
         msg!("Minted Printable NFT PDA!");
+        emit_cpi!(listing_created);
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_escrow(
+        ctx: Context<UpdateEscrow>,
+        monthly_rent: u64,
+        deposit_amount: u64,
+        late_fee_percent: u8,
+        min_renter_score: u16,
+        max_late_payment_percent: Option<u8>,
+        cancel_allowed_after: u16,
+        cancel_penalty_percent: u8,
+        months: u8,
+        schedule: PaymentSchedule,
+        yield_renter_share_percent: Option<u8>,
+        nft_uri: Option<String>,
+    ) -> Result<()> {
+        let listing_updated = ctx.accounts.update_escrow(
+            monthly_rent,
+            deposit_amount,
+            late_fee_percent,
+            min_renter_score,
+            max_late_payment_percent,
+            cancel_allowed_after,
+            cancel_penalty_percent,
+            months,
+            schedule,
+            yield_renter_share_percent,
+            nft_uri,
+        )?;
+        emit_cpi!(listing_updated);
         Ok(())
     }
 
     pub fn close_escrow(ctx: Context<Refund>) -> Result<()> {
-        ctx.accounts.burn_nft_and_close_vault()?;
+        let listing_closed = ctx.accounts.burn_nft_and_close_vault()?;
         msg!("Close Escrow");
+        emit_cpi!(listing_closed);
         Ok(())
     }
 
     pub fn take_escrow(ctx: Context<Take>) -> Result<()> {
-        ctx.accounts.init_agreement_pda(&ctx.bumps)?;
+        ctx.accounts.check_renter_eligibility()?;
+        let agreement_taken = ctx.accounts.init_agreement_pda(&ctx.bumps)?;
         msg!("Init Agreement PDA");
         ctx.accounts.transfer_deposit()?;
         msg!("Transfer Deposit");
         ctx.accounts.transfer_nft()?;
         msg!("Transfer NFT");
+        emit_cpi!(agreement_taken);
+        Ok(())
+    }
+
+    pub fn issue_tenancy_token(ctx: Context<IssueTenancyToken>) -> Result<()> {
+        let issued = ctx.accounts.issue_tenancy_token(&ctx.bumps)?;
+        emit_cpi!(issued);
         Ok(())
     }
 
     pub fn pay_rent(ctx: Context<MonthlyRent>) -> Result<()> {
-        ctx.accounts.monthly_rent()?;
+        let (rent_paid, drawdown, score_changed, withheld) = ctx.accounts.monthly_rent()?;
+        emit_cpi!(rent_paid);
+        if let Some(drawdown) = drawdown {
+            emit_cpi!(drawdown);
+        }
+        emit_cpi!(score_changed);
+        if let Some(withheld) = withheld {
+            emit_cpi!(withheld);
+        }
+        Ok(())
+    }
+
+    pub fn pay_partial(ctx: Context<PayPartial>, amount: u64) -> Result<()> {
+        let (applied, settled) = ctx.accounts.pay_partial(amount)?;
+        for (rent_paid, score_changed) in settled {
+            emit_cpi!(rent_paid);
+            emit_cpi!(score_changed);
+        }
+        emit_cpi!(applied);
+        Ok(())
+    }
+
+    pub fn add_co_renter(ctx: Context<AddCoRenter>, share_percent: u8) -> Result<()> {
+        let added = ctx.accounts.add_co_renter(share_percent)?;
+        emit_cpi!(added);
+        Ok(())
+    }
+
+    pub fn pay_share(ctx: Context<PayShare>) -> Result<()> {
+        let (share_paid, score_changed, rent_paid) = ctx.accounts.pay_share()?;
+        emit_cpi!(share_paid);
+        emit_cpi!(score_changed);
+        if let Some(rent_paid) = rent_paid {
+            emit_cpi!(rent_paid);
+        }
+        Ok(())
+    }
+
+    pub fn set_autopay(ctx: Context<SetAutopay>, enabled: bool) -> Result<()> {
+        let changed = ctx.accounts.set_autopay(enabled)?;
+        emit_cpi!(changed);
+        Ok(())
+    }
+
+    pub fn collect_rent(ctx: Context<CollectRent>) -> Result<()> {
+        let (rent_paid, drawdown, score_changed, tip_paid) = ctx.accounts.collect_rent()?;
+        emit_cpi!(rent_paid);
+        if let Some(drawdown) = drawdown {
+            emit_cpi!(drawdown);
+        }
+        emit_cpi!(score_changed);
+        emit_cpi!(tip_paid);
+        Ok(())
+    }
+
+    pub fn pay_from_deposit(ctx: Context<PayFromDeposit>) -> Result<()> {
+        let (rent_paid, drawdown, score_changed) = ctx.accounts.pay_from_deposit()?;
+        emit_cpi!(rent_paid);
+        emit_cpi!(drawdown);
+        emit_cpi!(score_changed);
+        Ok(())
+    }
+
+    pub fn top_up_deposit(ctx: Context<TopUpDeposit>) -> Result<()> {
+        let topped_up = ctx.accounts.top_up_deposit()?;
+        emit_cpi!(topped_up);
+        Ok(())
+    }
+
+    pub fn crank_missed_payment<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CrankMissedPayment<'info>>,
+    ) -> Result<()> {
+        let (rent_paid, drawdown, score_changes) = ctx
+            .accounts
+            .crank_missed_payment(ctx.remaining_accounts)?;
+        emit_cpi!(rent_paid);
+        emit_cpi!(drawdown);
+        for score_changed in score_changes {
+            emit_cpi!(score_changed);
+        }
         Ok(())
     }
 
     pub fn close_agreement_transfer_nft(ctx: Context<CloseAgreement>) -> Result<()> {
-        ctx.accounts.close_agreement()?;
+        let (move_out, drawdown, score_changed, stake_deactivated, ticket_settled) =
+            ctx.accounts.close_agreement(&ctx.bumps)?;
+        if let Some(drawdown) = drawdown {
+            emit_cpi!(drawdown);
+        }
+        if let Some(score_changed) = score_changed {
+            emit_cpi!(score_changed);
+        }
+        if let Some(stake_deactivated) = stake_deactivated {
+            emit_cpi!(stake_deactivated);
+        }
+        if let Some(ticket_settled) = ticket_settled {
+            emit_cpi!(ticket_settled);
+        }
+        emit_cpi!(move_out);
+        Ok(())
+    }
+
+    pub fn propose_deduction(
+        ctx: Context<ProposeDeduction>,
+        amount: u64,
+        evidence_uri: String,
+    ) -> Result<()> {
+        let proposed = ctx
+            .accounts
+            .propose_deduction(&ctx.bumps, amount, evidence_uri)?;
+        emit_cpi!(proposed);
+        Ok(())
+    }
+
+    pub fn respond_to_claim(ctx: Context<RespondToClaim>, accept: bool) -> Result<()> {
+        ctx.accounts.check_claim_open()?;
+        if accept {
+            let accepted = ctx.accounts.accept_claim()?;
+            emit_cpi!(accepted);
+        } else {
+            let disputed = ctx.accounts.dispute_claim()?;
+            emit_cpi!(disputed);
+        }
+        Ok(())
+    }
+
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, landlord_award: u64) -> Result<()> {
+        let resolved = ctx.accounts.resolve_dispute(landlord_award)?;
+        emit_cpi!(resolved);
+        Ok(())
+    }
+
+    pub fn settle_deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleDeposit<'info>>,
+    ) -> Result<()> {
+        let (settled, closed) = ctx.accounts.settle_deposit(ctx.remaining_accounts)?;
+        emit_cpi!(settled);
+        emit_cpi!(closed);
+        Ok(())
+    }
+
+    pub fn rate_counterparty(
+        ctx: Context<RateCounterparty>,
+        rating: u8,
+        review_hash: [u8; 32],
+    ) -> Result<()> {
+        let rating_left = ctx.accounts.rate_counterparty(rating, review_hash)?;
+        emit_cpi!(rating_left);
+        Ok(())
+    }
+
+    pub fn open_ticket(
+        ctx: Context<OpenTicket>,
+        description_hash: [u8; 32],
+        urgency: Urgency,
+    ) -> Result<()> {
+        let opened = ctx
+            .accounts
+            .open_ticket(&ctx.bumps, description_hash, urgency)?;
+        emit_cpi!(opened);
+        Ok(())
+    }
+
+    pub fn fund_repair(ctx: Context<FundRepair>, contractor: Pubkey, budget: u64) -> Result<()> {
+        let funded = ctx.accounts.fund_repair(contractor, budget)?;
+        emit_cpi!(funded);
+        Ok(())
+    }
+
+    pub fn confirm_repair(ctx: Context<ConfirmRepair>) -> Result<()> {
+        let confirmed = ctx.accounts.confirm_repair()?;
+        emit_cpi!(confirmed);
+        Ok(())
+    }
+
+    pub fn propose_renewal(
+        ctx: Context<ProposeRenewal>,
+        extra_periods: u8,
+        rent_amount: u64,
+        deposit_amount: u64,
+    ) -> Result<()> {
+        let proposed = ctx.accounts.propose_renewal(
+            &ctx.bumps,
+            extra_periods,
+            rent_amount,
+            deposit_amount,
+        )?;
+        emit_cpi!(proposed);
+        Ok(())
+    }
+
+    pub fn accept_renewal(
+        ctx: Context<AcceptRenewal>,
+        extra_periods: u8,
+        rent_amount: u64,
+        deposit_amount: u64,
+    ) -> Result<()> {
+        let accepted = ctx
+            .accounts
+            .accept_renewal(extra_periods, rent_amount, deposit_amount)?;
+        emit_cpi!(accepted);
+        Ok(())
+    }
+
+    pub fn nominate_successor(
+        ctx: Context<NominateSuccessor>,
+        successor: Option<Pubkey>,
+    ) -> Result<()> {
+        let nominated = ctx.accounts.nominate_successor(successor)?;
+        emit_cpi!(nominated);
+        Ok(())
+    }
+
+    pub fn transfer_agreement(ctx: Context<TransferAgreement>) -> Result<()> {
+        let transferred = ctx.accounts.transfer_agreement()?;
+        emit_cpi!(transferred);
+        Ok(())
+    }
+
+    pub fn terminate_agreement<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TerminateAgreement<'info>>,
+    ) -> Result<()> {
+        let (terminated, score_changes, ticket_settled, closed) = ctx
+            .accounts
+            .terminate_agreement(ctx.remaining_accounts)?;
+        emit_cpi!(terminated);
+        for score_changed in score_changes {
+            emit_cpi!(score_changed);
+        }
+        if let Some(ticket_settled) = ticket_settled {
+            emit_cpi!(ticket_settled);
+        }
+        emit_cpi!(closed);
+        Ok(())
+    }
+
+    pub fn stake_deposit(ctx: Context<StakeDeposit>) -> Result<()> {
+        let staked = ctx.accounts.stake_deposit(&ctx.bumps)?;
+        emit_cpi!(staked);
+        Ok(())
+    }
+
+    pub fn unstake_deposit(ctx: Context<UnstakeDeposit>) -> Result<()> {
+        let deactivated = ctx.accounts.unstake_deposit()?;
+        emit_cpi!(deactivated);
+        Ok(())
+    }
+
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        let distributed = ctx.accounts.withdraw_stake()?;
+        emit_cpi!(distributed);
         Ok(())
     }
 }
@@ -0,0 +1,146 @@
+//! Renter credit-score model. Every change to `Renter.score` goes through here so the
+//! rules can be tested without a validator.
+
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+const SECONDS_IN_DAY: i64 = 24 * 60 * 60;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub struct ScoringModel {
+    pub start_score: i16,                // 2 bytes - Score a new renter account starts with
+    pub on_time_reward: i16,             // 2 bytes - Added for rent paid before the grace period ends
+    pub late_penalty: i16,               // 2 bytes - Taken for every late period
+    pub late_penalty_per_day: i16,       // 2 bytes - Taken on top for each full day past the due date
+    pub early_termination_penalty: i16,  // 2 bytes - Taken when the renter leaves before cancel_allowed_after
+    pub eviction_penalty: i16,           // 2 bytes - Taken per unpaid period when the landlord terminates
+    pub completion_bonus: i16,           // 2 bytes - Added when a fully paid lease ends
+    pub min_score: i16,                  // 2 bytes
+    pub max_score: i16,                  // 2 bytes
+}
+
+impl ScoringModel {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_score <= self.start_score && self.start_score <= self.max_score,
+            ErrorCode::InvalidScoringModel
+        );
+        require!(
+            self.on_time_reward >= 0
+                && self.late_penalty >= 0
+                && self.late_penalty_per_day >= 0
+                && self.early_termination_penalty >= 0
+                && self.eviction_penalty >= 0
+                && self.completion_bonus >= 0,
+            ErrorCode::InvalidScoringModel
+        );
+        Ok(())
+    }
+
+    pub fn on_time_payment(&self, score: i16) -> i16 {
+        self.clamp(i64::from(score) + i64::from(self.on_time_reward))
+    }
+
+    /// `seconds_late` counts from the due date, so the grace period still adds daily penalties.
+    pub fn late_payment(&self, score: i16, seconds_late: i64) -> i16 {
+        let days_late = seconds_late.max(0) / SECONDS_IN_DAY;
+        let penalty = i64::from(self.late_penalty)
+            .saturating_add(days_late.saturating_mul(i64::from(self.late_penalty_per_day)));
+        self.clamp(i64::from(score).saturating_sub(penalty))
+    }
+
+    pub fn early_termination(&self, score: i16) -> i16 {
+        self.clamp(i64::from(score) - i64::from(self.early_termination_penalty))
+    }
+
+    pub fn eviction(&self, score: i16, unpaid_periods: u16) -> i16 {
+        let penalty = i64::from(self.eviction_penalty) * i64::from(unpaid_periods);
+        self.clamp(i64::from(score) - penalty)
+    }
+
+    pub fn completion(&self, score: i16) -> i16 {
+        self.clamp(i64::from(score) + i64::from(self.completion_bonus))
+    }
+
+    fn clamp(&self, score: i64) -> i16 {
+        // bounds fit in i16, so the clamped value always does too
+        score.clamp(i64::from(self.min_score), i64::from(self.max_score)) as i16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model() -> ScoringModel {
+        ScoringModel {
+            start_score: 500,
+            on_time_reward: 5,
+            late_penalty: 10,
+            late_penalty_per_day: 2,
+            early_termination_penalty: 30,
+            eviction_penalty: 50,
+            completion_bonus: 25,
+            min_score: 0,
+            max_score: 1000,
+        }
+    }
+
+    #[test]
+    fn on_time_payment_adds_reward_up_to_max() {
+        assert_eq!(model().on_time_payment(500), 505);
+        assert_eq!(model().on_time_payment(998), 1000);
+    }
+
+    #[test]
+    fn late_penalty_scales_with_full_days_late() {
+        let model = model();
+        assert_eq!(model.late_payment(500, 0), 490);
+        assert_eq!(model.late_payment(500, SECONDS_IN_DAY - 1), 490);
+        assert_eq!(model.late_payment(500, 3 * SECONDS_IN_DAY), 484);
+        // a clock running behind the due date never turns into a reward
+        assert_eq!(model.late_payment(500, -SECONDS_IN_DAY), 490);
+    }
+
+    #[test]
+    fn late_penalty_is_clamped_to_min() {
+        assert_eq!(model().late_payment(5, i64::MAX), 0);
+    }
+
+    #[test]
+    fn eviction_penalty_is_per_unpaid_period() {
+        assert_eq!(model().eviction(500, 3), 350);
+        assert_eq!(model().eviction(500, u16::MAX), 0);
+    }
+
+    #[test]
+    fn lease_end_adjustments() {
+        assert_eq!(model().early_termination(500), 470);
+        assert_eq!(model().completion(500), 525);
+        assert_eq!(model().completion(990), 1000);
+    }
+
+    #[test]
+    fn validate_rejects_start_outside_bounds_and_negative_steps() {
+        assert!(model().validate().is_ok());
+        assert!(ScoringModel {
+            start_score: 1001,
+            ..model()
+        }
+        .validate()
+        .is_err());
+        assert!(ScoringModel {
+            min_score: 600,
+            ..model()
+        }
+        .validate()
+        .is_err());
+        assert!(ScoringModel {
+            late_penalty: -1,
+            ..model()
+        }
+        .validate()
+        .is_err());
+    }
+}
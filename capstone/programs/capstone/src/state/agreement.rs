@@ -1,18 +1,337 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::ErrorCode, PaymentSchedule, MAX_TENANTS};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum AgreementStatus {
+    Active,
+    // NFT is back with the landlord, deposit waits for the claim window
+    MoveOut,
+    // landlord filed a deduction claim, deposit waits for it to be settled
+    DepositClaimed,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace, PartialEq, Eq, Debug)]
+pub struct Tenant {
+    pub wallet: Pubkey,            // 32 bytes
+    pub share_percent: u8,         // 1 byte - Share of rent and deposit this tenant is responsible for
+    pub deposit_contribution: u64, // 8 bytes - Part of the deposit this tenant put in, refunded pro rata
+    pub share_paid: u64,           // 8 bytes - Paid toward their share of the oldest unpaid period
+    pub share_settled: bool,       // 1 byte - Their share of the oldest unpaid period is in
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Agreement {
     pub landlord: Pubkey,           // 32 bytes
     pub renter: Pubkey, // 32 bytes we need so we can use renter for fn like close agreement we can check if its right account calling it
+    pub payment_mint: Pubkey, // 32 bytes - Mint rent and deposit are paid in
+    pub edition_mint: Pubkey, // 32 bytes - Property NFT, one agreement per property at a time
     pub start_date: i64, // 8 bytes - Unix timestamp (seconds)
     pub end_date: i64,  // 8 bytes
-    pub rent_amount: u64, // 8 bytes in lamports
-    pub deposit_amount: u64, // 8 bytes in lamports
+    pub rent_amount: u64, // 8 bytes in smallest unit of payment_mint
+    pub deposit_amount: u64, // 8 bytes in smallest unit of payment_mint
     pub late_fee_percent: u8, // 1 byte
     pub cancel_allowed_after: u16, // 2 bytes in months
     pub cancel_penalty_percent: u8, // 1 byte
     pub payments_made: u16, // 2 bytes - Number of successful payments
+    pub periods: u8,        // 1 byte - Number of rent periods in the lease
+    pub schedule: PaymentSchedule, // 9 bytes
+    pub missed_payments: u16, // 2 bytes - Periods settled from the deposit after grace expired
+    pub status: AgreementStatus, // 1 byte
+    pub move_out_date: i64, // 8 bytes - Set when the renter hands the property back
+    pub yield_renter_share_percent: Option<u8>, // 2 bytes - Copied from the escrow
+    pub staked_amount: u64, // 8 bytes - Deposit principal delegated to the stake account, 0 while liquid
+    pub stake_reserve: u64, // 8 bytes - Stake and unwrap account rent the renter funded, refunded on withdrawal
+    pub stake_funder: Pubkey, // 32 bytes - Renter who paid the stake reserve, it goes back to them
+    pub period_paid: u64,   // 8 bytes - Paid so far toward the oldest unpaid period
+    pub late_fees_owed: u64, // 8 bytes - Late fees assessed on unpaid periods past their grace window
+    pub late_fees_assessed: u16, // 2 bytes - Periods before this index have had their late fee assessed
+    pub autopay: bool,      // 1 byte - Renter approved the agreement to pull rent from their token account
+    #[max_len(MAX_TENANTS)]
+    pub tenants: Vec<Tenant>, // 4 + 50 * MAX_TENANTS bytes - Empty for a sole renter, the lead renter comes first
+    pub min_renter_score: u16, // 2 bytes - Copied from the escrow, a successor has to meet it too
+    pub max_late_payment_percent: Option<u8>, // 2 bytes - Copied from the escrow
+    pub successor: Option<Pubkey>, // 33 bytes - Renter nominated to take over the lease
+    pub tenancy_token: bool, // 1 byte - Current renter holds the soulbound tenancy token
     pub bump: u8,
-    pub deposit_bump: u8,
+}
+
+impl Agreement {
+    pub fn late_fee(&self) -> Result<u64> {
+        self.rent_amount
+            .checked_mul(u64::from(self.late_fee_percent))
+            .and_then(|v| v.checked_div(100))
+            .ok_or(ErrorCode::Overflow.into())
+    }
+
+    pub fn deposit_staked(&self) -> bool {
+        self.staked_amount > 0
+    }
+
+    /// Zero-based index of the next unpaid period, failing once every period of the lease is paid.
+    pub fn current_period(&self) -> Result<u16> {
+        require!(
+            self.payments_made < u16::from(self.periods),
+            ErrorCode::RentFullyPaid
+        );
+        Ok(self.payments_made)
+    }
+
+    /// Adds the late fee of every unpaid period whose grace window has run out, once per period.
+    pub fn accrue_late_fees(&mut self, now: i64) -> Result<()> {
+        let fee = self.late_fee()?;
+        let mut period = self.late_fees_assessed.max(self.payments_made);
+        while period < u16::from(self.periods)
+            && now > self.schedule.late_after(self.start_date, period)?
+        {
+            self.late_fees_owed = self
+                .late_fees_owed
+                .checked_add(fee)
+                .ok_or(ErrorCode::Overflow)?;
+            period += 1;
+            self.late_fees_assessed = period;
+        }
+        Ok(())
+    }
+
+    /// Whether the oldest unpaid period had its late fee assessed.
+    pub fn current_period_late(&self) -> bool {
+        self.payments_made < self.late_fees_assessed
+    }
+
+    /// Whether rent is split between co-renters, each paying their own share.
+    pub fn is_shared(&self) -> bool {
+        !self.tenants.is_empty()
+    }
+
+    pub fn tenant_index(&self, wallet: &Pubkey) -> Option<usize> {
+        self.tenants.iter().position(|tenant| tenant.wallet == *wallet)
+    }
+
+    /// Tenant's part of `amount`; the lead renter takes the rounding remainder so shares add up.
+    pub fn tenant_share(&self, index: usize, amount: u64) -> Result<u64> {
+        let share_of = |tenant: &Tenant| {
+            amount
+                .checked_mul(u64::from(tenant.share_percent))
+                .and_then(|v| v.checked_div(100))
+                .ok_or(ErrorCode::Overflow)
+        };
+        if index > 0 {
+            return Ok(share_of(&self.tenants[index])?);
+        }
+        let mut others = 0u64;
+        for tenant in &self.tenants[1..] {
+            others = others.checked_add(share_of(tenant)?).ok_or(ErrorCode::Overflow)?;
+        }
+        amount.checked_sub(others).ok_or(ErrorCode::Overflow.into())
+    }
+
+    /// What a tenant still owes on the oldest unpaid period. Only tenants paying after the
+    /// grace window carry their part of the late fee.
+    pub fn tenant_balance(&self, index: usize, late: bool) -> Result<u64> {
+        let tenant = &self.tenants[index];
+        if tenant.share_settled {
+            return Ok(0);
+        }
+        let mut due = self.tenant_share(index, self.rent_amount)?;
+        if late {
+            due = due
+                .checked_add(self.tenant_share(index, self.late_fee()?)?)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+        Ok(due.saturating_sub(tenant.share_paid))
+    }
+
+    /// Co-renters' part of a deposit refund in tenant order, pro rata to what each put in.
+    /// The lead renter gets whatever is left.
+    pub fn co_renter_refunds(&self, amount: u64) -> Result<Vec<u64>> {
+        let mut contributed = 0u64;
+        for tenant in &self.tenants {
+            contributed = contributed
+                .checked_add(tenant.deposit_contribution)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+        let mut refunds = Vec::new();
+        for tenant in self.tenants.iter().skip(1) {
+            let refund = (u128::from(amount) * u128::from(tenant.deposit_contribution))
+                .checked_div(u128::from(contributed))
+                .unwrap_or(0);
+            refunds.push(u64::try_from(refund).map_err(|_| ErrorCode::Overflow)?);
+        }
+        Ok(refunds)
+    }
+
+    /// Rent plus any assessed late fee still owed on the oldest unpaid period.
+    pub fn period_balance(&self) -> Result<u64> {
+        let fee = if self.current_period_late() {
+            self.late_fee()?
+        } else {
+            0
+        };
+        self.rent_amount
+            .checked_add(fee)
+            .and_then(|v| v.checked_sub(self.period_paid))
+            .ok_or(ErrorCode::Overflow.into())
+    }
+
+    /// Marks the oldest unpaid period as paid and clears its entries from the arrears ledger.
+    pub fn settle_period(&mut self) -> Result<()> {
+        if self.current_period_late() {
+            self.late_fees_owed = self
+                .late_fees_owed
+                .checked_sub(self.late_fee()?)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+        self.period_paid = 0;
+        for tenant in self.tenants.iter_mut() {
+            tenant.share_paid = 0;
+            tenant.share_settled = false;
+        }
+        self.payments_made = self
+            .payments_made
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Rent still owed for the rest of the lease, which is what auto-pay needs approved.
+    pub fn remaining_rent(&self) -> Result<u64> {
+        let remaining = u64::from(self.periods).saturating_sub(u64::from(self.payments_made));
+        self.rent_amount
+            .checked_mul(remaining)
+            .map(|v| v.saturating_sub(self.period_paid))
+            .ok_or(ErrorCode::Overflow.into())
+    }
+
+    /// Rent for every period that has started but is unpaid, plus assessed late fees,
+    /// minus what was already paid toward the oldest one.
+    pub fn amount_owed(&self, now: i64) -> Result<u64> {
+        let mut started = 0u64;
+        for period in self.payments_made..u16::from(self.periods) {
+            if now < self.schedule.period_start(self.start_date, period)? {
+                break;
+            }
+            started += 1;
+        }
+        self.rent_amount
+            .checked_mul(started)
+            .and_then(|v| v.checked_add(self.late_fees_owed))
+            .map(|v| v.saturating_sub(self.period_paid))
+            .ok_or(ErrorCode::Overflow.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+    const MONTH: i64 = 30 * DAY;
+
+    fn agreement() -> Agreement {
+        Agreement {
+            landlord: Pubkey::default(),
+            renter: Pubkey::default(),
+            payment_mint: Pubkey::default(),
+            edition_mint: Pubkey::default(),
+            start_date: 0,
+            end_date: 3 * MONTH,
+            rent_amount: 1_000,
+            deposit_amount: 2_000,
+            late_fee_percent: 10,
+            cancel_allowed_after: 1,
+            cancel_penalty_percent: 5,
+            payments_made: 0,
+            periods: 3,
+            schedule: PaymentSchedule {
+                period_seconds: MONTH as u32,
+                grace_seconds: (3 * DAY) as u32,
+                calendar_month: false,
+            },
+            missed_payments: 0,
+            status: AgreementStatus::Active,
+            move_out_date: 0,
+            yield_renter_share_percent: None,
+            staked_amount: 0,
+            stake_reserve: 0,
+            stake_funder: Pubkey::default(),
+            period_paid: 0,
+            late_fees_owed: 0,
+            late_fees_assessed: 0,
+            autopay: false,
+            tenants: Vec::new(),
+            min_renter_score: 0,
+            max_late_payment_percent: None,
+            successor: None,
+            tenancy_token: false,
+            bump: 0,
+        }
+    }
+
+    fn tenant(share_percent: u8) -> Tenant {
+        Tenant {
+            wallet: Pubkey::new_unique(),
+            share_percent,
+            deposit_contribution: 0,
+            share_paid: 0,
+            share_settled: false,
+        }
+    }
+
+    #[test]
+    fn late_fees_are_assessed_once_per_overdue_period() {
+        let mut agreement = agreement();
+        agreement.accrue_late_fees(MONTH + 3 * DAY).unwrap();
+        assert_eq!(agreement.late_fees_owed, 0);
+
+        agreement.accrue_late_fees(2 * MONTH + 4 * DAY).unwrap();
+        agreement.accrue_late_fees(2 * MONTH + 5 * DAY).unwrap();
+        assert_eq!(agreement.late_fees_owed, 200);
+        assert_eq!(agreement.late_fees_assessed, 2);
+        assert_eq!(agreement.amount_owed(2 * MONTH + 5 * DAY).unwrap(), 3_200);
+    }
+
+    #[test]
+    fn installments_settle_the_oldest_period_with_its_fee() {
+        let mut agreement = agreement();
+        agreement.accrue_late_fees(MONTH + 4 * DAY).unwrap();
+        agreement.period_paid = 600;
+        assert_eq!(agreement.period_balance().unwrap(), 500);
+        assert_eq!(agreement.amount_owed(MONTH + 4 * DAY).unwrap(), 1_500);
+
+        agreement.settle_period().unwrap();
+        assert_eq!(agreement.payments_made, 1);
+        assert_eq!(agreement.period_paid, 0);
+        assert_eq!(agreement.late_fees_owed, 0);
+        // the next period is on time, so it owes rent only
+        assert_eq!(agreement.period_balance().unwrap(), 1_000);
+    }
+
+    #[test]
+    fn shares_add_up_to_the_rent_and_carry_their_own_late_fee() {
+        let mut agreement = agreement();
+        agreement.rent_amount = 1_001;
+        agreement.tenants = vec![tenant(34), tenant(33), tenant(33)];
+        assert_eq!(agreement.tenant_share(1, 1_001).unwrap(), 330);
+        assert_eq!(agreement.tenant_share(0, 1_001).unwrap(), 341);
+
+        agreement.tenants[1].share_paid = 300;
+        assert_eq!(agreement.tenant_balance(1, false).unwrap(), 30);
+        // 33% of the 100 late fee on top of the unpaid share
+        assert_eq!(agreement.tenant_balance(1, true).unwrap(), 63);
+
+        agreement.tenants[0].deposit_contribution = 600;
+        agreement.tenants[1].deposit_contribution = 700;
+        agreement.tenants[2].deposit_contribution = 700;
+        assert_eq!(agreement.co_renter_refunds(1_000).unwrap(), vec![350, 350]);
+
+        agreement.tenants[2].share_settled = true;
+        assert_eq!(agreement.tenant_balance(2, true).unwrap(), 0);
+        agreement.settle_period().unwrap();
+        assert!(agreement
+            .tenants
+            .iter()
+            .all(|tenant| tenant.share_paid == 0 && !tenant.share_settled));
+    }
 }
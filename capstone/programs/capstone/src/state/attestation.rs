@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::Renter;
+
+/// Snapshot of a renter's reputation that other programs can read without trusting the renter.
+/// Only this program can write the account, so `attester` and the PDA address vouch for it.
+#[account]
+#[derive(InitSpace)]
+pub struct Attestation {
+    pub renter: Pubkey,        // 32 bytes - Wallet the reputation belongs to
+    pub attester: Pubkey,      // 32 bytes - Program that recorded the figures
+    pub score: i16,            // 2 bytes - Credit score at attestation time
+    pub total_payments: u32,   // 4 bytes - Count of successful payments
+    pub late_payments: u32,    // 4 bytes - Count of late payments
+    pub completed_leases: u32, // 4 bytes - Count of agreements paid through to the end
+    pub attested_at: i64,      // 8 bytes - When the figures were copied from the renter account
+    pub bump: u8,
+}
+
+impl Attestation {
+    /// Copies the renter account's figures, stamped with `now`.
+    pub fn record(&mut self, renter: Pubkey, account: &Renter, now: i64, bump: u8) {
+        self.renter = renter;
+        self.attester = crate::ID;
+        self.score = account.score;
+        self.total_payments = account.total_payments;
+        self.late_payments = account.late_payments;
+        self.completed_leases = account.completed_leases;
+        self.attested_at = now;
+        self.bump = bump;
+    }
+
+    /// Share of the attested payments that were late, in whole percent.
+    pub fn late_payment_percent(&self) -> u64 {
+        if self.total_payments == 0 {
+            return 0;
+        }
+        u64::from(self.late_payments) * 100 / u64::from(self.total_payments)
+    }
+}
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,             // 32 bytes - Can update the marketplace settings
+    pub arbiter: Pubkey,           // 32 bytes - Settles disputed deposit deductions
+    pub claim_window: i64,         // 8 bytes - Seconds after move-out the landlord can claim deductions
+    pub response_window: i64,      // 8 bytes - Seconds the renter has to accept or dispute a claim
+    pub arbitration_window: i64,   // 8 bytes - Seconds the arbiter has to settle a dispute
+    pub repair_sla: i64,           // 8 bytes - Seconds the landlord has to fund an urgent repair
+    pub rent_withhold_percent: u8, // 1 byte - Share of rent the renter may hold back past the SLA
+    pub bump: u8,
+}
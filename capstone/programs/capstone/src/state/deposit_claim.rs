@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum ClaimStatus {
+    Proposed,
+    Accepted,
+    Disputed,
+    Resolved,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DepositClaim {
+    pub agreement: Pubkey,    // 32 bytes
+    pub amount: u64,          // 8 bytes - Deduction the landlord asked for
+    #[max_len(200)]
+    pub evidence_uri: String, // 4 + 200 bytes - Photos, invoices etc. backing the claim
+    pub status: ClaimStatus,  // 1 byte
+    pub proposed_at: i64,     // 8 bytes
+    pub respond_by: i64,      // 8 bytes - Unanswered claims are accepted after this
+    pub arbitrate_by: i64,    // 8 bytes - Disputes the arbiter leaves open are dropped after this
+    pub awarded_amount: u64,  // 8 bytes - Deduction the landlord gets once accepted or resolved
+    pub bump: u8,
+}
@@ -1,16 +1,143 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::ErrorCode, events::ListingCreated, PaymentSchedule, ScoringModel};
+
 #[account]
 #[derive(InitSpace)]
 pub struct Escrow {
     pub landlord: Pubkey, // 32 bytes - Wallet address of landlord (i think dont need this)
+    pub property_id: u64, // 8 bytes - Landlord chosen id of the unit, part of the edition mint seeds
+    pub payment_mint: Pubkey, // 32 bytes - Mint rent and deposit are paid in (native mint for SOL)
     pub monthly_rent: u64, // 8 bytes - Rent amount in smallest token unit (e.g., USDC 6 decimals)
     pub deposit_amount: u64, // 8 bytes - Security deposit
     pub late_fee_percent: u8, // 1 byte - % late fee
     pub min_renter_score: u16, // 2 bytes - Required renter score
+    pub max_late_payment_percent: Option<u8>, // 2 bytes - Optional cap on renter's late/total payments
     pub months: u8,
+    pub schedule: PaymentSchedule, // 9 bytes - Rent period, grace window and calendar-month option
     pub cancel_allowed_after: u16,  // 2 bytes - In months
     pub cancel_penalty_percent: u8, // 1 byte - % penalty
+    pub yield_renter_share_percent: Option<u8>, // 2 bytes - Renter's % of staking rewards, None disables deposit staking
     pub bump: u8,
     pub edition_mint_bump: u8,
 }
+
+impl Escrow {
+    /// Bounds the listing terms, checked whenever the landlord sets them.
+    pub fn validate(&self, scoring: &ScoringModel) -> Result<()> {
+        require!(self.months > 0, ErrorCode::InvalidSchedule);
+        self.schedule.validate()?;
+        require!(
+            self.monthly_rent > 0
+                && self.late_fee_percent <= 100
+                && self.cancel_penalty_percent <= 100
+                && self.cancel_allowed_after <= u16::from(self.months),
+            ErrorCode::InvalidEscrowTerms
+        );
+        // the whole lease, deposit and every month's rent, has to fit in a token amount
+        let lease_total = self
+            .monthly_rent
+            .checked_mul(u64::from(self.months))
+            .and_then(|rent| rent.checked_add(self.deposit_amount));
+        require!(
+            self.deposit_amount > 0 && lease_total.is_some(),
+            ErrorCode::InvalidEscrowTerms
+        );
+        // a listing asking for more than the model's top score could never be taken
+        require!(
+            i32::from(self.min_renter_score) <= i32::from(scoring.max_score),
+            ErrorCode::InvalidEscrowTerms
+        );
+        if let Some(percent) = self.max_late_payment_percent {
+            require!(percent <= 100, ErrorCode::InvalidEscrowTerms);
+        }
+        if let Some(percent) = self.yield_renter_share_percent {
+            require!(percent <= 100, ErrorCode::InvalidEscrowTerms);
+        }
+        Ok(())
+    }
+
+    /// Fills in a new listing of the landlord's unit `property_id` and checks its terms. Both NFT
+    /// backends open their escrow this way once the edition NFT is in the vault.
+    #[allow(clippy::too_many_arguments)]
+    pub fn open(
+        &mut self,
+        landlord: Pubkey,
+        property_id: u64,
+        payment_mint: Pubkey,
+        bump: u8,
+        edition_mint_bump: u8,
+        monthly_rent: u64,
+        deposit_amount: u64,
+        late_fee_percent: u8,
+        min_renter_score: u16,
+        max_late_payment_percent: Option<u8>,
+        cancel_allowed_after: u16,
+        cancel_penalty_percent: u8,
+        months: u8,
+        schedule: PaymentSchedule,
+        yield_renter_share_percent: Option<u8>,
+        scoring: &ScoringModel,
+    ) -> Result<()> {
+        self.landlord = landlord;
+        self.property_id = property_id;
+        self.payment_mint = payment_mint;
+        self.bump = bump;
+        self.edition_mint_bump = edition_mint_bump;
+        self.apply_terms(
+            monthly_rent,
+            deposit_amount,
+            late_fee_percent,
+            min_renter_score,
+            max_late_payment_percent,
+            cancel_allowed_after,
+            cancel_penalty_percent,
+            months,
+            schedule,
+            yield_renter_share_percent,
+            scoring,
+        )
+    }
+
+    /// Replaces the listing terms of an untaken listing and checks them.
+    #[allow(clippy::too_many_arguments)]
+    pub fn apply_terms(
+        &mut self,
+        monthly_rent: u64,
+        deposit_amount: u64,
+        late_fee_percent: u8,
+        min_renter_score: u16,
+        max_late_payment_percent: Option<u8>,
+        cancel_allowed_after: u16,
+        cancel_penalty_percent: u8,
+        months: u8,
+        schedule: PaymentSchedule,
+        yield_renter_share_percent: Option<u8>,
+        scoring: &ScoringModel,
+    ) -> Result<()> {
+        self.monthly_rent = monthly_rent;
+        self.deposit_amount = deposit_amount;
+        self.late_fee_percent = late_fee_percent;
+        self.min_renter_score = min_renter_score;
+        self.max_late_payment_percent = max_late_payment_percent;
+        self.cancel_allowed_after = cancel_allowed_after;
+        self.cancel_penalty_percent = cancel_penalty_percent;
+        self.months = months;
+        self.schedule = schedule;
+        self.yield_renter_share_percent = yield_renter_share_percent;
+        self.validate(scoring)
+    }
+
+    pub fn listing_created(&self, escrow: Pubkey, edition_mint: Pubkey) -> ListingCreated {
+        ListingCreated {
+            escrow,
+            landlord: self.landlord,
+            edition_mint,
+            property_id: self.property_id,
+            payment_mint: self.payment_mint,
+            monthly_rent: self.monthly_rent,
+            deposit_amount: self.deposit_amount,
+            months: self.months,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::ErrorCode, Property, PropertyDetails, PropertyStatus};
+
+#[account]
+#[derive(InitSpace)]
+pub struct Landlord {
+    pub listing_count: u32,     // 4 bytes - Listings open for renters to take
+    pub active_agreements: u32, // 4 bytes - Agreements currently running
+    pub completed_leases: u32,  // 4 bytes - Agreements paid through to the end
+    pub rating_total: u32,      // 4 bytes - Sum of the ratings renters left
+    pub rating_count: u32,      // 4 bytes - Number of ratings renters left
+    pub bump: u8,
+}
+
+impl Landlord {
+    pub fn new(bump: u8) -> Self {
+        Landlord {
+            listing_count: 0,
+            active_agreements: 0,
+            completed_leases: 0,
+            rating_total: 0,
+            rating_count: 0,
+            bump,
+        }
+    }
+
+    /// Puts the unit behind `edition_mint` up for rent and counts it as an open listing.
+    pub fn list_property(
+        &mut self,
+        property: &mut Property,
+        landlord: Pubkey,
+        edition_mint: Pubkey,
+        details: PropertyDetails,
+        bump: u8,
+    ) -> Result<()> {
+        details.validate()?;
+        *property = Property {
+            landlord,
+            edition_mint,
+            details,
+            status: PropertyStatus::Listed,
+            bump,
+        };
+        self.open_listing()
+    }
+
+    pub fn open_listing(&mut self) -> Result<()> {
+        self.listing_count = self
+            .listing_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    pub fn close_listing(&mut self) -> Result<()> {
+        self.listing_count = self
+            .listing_count
+            .checked_sub(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// A listing was taken, it stops counting as open and starts counting as an agreement.
+    pub fn start_agreement(&mut self) -> Result<()> {
+        self.close_listing()?;
+        self.active_agreements = self
+            .active_agreements
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    pub fn add_rating(&mut self, rating: u8) -> Result<()> {
+        self.rating_total = self
+            .rating_total
+            .checked_add(u32::from(rating))
+            .ok_or(ErrorCode::Overflow)?;
+        self.rating_count = self
+            .rating_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    pub fn end_agreement(&mut self, completed: bool) -> Result<()> {
+        self.active_agreements = self
+            .active_agreements
+            .checked_sub(1)
+            .ok_or(ErrorCode::Overflow)?;
+        if completed {
+            self.completed_leases = self
+                .completed_leases
+                .checked_add(1)
+                .ok_or(ErrorCode::Overflow)?;
+        }
+        Ok(())
+    }
+}
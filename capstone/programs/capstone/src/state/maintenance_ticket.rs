@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum Urgency {
+    Routine,
+    Urgent,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum TicketStatus {
+    Open,
+    Funded,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct MaintenanceTicket {
+    pub agreement: Pubkey,          // 32 bytes
+    pub landlord: Pubkey,           // 32 bytes
+    pub renter: Pubkey,             // 32 bytes
+    pub payment_mint: Pubkey,       // 32 bytes
+    pub description_hash: [u8; 32], // 32 bytes - Hash of the off-chain problem description
+    pub urgency: Urgency,           // 1 byte
+    pub status: TicketStatus,       // 1 byte
+    pub opened_at: i64,             // 8 bytes
+    pub contractor: Option<Pubkey>, // 1 + 32 bytes - Paid the repair budget once the renter confirms
+    pub budget: u64,                // 8 bytes - Held in the ticket vault until the repair is confirmed
+    pub withheld: u64,              // 8 bytes - Rent held in the ticket vault until the repair is confirmed
+    pub bump: u8,
+}
+
+impl MaintenanceTicket {
+    /// An urgent ticket the landlord has not funded within `repair_sla` seconds lets the renter
+    /// hold back part of their rent. Funding stops the clock, the contractor is not the landlord's
+    /// to hurry.
+    pub fn overdue(&self, now: i64, repair_sla: i64) -> Result<bool> {
+        if self.urgency != Urgency::Urgent || self.status != TicketStatus::Open {
+            return Ok(false);
+        }
+        let deadline = self
+            .opened_at
+            .checked_add(repair_sla)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(now > deadline)
+    }
+}
@@ -1,8 +1,38 @@
 pub mod renter;
 pub use renter::*;
 
+pub mod landlord;
+pub use landlord::*;
+
+pub mod property;
+pub use property::*;
+
 pub mod escrow;
 pub use escrow::*;
 
 pub mod agreement;
 pub use agreement::*;
+
+pub mod schedule;
+pub use schedule::*;
+
+pub mod config;
+pub use config::*;
+
+pub mod deposit_claim;
+pub use deposit_claim::*;
+
+pub mod renewal;
+pub use renewal::*;
+
+pub mod scoring_config;
+pub use scoring_config::*;
+
+pub mod attestation;
+pub use attestation::*;
+
+pub mod review;
+pub use review::*;
+
+pub mod maintenance_ticket;
+pub use maintenance_ticket::*;
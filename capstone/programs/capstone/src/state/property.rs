@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum UnitType {
+    Apartment,
+    House,
+    Studio,
+    Room,
+    Other,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub enum PropertyStatus {
+    Listed,
+    Leased,
+    // listing closed or the lease ended, the NFT is back with the landlord
+    Delisted,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub struct PropertyDetails {
+    pub address_hash: [u8; 32], // 32 bytes - Hash of the street address, kept off-chain
+    pub unit_type: UnitType,    // 1 byte
+    pub capacity: u8,           // 1 byte - Most people the unit is let to
+}
+
+impl PropertyDetails {
+    pub fn validate(&self) -> Result<()> {
+        require!(self.capacity > 0, ErrorCode::InvalidPropertyDetails);
+        require!(
+            self.address_hash != [0; 32],
+            ErrorCode::InvalidPropertyDetails
+        );
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Property {
+    pub landlord: Pubkey,         // 32 bytes
+    pub edition_mint: Pubkey,     // 32 bytes - Edition NFT that stands for the unit
+    pub details: PropertyDetails, // 34 bytes
+    pub status: PropertyStatus,   // 1 byte
+    pub bump: u8,
+}
@@ -0,0 +1,12 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Renewal {
+    pub agreement: Pubkey,   // 32 bytes
+    pub extra_periods: u8,   // 1 byte - Rent periods added to the lease
+    pub rent_amount: u64,    // 8 bytes - Rent for the renewed lease
+    pub deposit_amount: u64, // 8 bytes - Deposit held for the renewed lease
+    pub proposed_at: i64,    // 8 bytes - The renter can accept within RENEWAL_WINDOW of this
+    pub bump: u8,
+}
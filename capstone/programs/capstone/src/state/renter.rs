@@ -1,10 +1,130 @@
 use anchor_lang::prelude::*;
 
+use crate::{error::ErrorCode, events::ScoreChanged, ScoringModel};
+
 #[account]
 #[derive(InitSpace)]
 pub struct Renter {
-    pub score: i16,                   // 2 bytes - Credit score (0–1000 range)
+    pub score: i16,                   // 2 bytes - Credit score, bounded by the scoring config
     pub total_payments: u32,          // 4 bytes - Count of successful payments
     pub late_payments: u32,           // 4 bytes - Count of late payments
+    pub completed_leases: u32,        // 4 bytes - Count of agreements paid through to the end
+    pub rating_total: u32,            // 4 bytes - Sum of the ratings landlords left
+    pub rating_count: u32,            // 4 bytes - Number of ratings landlords left
     pub bump: u8,
-}
\ No newline at end of file
+}
+
+impl Renter {
+    /// Records a rent payment and scores it, `seconds_late` past the due date when it came in late.
+    pub fn record_payment(
+        &mut self,
+        wallet: Pubkey,
+        model: &ScoringModel,
+        seconds_late: Option<i64>,
+    ) -> Result<ScoreChanged> {
+        let previous_score = self.score;
+        self.total_payments = self
+            .total_payments
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        if let Some(seconds_late) = seconds_late {
+            self.score = model.late_payment(previous_score, seconds_late);
+            self.late_payments = self
+                .late_payments
+                .checked_add(1)
+                .ok_or(ErrorCode::Overflow)?;
+        } else {
+            self.score = model.on_time_payment(previous_score);
+        }
+        Ok(ScoreChanged {
+            renter: wallet,
+            previous_score,
+            new_score: self.score,
+        })
+    }
+
+    /// Records the overdue periods of a terminated agreement as late payments and takes the
+    /// eviction penalty for each of the `unpaid_periods`.
+    pub fn record_eviction(
+        &mut self,
+        wallet: Pubkey,
+        model: &ScoringModel,
+        overdue_periods: u16,
+        unpaid_periods: u16,
+    ) -> Result<ScoreChanged> {
+        let previous_score = self.score;
+        // overdue periods count as late payments like the crank records them
+        self.total_payments = self
+            .total_payments
+            .checked_add(u32::from(overdue_periods))
+            .ok_or(ErrorCode::Overflow)?;
+        self.late_payments = self
+            .late_payments
+            .checked_add(u32::from(overdue_periods))
+            .ok_or(ErrorCode::Overflow)?;
+        self.score = model.eviction(previous_score, unpaid_periods);
+        msg!(
+            "Renter score reduced by {}",
+            i32::from(previous_score) - i32::from(self.score)
+        );
+        Ok(ScoreChanged {
+            renter: wallet,
+            previous_score,
+            new_score: self.score,
+        })
+    }
+
+    /// Loads a co-renter's account passed as a remaining account, checking it is `wallet`'s.
+    pub fn load_tenant<'info>(
+        info: &'info AccountInfo<'info>,
+        wallet: &Pubkey,
+    ) -> Result<Account<'info, Renter>> {
+        let renter = Account::<Renter>::try_from(info)?;
+        let expected = Pubkey::create_program_address(
+            &[b"renter", wallet.as_ref(), &[renter.bump]],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::TenantAccountMismatch)?;
+        require_keys_eq!(info.key(), expected, ErrorCode::TenantAccountMismatch);
+        Ok(renter)
+    }
+
+    pub fn add_rating(&mut self, rating: u8) -> Result<()> {
+        self.rating_total = self
+            .rating_total
+            .checked_add(u32::from(rating))
+            .ok_or(ErrorCode::Overflow)?;
+        self.rating_count = self
+            .rating_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Share of the renter's recorded payments that were late, in whole percent.
+    pub fn late_payment_percent(&self) -> u64 {
+        if self.total_payments == 0 {
+            return 0;
+        }
+        u64::from(self.late_payments) * 100 / u64::from(self.total_payments)
+    }
+
+    /// Checks the renter against a listing's minimum score and optional late-payment cap.
+    pub fn check_eligibility(
+        &self,
+        min_score: u16,
+        max_late_payment_percent: Option<u8>,
+    ) -> Result<()> {
+        require!(
+            i32::from(self.score) >= i32::from(min_score),
+            ErrorCode::RenterScoreTooLow
+        );
+        if let Some(max_late_percent) = max_late_payment_percent {
+            require!(
+                self.late_payment_percent() <= u64::from(max_late_percent),
+                ErrorCode::TooManyLatePayments
+            );
+        }
+        Ok(())
+    }
+}
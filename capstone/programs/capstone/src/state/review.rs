@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+/// Ratings the two parties of an agreement leave each other after move-out. Opened by
+/// `close_agreement_transfer_nft` so it outlives the agreement account.
+#[account]
+#[derive(InitSpace)]
+pub struct Review {
+    pub agreement: Pubkey,              // 32 bytes
+    pub landlord: Pubkey,               // 32 bytes
+    pub renter: Pubkey,                 // 32 bytes - Lead renter at move-out
+    pub review_by: i64,                 // 8 bytes - Ratings are no longer accepted after this
+    pub landlord_rating: Option<u8>,    // 2 bytes - 1-5, left by the renter for the landlord
+    pub landlord_review_hash: [u8; 32], // 32 bytes - Hash of the renter's written review
+    pub renter_rating: Option<u8>,      // 2 bytes - 1-5, left by the landlord for the renter
+    pub renter_review_hash: [u8; 32],   // 32 bytes - Hash of the landlord's written review
+    pub bump: u8,
+}
@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+const SECONDS_IN_DAY: i64 = 24 * 60 * 60;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace, PartialEq, Eq, Debug)]
+pub struct PaymentSchedule {
+    pub period_seconds: u32, // 4 bytes - Length of one rent period (unused when calendar_month is set)
+    pub grace_seconds: u32,  // 4 bytes - Time after a due date before rent counts as late
+    pub calendar_month: bool, // 1 byte - Rent falls due on the start day of every calendar month
+}
+
+impl PaymentSchedule {
+    pub fn validate(&self) -> Result<()> {
+        // shortest calendar month is 28 days, grace has to end before the next period is due
+        let period_seconds = if self.calendar_month {
+            28 * SECONDS_IN_DAY
+        } else {
+            i64::from(self.period_seconds)
+        };
+        require!(period_seconds > 0, ErrorCode::InvalidSchedule);
+        require!(
+            i64::from(self.grace_seconds) < period_seconds,
+            ErrorCode::InvalidSchedule
+        );
+        Ok(())
+    }
+
+    /// Timestamp at which rent for the zero-based `period` is due (the end of that period).
+    pub fn due_date(&self, start_date: i64, period: u16) -> Result<i64> {
+        let periods = i64::from(period) + 1;
+        if self.calendar_month {
+            return add_months(start_date, periods);
+        }
+        i64::from(self.period_seconds)
+            .checked_mul(periods)
+            .and_then(|offset| start_date.checked_add(offset))
+            .ok_or(ErrorCode::Overflow.into())
+    }
+
+    /// Timestamp from which rent for the zero-based `period` may be paid.
+    pub fn period_start(&self, start_date: i64, period: u16) -> Result<i64> {
+        match period.checked_sub(1) {
+            Some(previous) => self.due_date(start_date, previous),
+            None => Ok(start_date),
+        }
+    }
+
+    /// Timestamp after which unpaid rent for the zero-based `period` is considered missed.
+    pub fn late_after(&self, start_date: i64, period: u16) -> Result<i64> {
+        self.due_date(start_date, period)?
+            .checked_add(i64::from(self.grace_seconds))
+            .ok_or(ErrorCode::Overflow.into())
+    }
+}
+
+/// Adds calendar months to a unix timestamp, clamping the day to the length of the target month
+/// (Jan 31 + 1 month = Feb 28/29).
+fn add_months(timestamp: i64, months: i64) -> Result<i64> {
+    let days = timestamp.div_euclid(SECONDS_IN_DAY);
+    let seconds_of_day = timestamp.rem_euclid(SECONDS_IN_DAY);
+    let (year, month, day) = civil_from_days(days);
+
+    let month_index = (month as i64 - 1)
+        .checked_add(months)
+        .ok_or(ErrorCode::Overflow)?;
+    let target_year = year
+        .checked_add(month_index.div_euclid(12))
+        .ok_or(ErrorCode::Overflow)?;
+    let target_month = (month_index.rem_euclid(12) + 1) as u32;
+    let target_day = day.min(days_in_month(target_year, target_month));
+
+    days_from_civil(target_year, target_month, target_day)
+        .checked_mul(SECONDS_IN_DAY)
+        .and_then(|v| v.checked_add(seconds_of_day))
+        .ok_or(ErrorCode::Overflow.into())
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Howard Hinnant's civil calendar algorithms, days are counted from 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = i64::from(month);
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // midnight UTC of each date
+    const JAN_31_2023: i64 = 1_675_123_200;
+    const FEB_28_2023: i64 = 1_677_542_400;
+    const MAR_31_2023: i64 = 1_680_220_800;
+    const JAN_31_2024: i64 = 1_706_659_200;
+    const FEB_29_2024: i64 = 1_709_164_800;
+    const MAR_31_2024: i64 = 1_711_843_200;
+    const NOV_30_2023: i64 = 1_701_302_400;
+    const DEC_15_2023: i64 = 1_702_598_400;
+    const JAN_15_2024: i64 = 1_705_276_800;
+    const JAN_15_2025: i64 = 1_736_899_200;
+
+    fn calendar_month(grace_days: u32) -> PaymentSchedule {
+        PaymentSchedule {
+            period_seconds: 0,
+            grace_seconds: grace_days * SECONDS_IN_DAY as u32,
+            calendar_month: true,
+        }
+    }
+
+    #[test]
+    fn month_end_clamps_to_february() {
+        assert_eq!(add_months(JAN_31_2023, 1).unwrap(), FEB_28_2023);
+        assert_eq!(add_months(JAN_31_2024, 1).unwrap(), FEB_29_2024);
+        // every due date is counted from the start, so a short month does not pull later ones in
+        assert_eq!(add_months(JAN_31_2023, 2).unwrap(), MAR_31_2023);
+        assert_eq!(add_months(JAN_31_2024, 2).unwrap(), MAR_31_2024);
+    }
+
+    #[test]
+    fn december_rolls_into_the_next_year() {
+        assert_eq!(add_months(DEC_15_2023, 1).unwrap(), JAN_15_2024);
+        assert_eq!(add_months(DEC_15_2023, 13).unwrap(), JAN_15_2025);
+        assert_eq!(add_months(NOV_30_2023, 3).unwrap(), FEB_29_2024);
+    }
+
+    #[test]
+    fn time_of_day_is_kept() {
+        let noon = 12 * 60 * 60;
+        assert_eq!(
+            add_months(JAN_31_2023 + noon, 1).unwrap(),
+            FEB_28_2023 + noon
+        );
+    }
+
+    #[test]
+    fn calendar_periods_fall_due_at_month_ends() {
+        let schedule = calendar_month(3);
+        assert_eq!(schedule.period_start(JAN_31_2024, 0).unwrap(), JAN_31_2024);
+        assert_eq!(schedule.due_date(JAN_31_2024, 0).unwrap(), FEB_29_2024);
+        assert_eq!(schedule.period_start(JAN_31_2024, 1).unwrap(), FEB_29_2024);
+        assert_eq!(schedule.due_date(JAN_31_2024, 1).unwrap(), MAR_31_2024);
+        assert_eq!(
+            schedule.late_after(JAN_31_2024, 0).unwrap(),
+            FEB_29_2024 + 3 * SECONDS_IN_DAY
+        );
+    }
+
+    #[test]
+    fn grace_must_end_within_the_shortest_month() {
+        assert!(calendar_month(27).validate().is_ok());
+        assert!(calendar_month(28).validate().is_err());
+        let fixed = PaymentSchedule {
+            period_seconds: 0,
+            grace_seconds: 0,
+            calendar_month: false,
+        };
+        assert!(fixed.validate().is_err());
+    }
+}
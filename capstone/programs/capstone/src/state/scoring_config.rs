@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+use crate::ScoringModel;
+
+#[account]
+#[derive(InitSpace)]
+pub struct ScoringConfig {
+    pub admin: Pubkey,       // 32 bytes - Can update the scoring model
+    pub model: ScoringModel, // 18 bytes
+    pub bump: u8,
+}
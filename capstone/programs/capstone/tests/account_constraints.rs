@@ -0,0 +1,182 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, prelude::*};
+use anchor_spl::associated_token::get_associated_token_address;
+use capstone::Agreement;
+use common::*;
+use solana_signer::Signer;
+
+#[test]
+fn pay_rent_with_bound_accounts_succeeds() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    assert_eq!(token_balance(&svm, &lease.landlord_payment_ata), RENT);
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.payments_made, 1);
+}
+
+#[test]
+fn pay_rent_rejects_another_renters_score_account() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let accomplice = Pubkey::new_unique();
+
+    let mut accounts = lease.pay_rent_accounts();
+    accounts.renter = set_renter(&mut svm, &accomplice, 20);
+    let ix = instruction(capstone::instruction::PayRent {}, accounts);
+
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        AnchorError::ConstraintSeeds,
+    );
+}
+
+#[test]
+fn pay_rent_rejects_substituted_landlord() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let attacker = funded_keypair(&mut svm);
+
+    let mut accounts = lease.pay_rent_accounts();
+    accounts.landlord = attacker.pubkey();
+    accounts.landlord_payment_ata =
+        set_token_account(&mut svm, attacker.pubkey(), lease.payment_mint, 0);
+    let ix = instruction(capstone::instruction::PayRent {}, accounts);
+
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        AnchorError::ConstraintHasOne,
+    );
+}
+
+#[test]
+fn pay_rent_rejects_agreement_outside_its_seeds() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let attacker = funded_keypair(&mut svm);
+
+    // a program-owned agreement at an arbitrary address that names the attacker as landlord
+    let mut forged: Agreement = fetch(&svm, &lease.agreement);
+    forged.landlord = attacker.pubkey();
+    let forged_address = Pubkey::new_unique();
+    set_program_account(&mut svm, forged_address, &forged, 8 + Agreement::INIT_SPACE);
+
+    let mut accounts = lease.pay_rent_accounts();
+    accounts.agreement = forged_address;
+    accounts.landlord = attacker.pubkey();
+    accounts.deposit_vault =
+        set_token_account(&mut svm, forged_address, lease.payment_mint, DEPOSIT);
+    accounts.landlord_payment_ata =
+        set_token_account(&mut svm, attacker.pubkey(), lease.payment_mint, 0);
+    let ix = instruction(capstone::instruction::PayRent {}, accounts);
+
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        AnchorError::ConstraintSeeds,
+    );
+}
+
+#[test]
+fn crank_rejects_another_renters_score_account() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let cranker = funded_keypair(&mut svm);
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+
+    let mut accounts = lease.crank_accounts(cranker.pubkey());
+    accounts.renter = set_renter(&mut svm, &Pubkey::new_unique(), 20);
+    let ix = instruction(capstone::instruction::CrankMissedPayment {}, accounts);
+
+    assert_error(
+        send(&mut svm, ix, &cranker, &[]),
+        AnchorError::ConstraintSeeds,
+    );
+}
+
+#[test]
+fn crank_rejects_substituted_landlord() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let attacker = funded_keypair(&mut svm);
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+
+    let mut accounts = lease.crank_accounts(attacker.pubkey());
+    accounts.landlord = attacker.pubkey();
+    accounts.landlord_payment_ata =
+        set_token_account(&mut svm, attacker.pubkey(), lease.payment_mint, 0);
+    let ix = instruction(capstone::instruction::CrankMissedPayment {}, accounts);
+
+    assert_error(
+        send(&mut svm, ix, &attacker, &[]),
+        AnchorError::ConstraintHasOne,
+    );
+}
+
+#[test]
+fn close_agreement_rejects_substituted_landlord() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let attacker = funded_keypair(&mut svm);
+
+    let mut accounts = lease.close_accounts();
+    accounts.landlord = attacker.pubkey();
+    accounts.landlord_ata = set_token_account(&mut svm, attacker.pubkey(), lease.edition_mint, 0);
+    accounts.landlord_payment_ata =
+        set_token_account(&mut svm, attacker.pubkey(), lease.payment_mint, 0);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        accounts,
+    );
+
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        AnchorError::ConstraintHasOne,
+    );
+}
+
+#[test]
+fn close_agreement_rejects_another_renters_score_account() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    let mut accounts = lease.close_accounts();
+    accounts.renter = set_renter(&mut svm, &Pubkey::new_unique(), 20);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        accounts,
+    );
+
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        AnchorError::ConstraintSeeds,
+    );
+}
+
+#[test]
+fn close_agreement_rejects_another_edition_mint() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    // a decoy NFT held by the agreement would leave the real edition locked in the vault
+    let decoy = Pubkey::new_unique();
+    set_mint(&mut svm, decoy, lease.landlord.pubkey(), 0, 1);
+    let mut accounts = lease.close_accounts();
+    accounts.edition_mint = decoy;
+    accounts.nft_vault = set_token_account(&mut svm, lease.agreement, decoy, 1);
+    accounts.landlord_ata = get_associated_token_address(&lease.landlord.pubkey(), &decoy);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        accounts,
+    );
+
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        AnchorError::ConstraintHasOne,
+    );
+}
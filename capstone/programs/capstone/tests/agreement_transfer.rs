@@ -0,0 +1,120 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, prelude::Pubkey, Space};
+use capstone::{error::ErrorCode, events::AgreementTransferred, Agreement};
+use common::*;
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+fn incoming_renter(svm: &mut LiteSVM, lease: &ActiveAgreement, score: i16) -> Keypair {
+    let incoming = funded_keypair(svm);
+    set_renter(svm, &incoming.pubkey(), score);
+    set_token_account(svm, incoming.pubkey(), lease.payment_mint, WALLET_BALANCE);
+    incoming
+}
+
+fn pay_rent(svm: &mut LiteSVM, lease: &ActiveAgreement) {
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    send(svm, ix, &lease.renter, &[]).unwrap();
+}
+
+fn nominate(svm: &mut LiteSVM, lease: &ActiveAgreement, successor: Option<Pubkey>) {
+    let ix = instruction(
+        capstone::instruction::NominateSuccessor { successor },
+        lease.nominate_successor_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[]).unwrap();
+}
+
+#[allow(clippy::result_large_err)]
+fn transfer(svm: &mut LiteSVM, lease: &ActiveAgreement, incoming: &Keypair) -> TransactionResult {
+    let ix = instruction(
+        capstone::instruction::TransferAgreement {},
+        lease.transfer_agreement_accounts(incoming.pubkey()),
+    );
+    send(svm, ix, incoming, &[&lease.landlord])
+}
+
+#[test]
+fn nominated_renter_takes_over_with_a_fresh_deposit() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let incoming = incoming_renter(&mut svm, &lease, 20);
+    pay_rent(&mut svm, &lease);
+    nominate(&mut svm, &lease, Some(incoming.pubkey()));
+
+    let meta = transfer(&mut svm, &lease, &incoming).unwrap();
+    let transferred = events::<AgreementTransferred>(&meta);
+    assert_eq!(transferred[0].previous_renter, lease.renter.pubkey());
+    assert_eq!(transferred[0].deposit_refunded, DEPOSIT);
+
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.renter, incoming.pubkey());
+    assert_eq!(agreement.successor, None);
+    assert_eq!(agreement.payments_made, 1);
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT);
+    assert_eq!(token_balance(&svm, &lease.nft_vault), 1);
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE - RENT + DEPOSIT
+    );
+
+    // the outgoing renter no longer controls the lease
+    let ix = instruction(
+        capstone::instruction::NominateSuccessor { successor: None },
+        lease.nominate_successor_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        AnchorError::ConstraintHasOne,
+    );
+}
+
+#[test]
+fn transfer_needs_nomination_and_settled_rent() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let incoming = incoming_renter(&mut svm, &lease, 20);
+
+    assert_error(
+        transfer(&mut svm, &lease, &incoming),
+        ErrorCode::NotNominated,
+    );
+    nominate(&mut svm, &lease, Some(incoming.pubkey()));
+    assert_error(
+        transfer(&mut svm, &lease, &incoming),
+        ErrorCode::ArrearsOutstanding,
+    );
+
+    pay_rent(&mut svm, &lease);
+    nominate(&mut svm, &lease, None);
+    assert_error(
+        transfer(&mut svm, &lease, &incoming),
+        ErrorCode::NotNominated,
+    );
+}
+
+#[test]
+fn successor_must_meet_the_listing_score() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let incoming = incoming_renter(&mut svm, &lease, 10);
+    let mut agreement: Agreement = fetch(&svm, &lease.agreement);
+    agreement.min_renter_score = 15;
+    set_program_account(
+        &mut svm,
+        lease.agreement,
+        &agreement,
+        8 + Agreement::INIT_SPACE,
+    );
+    pay_rent(&mut svm, &lease);
+    nominate(&mut svm, &lease, Some(incoming.pubkey()));
+
+    assert_error(
+        transfer(&mut svm, &lease, &incoming),
+        ErrorCode::RenterScoreTooLow,
+    );
+}
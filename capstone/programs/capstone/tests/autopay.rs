@@ -0,0 +1,167 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::{associated_token::get_associated_token_address, token::spl_token};
+use capstone::{
+    error::ErrorCode,
+    events::{DepositDrawdown, KeeperTipPaid, RentPaid},
+    Agreement, Renter, KEEPER_TIP_BPS,
+};
+use common::*;
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+// rent plus the fixture's 10% late fee
+const PERIOD_CHARGE: u64 = RENT + RENT / 10;
+
+fn tip(amount: u64) -> u64 {
+    amount * KEEPER_TIP_BPS / 10_000
+}
+
+fn set_autopay(svm: &mut LiteSVM, lease: &ActiveAgreement, enabled: bool) {
+    let ix = instruction(
+        capstone::instruction::SetAutopay { enabled },
+        lease.set_autopay_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[]).unwrap();
+}
+
+#[allow(clippy::result_large_err)]
+fn collect(svm: &mut LiteSVM, lease: &ActiveAgreement, keeper: &Keypair) -> TransactionResult {
+    let ix = instruction(
+        capstone::instruction::CollectRent {},
+        lease.collect_rent_accounts(keeper.pubkey()),
+    );
+    send(svm, ix, keeper, &[])
+}
+
+#[test]
+fn keeper_collects_due_rent_through_the_approval() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let keeper = funded_keypair(&mut svm);
+
+    assert_error(
+        collect(&mut svm, &lease, &keeper),
+        ErrorCode::AutopayNotEnabled,
+    );
+    set_autopay(&mut svm, &lease, true);
+    let renter_ata = svm.get_account(&lease.renter_payment_ata).unwrap();
+    let renter_ata = spl_token::state::Account::unpack(&renter_ata.data).unwrap();
+    assert_eq!(renter_ata.delegated_amount, 3 * RENT + tip(3 * RENT));
+
+    assert_error(collect(&mut svm, &lease, &keeper), ErrorCode::RentNotDue);
+
+    warp_to(&mut svm, START_DATE + MONTH);
+    let meta = collect(&mut svm, &lease, &keeper).unwrap();
+    assert_eq!(events::<KeeperTipPaid>(&meta)[0].amount, tip(RENT));
+    assert!(!events::<RentPaid>(&meta)[0].from_deposit);
+
+    let keeper_ata = get_associated_token_address(&keeper.pubkey(), &lease.payment_mint);
+    assert_eq!(token_balance(&svm, &keeper_ata), tip(RENT));
+    // the renter pays the tip on top of the rent, the landlord gets the rent in full
+    assert_eq!(token_balance(&svm, &lease.landlord_payment_ata), RENT);
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE - RENT - tip(RENT)
+    );
+    assert_eq!(fetch::<Agreement>(&svm, &lease.agreement).payments_made, 1);
+    assert_eq!(fetch::<Renter>(&svm, &lease.renter_account).score, 22);
+
+    // exactly one period per collection
+    assert_error(collect(&mut svm, &lease, &keeper), ErrorCode::RentNotDue);
+}
+
+#[test]
+fn revoked_approval_falls_back_to_the_deposit_after_grace() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let keeper = funded_keypair(&mut svm);
+    set_autopay(&mut svm, &lease, true);
+
+    // the renter revokes outside the program, so the agreement still has auto-pay on
+    let revoke = spl_token::instruction::revoke(
+        &spl_token::ID,
+        &lease.renter_payment_ata,
+        &lease.renter.pubkey(),
+        &[],
+    )
+    .unwrap();
+    send(&mut svm, revoke, &lease.renter, &[]).unwrap();
+
+    warp_to(&mut svm, START_DATE + MONTH + DAY);
+    assert_error(
+        collect(&mut svm, &lease, &keeper),
+        ErrorCode::RentNotCollectable,
+    );
+
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+    let meta = collect(&mut svm, &lease, &keeper).unwrap();
+    assert!(events::<RentPaid>(&meta)[0].from_deposit);
+    let drawn = PERIOD_CHARGE + tip(PERIOD_CHARGE);
+    assert_eq!(events::<DepositDrawdown>(&meta)[0].amount, drawn);
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT - drawn);
+    assert_eq!(
+        token_balance(&svm, &lease.landlord_payment_ata),
+        PERIOD_CHARGE
+    );
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.payments_made, 1);
+    assert_eq!(agreement.missed_payments, 1);
+}
+
+#[test]
+fn renter_can_turn_autopay_off() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let keeper = funded_keypair(&mut svm);
+    set_autopay(&mut svm, &lease, true);
+    set_autopay(&mut svm, &lease, false);
+
+    let renter_ata = svm.get_account(&lease.renter_payment_ata).unwrap();
+    let renter_ata = spl_token::state::Account::unpack(&renter_ata.data).unwrap();
+    assert!(renter_ata.delegate.is_none());
+    warp_to(&mut svm, START_DATE + MONTH);
+    assert_error(
+        collect(&mut svm, &lease, &keeper),
+        ErrorCode::AutopayNotEnabled,
+    );
+}
+
+#[test]
+fn autopay_does_not_take_over_another_agreements_approval() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    // the payment account already auto-pays another lease
+    let other_agreement = anchor_lang::prelude::Pubkey::new_unique();
+    let approve = spl_token::instruction::approve(
+        &spl_token::ID,
+        &lease.renter_payment_ata,
+        &other_agreement,
+        &lease.renter.pubkey(),
+        &[],
+        RENT,
+    )
+    .unwrap();
+    send(&mut svm, approve, &lease.renter, &[]).unwrap();
+
+    let ix = instruction(
+        capstone::instruction::SetAutopay { enabled: true },
+        lease.set_autopay_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::AutopayDelegateTaken,
+    );
+
+    // turning auto-pay off here leaves the other lease's approval alone
+    set_autopay(&mut svm, &lease, false);
+    let renter_ata = svm.get_account(&lease.renter_payment_ata).unwrap();
+    let renter_ata = spl_token::state::Account::unpack(&renter_ata.data).unwrap();
+    assert_eq!(renter_ata.delegate, Some(other_agreement).into());
+    assert_eq!(renter_ata.delegated_amount, RENT);
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::Space;
+use capstone::{events::RentPaid, Agreement, PaymentSchedule};
+use common::*;
+use litesvm::LiteSVM;
+
+// midnight UTC; the first period of a lease starting on Jan 31 2024 ends on Feb 29
+const JAN_31_2024: i64 = 1_706_659_200;
+const FEB_29_2024: i64 = 1_709_164_800;
+const MAR_31_2024: i64 = 1_711_843_200;
+
+/// The fixture agreement moved onto calendar-month due dates starting on Jan 31 2024.
+fn calendar_lease(svm: &mut LiteSVM) -> ActiveAgreement {
+    let lease = ActiveAgreement::new(svm);
+    let mut agreement: Agreement = fetch(svm, &lease.agreement);
+    agreement.start_date = JAN_31_2024;
+    agreement.schedule = PaymentSchedule {
+        period_seconds: 0,
+        grace_seconds: (3 * DAY) as u32,
+        calendar_month: true,
+    };
+    agreement.end_date = agreement
+        .schedule
+        .due_date(JAN_31_2024, u16::from(agreement.periods) - 1)
+        .unwrap();
+    set_program_account(svm, lease.agreement, &agreement, 8 + Agreement::INIT_SPACE);
+    lease
+}
+
+fn pay_rent(svm: &mut LiteSVM, lease: &ActiveAgreement) -> RentPaid {
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    let meta = send(svm, ix, &lease.renter, &[]).unwrap();
+    events::<RentPaid>(&meta).remove(0)
+}
+
+#[test]
+fn calendar_month_rent_falls_due_at_each_month_end() {
+    let mut svm = setup();
+    let lease = calendar_lease(&mut svm);
+
+    // a 30-day period would run to Mar 1, the calendar month ends on Feb 29
+    warp_to(&mut svm, FEB_29_2024 + 3 * DAY);
+    assert!(!pay_rent(&mut svm, &lease).late);
+
+    warp_to(&mut svm, MAR_31_2024 + 3 * DAY + 1);
+    let paid = pay_rent(&mut svm, &lease);
+    assert_eq!((paid.period, paid.late), (1, true));
+    assert_eq!(fetch::<Agreement>(&svm, &lease.agreement).payments_made, 2);
+}
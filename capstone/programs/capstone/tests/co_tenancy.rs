@@ -0,0 +1,228 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::prelude::{AccountMeta, Pubkey};
+use capstone::{
+    error::ErrorCode,
+    events::{CoRenterAdded, DepositSettled, RentPaid, ScoreChanged, SharePaid},
+    Agreement, Renter,
+};
+use common::*;
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const CO_SHARE: u8 = 40;
+const LATE_FEE: u64 = RENT / 10;
+
+struct CoRenter {
+    keypair: Keypair,
+    account: Pubkey,
+    payment_ata: Pubkey,
+}
+
+fn shared_lease(svm: &mut LiteSVM) -> (ActiveAgreement, CoRenter) {
+    let lease = ActiveAgreement::new(svm);
+    let keypair = funded_keypair(svm);
+    let account = set_renter(svm, &keypair.pubkey(), 20);
+    let payment_ata = set_token_account(svm, keypair.pubkey(), lease.payment_mint, WALLET_BALANCE);
+
+    let ix = instruction(
+        capstone::instruction::AddCoRenter {
+            share_percent: CO_SHARE,
+        },
+        lease.add_co_renter_accounts(keypair.pubkey()),
+    );
+    let meta = send(svm, ix, &keypair, &[&lease.landlord, &lease.renter]).unwrap();
+    assert_eq!(
+        events::<CoRenterAdded>(&meta)[0].deposit_contribution,
+        DEPOSIT * 40 / 100
+    );
+    (
+        lease,
+        CoRenter {
+            keypair,
+            account,
+            payment_ata,
+        },
+    )
+}
+
+#[allow(clippy::result_large_err)]
+fn pay_share(svm: &mut LiteSVM, lease: &ActiveAgreement, payer: &Keypair) -> TransactionResult {
+    let ix = instruction(
+        capstone::instruction::PayShare {},
+        lease.pay_share_accounts(payer.pubkey()),
+    );
+    send(svm, ix, payer, &[])
+}
+
+#[test]
+fn co_renter_buys_into_the_deposit() {
+    let mut svm = setup();
+    let (lease, co) = shared_lease(&mut svm);
+
+    assert_eq!(
+        token_balance(&svm, &co.payment_ata),
+        WALLET_BALANCE - DEPOSIT * 40 / 100
+    );
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE + DEPOSIT * 40 / 100
+    );
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.tenants.len(), 2);
+    assert_eq!(agreement.tenants[0].share_percent, 60);
+    assert_eq!(
+        agreement.tenants[0].deposit_contribution,
+        DEPOSIT * 60 / 100
+    );
+
+    // whole-rent payments are off once the rent is split
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::SharedAgreement,
+    );
+}
+
+#[test]
+fn period_is_paid_once_every_share_is_in() {
+    let mut svm = setup();
+    let (lease, co) = shared_lease(&mut svm);
+
+    let meta = pay_share(&mut svm, &lease, &co.keypair).unwrap();
+    assert_eq!(events::<SharePaid>(&meta)[0].amount, RENT * 40 / 100);
+    assert!(events::<RentPaid>(&meta).is_empty());
+    assert_eq!(fetch::<Agreement>(&svm, &lease.agreement).payments_made, 0);
+    assert_error(
+        pay_share(&mut svm, &lease, &co.keypair),
+        ErrorCode::ShareAlreadyPaid,
+    );
+
+    let meta = pay_share(&mut svm, &lease, &lease.renter).unwrap();
+    assert_eq!(events::<RentPaid>(&meta).len(), 1);
+    assert_eq!(token_balance(&svm, &lease.landlord_payment_ata), RENT);
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.payments_made, 1);
+    assert!(agreement.tenants.iter().all(|tenant| !tenant.share_settled));
+    assert_eq!(fetch::<Renter>(&svm, &co.account).score, 22);
+    assert_eq!(fetch::<Renter>(&svm, &lease.renter_account).score, 22);
+}
+
+#[test]
+fn late_share_only_costs_its_payer() {
+    let mut svm = setup();
+    let (lease, co) = shared_lease(&mut svm);
+    warp_to(&mut svm, START_DATE + MONTH);
+    pay_share(&mut svm, &lease, &co.keypair).unwrap();
+
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+    let meta = pay_share(&mut svm, &lease, &lease.renter).unwrap();
+    let share = events::<SharePaid>(&meta);
+    assert!(share[0].late);
+    assert_eq!(share[0].amount, (RENT + LATE_FEE) * 60 / 100);
+    assert!(events::<RentPaid>(&meta)[0].late);
+
+    assert_eq!(fetch::<Renter>(&svm, &lease.renter_account).score, 18);
+    assert_eq!(fetch::<Renter>(&svm, &co.account).score, 22);
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.payments_made, 1);
+    assert_eq!(agreement.late_fees_owed, 0);
+}
+
+#[test]
+fn crank_penalizes_only_the_missing_shares() {
+    let mut svm = setup();
+    let (lease, co) = shared_lease(&mut svm);
+    pay_share(&mut svm, &lease, &lease.renter).unwrap();
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+
+    let cranker = funded_keypair(&mut svm);
+    let ix = instruction(
+        capstone::instruction::CrankMissedPayment {},
+        lease.crank_accounts(cranker.pubkey()),
+    );
+    assert_error(
+        send(&mut svm, ix.clone(), &cranker, &[]),
+        ErrorCode::TenantAccountMismatch,
+    );
+
+    let mut ix = ix;
+    ix.accounts.push(AccountMeta::new(co.account, false));
+    let meta = send(&mut svm, ix, &cranker, &[]).unwrap();
+    let score_changes = events::<ScoreChanged>(&meta);
+    assert_eq!(score_changes.len(), 1);
+    assert_eq!(score_changes[0].renter, co.keypair.pubkey());
+    assert_eq!(fetch::<Renter>(&svm, &co.account).score, 18);
+    assert_eq!(fetch::<Renter>(&svm, &lease.renter_account).score, 22);
+
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.payments_made, 1);
+    assert_eq!(agreement.missed_payments, 1);
+}
+
+#[test]
+fn termination_penalizes_every_renter_behind_on_rent() {
+    let mut svm = setup();
+    let (lease, co) = shared_lease(&mut svm);
+    // the lead renter's share of the first period is in, the co-renter's never is
+    pay_share(&mut svm, &lease, &lease.renter).unwrap();
+    warp_to(&mut svm, START_DATE + 2 * MONTH + 4 * DAY);
+
+    let mut ix = instruction(
+        capstone::instruction::TerminateAgreement {},
+        lease.terminate_accounts(),
+    );
+    ix.accounts.push(AccountMeta::new(co.payment_ata, false));
+    assert_error(
+        send(&mut svm, ix.clone(), &lease.landlord, &[]),
+        ErrorCode::TenantAccountMismatch,
+    );
+
+    ix.accounts.push(AccountMeta::new(co.account, false));
+    let meta = send(&mut svm, ix, &lease.landlord, &[]).unwrap();
+    assert_eq!(events::<ScoreChanged>(&meta).len(), 2);
+
+    // the lead renter owes the second period, the co-renter both
+    let lead: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!((lead.score, lead.late_payments), (22 - 5, 1));
+    let co_renter: Renter = fetch(&svm, &co.account);
+    assert_eq!((co_renter.score, co_renter.late_payments), (20 - 2 * 5, 2));
+}
+
+#[test]
+fn deposit_is_refunded_by_contribution() {
+    let mut svm = setup();
+    let (lease, co) = shared_lease(&mut svm);
+    set_config(&mut svm, lease.landlord.pubkey(), lease.landlord.pubkey());
+
+    let move_out_date = START_DATE + MONTH + DAY;
+    warp_to(&mut svm, move_out_date);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    warp_to(&mut svm, move_out_date + CLAIM_WINDOW + 1);
+    let co_balance = token_balance(&svm, &co.payment_ata);
+    let lead_balance = token_balance(&svm, &lease.renter_payment_ata);
+    let mut ix = instruction(
+        capstone::instruction::SettleDeposit {},
+        lease.settle_deposit_accounts(lease.renter.pubkey(), false),
+    );
+    ix.accounts.push(AccountMeta::new(co.payment_ata, false));
+    let meta = send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    assert_eq!(events::<DepositSettled>(&meta)[0].renter_amount, DEPOSIT);
+    assert_eq!(
+        token_balance(&svm, &co.payment_ata) - co_balance,
+        DEPOSIT * 40 / 100
+    );
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata) - lead_balance,
+        DEPOSIT * 60 / 100
+    );
+}
@@ -0,0 +1,1471 @@
+#![allow(dead_code, deprecated, clippy::result_large_err)]
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        epoch_schedule::EpochSchedule, instruction::Instruction, program_option::COption,
+        program_pack::Pack, stake, sysvar,
+    },
+    system_program, AccountSerialize, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id},
+    metadata,
+    token::spl_token,
+    token_2022::spl_token_2022,
+};
+use capstone::{
+    Agreement, AgreementStatus, Config, Escrow, Landlord, PaymentSchedule, Property,
+    PropertyDetails, PropertyStatus, Renter, ScoringConfig, ScoringModel, UnitType,
+    BPF_LOADER_UPGRADEABLE,
+};
+use litesvm::{
+    types::{TransactionMetadata, TransactionResult},
+    LiteSVM,
+};
+use solana_account::Account;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+pub const DAY: i64 = 24 * 60 * 60;
+pub const MONTH: i64 = 30 * DAY;
+pub const RENT: u64 = 1_000_000_000;
+pub const DEPOSIT: u64 = 2_000_000_000;
+pub const WALLET_BALANCE: u64 = 50_000_000_000;
+pub const START_DATE: i64 = 1_750_000_000;
+
+/// Token program of the NFTs `init_landlord` and `create_escrow` mint.
+#[cfg(not(feature = "token-2022-nft"))]
+pub const NFT_TOKEN_PROGRAM: Pubkey = spl_token::ID;
+#[cfg(feature = "token-2022-nft")]
+pub const NFT_TOKEN_PROGRAM: Pubkey = spl_token_2022::ID;
+
+pub fn setup() -> LiteSVM {
+    let mut svm = LiteSVM::new();
+    let program = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../target/deploy/capstone.so"
+    );
+    svm.add_program_from_file(capstone::ID, program)
+        .expect("build the program with `anchor build` before running litesvm tests");
+    svm
+}
+
+pub fn funded_keypair(svm: &mut LiteSVM) -> Keypair {
+    let keypair = Keypair::new();
+    svm.airdrop(&keypair.pubkey(), 10_000_000_000).unwrap();
+    keypair
+}
+
+pub fn warp_to(svm: &mut LiteSVM, unix_timestamp: i64) {
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = unix_timestamp;
+    svm.set_sysvar(&clock);
+}
+
+pub fn send(
+    svm: &mut LiteSVM,
+    ix: Instruction,
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> TransactionResult {
+    svm.expire_blockhash();
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &all_signers,
+        svm.latest_blockhash(),
+    );
+    svm.send_transaction(tx)
+}
+
+pub fn assert_error(result: TransactionResult, code: impl Into<u32>) {
+    use anchor_lang::solana_program::instruction::InstructionError;
+    use solana_transaction_error::TransactionError;
+
+    let code = code.into();
+    match result {
+        Ok(_) => panic!("transaction succeeded, expected custom error {code}"),
+        Err(failed) => assert_eq!(
+            failed.err,
+            TransactionError::InstructionError(0, InstructionError::Custom(code)),
+            "{}",
+            failed.meta.pretty_logs()
+        ),
+    }
+}
+
+pub fn set_program_account<T: AccountSerialize>(
+    svm: &mut LiteSVM,
+    address: Pubkey,
+    account: &T,
+    space: usize,
+) {
+    let mut data = Vec::with_capacity(space);
+    account.try_serialize(&mut data).unwrap();
+    data.resize(space, 0);
+    let lamports = svm.minimum_balance_for_rent_exemption(space);
+    svm.set_account(
+        address,
+        Account {
+            lamports,
+            data,
+            owner: capstone::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+pub fn set_mint(svm: &mut LiteSVM, address: Pubkey, authority: Pubkey, decimals: u8, supply: u64) {
+    let mint = spl_token::state::Mint {
+        mint_authority: COption::Some(authority),
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+    let mut data = vec![0; spl_token::state::Mint::LEN];
+    mint.pack_into_slice(&mut data);
+    let lamports = svm.minimum_balance_for_rent_exemption(data.len());
+    svm.set_account(
+        address,
+        Account {
+            lamports,
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+/// Creates (or overwrites) the classic-token ATA of `owner` for `mint` holding `amount`.
+/// Accounts of the native mint are backed by lamports like wrapped SOL.
+pub fn set_token_account(svm: &mut LiteSVM, owner: Pubkey, mint: Pubkey, amount: u64) -> Pubkey {
+    let address = get_associated_token_address_with_program_id(&owner, &mint, &spl_token::ID);
+    let rent = svm.minimum_balance_for_rent_exemption(spl_token::state::Account::LEN);
+    let native = mint == spl_token::native_mint::ID;
+    let token_account = spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        delegate: COption::None,
+        state: spl_token::state::AccountState::Initialized,
+        is_native: if native {
+            COption::Some(rent)
+        } else {
+            COption::None
+        },
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    token_account.pack_into_slice(&mut data);
+    let lamports = if native { rent + amount } else { rent };
+    svm.set_account(
+        address,
+        Account {
+            lamports,
+            data,
+            owner: spl_token::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+    address
+}
+
+pub fn token_balance(svm: &LiteSVM, address: &Pubkey) -> u64 {
+    let account = svm.get_account(address).expect("token account missing");
+    spl_token::state::Account::unpack(&account.data)
+        .unwrap()
+        .amount
+}
+
+pub fn fetch<T: AccountDeserialize>(svm: &LiteSVM, address: &Pubkey) -> T {
+    let account = svm.get_account(address).expect("account missing");
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+pub fn renter_pda(renter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"renter", renter.as_ref()], &capstone::ID).0
+}
+
+pub fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &capstone::ID).0
+}
+
+pub fn agreement_pda(edition_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"agreement", edition_mint.as_ref()], &capstone::ID)
+}
+
+pub fn set_renter(svm: &mut LiteSVM, renter: &Pubkey, score: i16) -> Pubkey {
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"renter", renter.as_ref()], &capstone::ID);
+    set_program_account(
+        svm,
+        address,
+        &Renter {
+            score,
+            total_payments: 0,
+            late_payments: 0,
+            completed_leases: 0,
+            rating_total: 0,
+            rating_count: 0,
+            bump,
+        },
+        8 + Renter::INIT_SPACE,
+    );
+    address
+}
+
+pub fn landlord_pda(landlord: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"landlord", landlord.as_ref()], &capstone::ID).0
+}
+
+/// Writes the landlord profile with `listing_count` open listings and `active_agreements` leases.
+pub fn set_landlord(
+    svm: &mut LiteSVM,
+    landlord: &Pubkey,
+    listing_count: u32,
+    active_agreements: u32,
+) -> Pubkey {
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"landlord", landlord.as_ref()], &capstone::ID);
+    set_program_account(
+        svm,
+        address,
+        &Landlord {
+            listing_count,
+            active_agreements,
+            completed_leases: 0,
+            rating_total: 0,
+            rating_count: 0,
+            bump,
+        },
+        8 + Landlord::INIT_SPACE,
+    );
+    address
+}
+
+pub fn property_details() -> PropertyDetails {
+    PropertyDetails {
+        address_hash: [7; 32],
+        unit_type: UnitType::Apartment,
+        capacity: 2,
+    }
+}
+
+pub fn property_pda(edition_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"property", edition_mint.as_ref()], &capstone::ID).0
+}
+
+pub fn set_property(
+    svm: &mut LiteSVM,
+    landlord: &Pubkey,
+    edition_mint: &Pubkey,
+    status: PropertyStatus,
+) -> Pubkey {
+    let (address, bump) =
+        Pubkey::find_program_address(&[b"property", edition_mint.as_ref()], &capstone::ID);
+    set_program_account(
+        svm,
+        address,
+        &Property {
+            landlord: *landlord,
+            edition_mint: *edition_mint,
+            details: property_details(),
+            status,
+            bump,
+        },
+        8 + Property::INIT_SPACE,
+    );
+    address
+}
+
+pub fn attestation_pda(renter: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"attestation", renter.as_ref()], &capstone::ID).0
+}
+
+pub fn attest_reputation_accounts(
+    payer: &Pubkey,
+    renter: &Pubkey,
+) -> capstone::accounts::AttestReputation {
+    capstone::accounts::AttestReputation {
+        payer: *payer,
+        renter: *renter,
+        renter_account: renter_pda(renter),
+        attestation: attestation_pda(renter),
+        system_program: system_program::ID,
+        event_authority: event_authority(),
+        program: capstone::ID,
+    }
+}
+
+pub fn monthly_schedule() -> PaymentSchedule {
+    PaymentSchedule {
+        period_seconds: MONTH as u32,
+        grace_seconds: (3 * DAY) as u32,
+        calendar_month: false,
+    }
+}
+
+pub fn scoring_model() -> ScoringModel {
+    ScoringModel {
+        start_score: 20,
+        on_time_reward: 2,
+        late_penalty: 2,
+        late_penalty_per_day: 0,
+        early_termination_penalty: 5,
+        eviction_penalty: 5,
+        completion_bonus: 10,
+        min_score: 0,
+        max_score: 1000,
+    }
+}
+
+pub fn program_data_pda() -> Pubkey {
+    Pubkey::find_program_address(&[capstone::ID.as_ref()], &BPF_LOADER_UPGRADEABLE).0
+}
+
+/// Writes the program's ProgramData account naming `authority` as upgrade authority. litesvm
+/// loads the program with the non-upgradeable loader, so it does not exist otherwise.
+pub fn set_upgrade_authority(svm: &mut LiteSVM, authority: Pubkey) -> Pubkey {
+    // UpgradeableLoaderState::ProgramData { slot: 0, upgrade_authority_address: Some(authority) }
+    let mut data = 3u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(authority.as_ref());
+    let lamports = svm.minimum_balance_for_rent_exemption(data.len());
+    let address = program_data_pda();
+    svm.set_account(
+        address,
+        Account {
+            lamports,
+            data,
+            owner: BPF_LOADER_UPGRADEABLE,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+    address
+}
+
+pub fn scoring_config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"scoring_config"], &capstone::ID).0
+}
+
+pub fn set_scoring_config(svm: &mut LiteSVM, admin: Pubkey, model: ScoringModel) -> Pubkey {
+    let (address, bump) = Pubkey::find_program_address(&[b"scoring_config"], &capstone::ID);
+    set_program_account(
+        svm,
+        address,
+        &ScoringConfig { admin, model, bump },
+        8 + ScoringConfig::INIT_SPACE,
+    );
+    address
+}
+
+pub const CLAIM_WINDOW: i64 = 7 * DAY;
+pub const RESPONSE_WINDOW: i64 = 5 * DAY;
+pub const ARBITRATION_WINDOW: i64 = 10 * DAY;
+pub const REPAIR_SLA: i64 = 3 * DAY;
+pub const RENT_WITHHOLD_PERCENT: u8 = 30;
+
+pub fn config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &capstone::ID).0
+}
+
+pub fn set_config(svm: &mut LiteSVM, admin: Pubkey, arbiter: Pubkey) -> Pubkey {
+    let (address, bump) = Pubkey::find_program_address(&[b"config"], &capstone::ID);
+    set_program_account(
+        svm,
+        address,
+        &Config {
+            admin,
+            arbiter,
+            claim_window: CLAIM_WINDOW,
+            response_window: RESPONSE_WINDOW,
+            arbitration_window: ARBITRATION_WINDOW,
+            repair_sla: REPAIR_SLA,
+            rent_withhold_percent: RENT_WITHHOLD_PERCENT,
+            bump,
+        },
+        8 + Config::INIT_SPACE,
+    );
+    address
+}
+
+/// An untaken listing with its edition NFT in the escrow vault, written straight into the bank.
+pub struct Listing {
+    pub edition_mint: Pubkey,
+    pub escrow: Pubkey,
+    pub vault: Pubkey,
+}
+
+impl Listing {
+    pub fn new(
+        svm: &mut LiteSVM,
+        landlord: &Pubkey,
+        payment_mint: Pubkey,
+        property_id: u64,
+    ) -> Self {
+        let edition_mint = Pubkey::new_unique();
+        set_mint(svm, edition_mint, *landlord, 0, 1);
+        let (escrow, bump) =
+            Pubkey::find_program_address(&[b"escrow", edition_mint.as_ref()], &capstone::ID);
+        set_program_account(
+            svm,
+            escrow,
+            &Escrow {
+                landlord: *landlord,
+                property_id,
+                payment_mint,
+                monthly_rent: RENT,
+                deposit_amount: DEPOSIT,
+                late_fee_percent: 10,
+                min_renter_score: 15,
+                max_late_payment_percent: None,
+                months: 3,
+                schedule: monthly_schedule(),
+                cancel_allowed_after: 1,
+                cancel_penalty_percent: 5,
+                yield_renter_share_percent: None,
+                bump,
+                edition_mint_bump: 0,
+            },
+            8 + Escrow::INIT_SPACE,
+        );
+        let vault = set_token_account(svm, escrow, edition_mint, 1);
+        set_property(svm, landlord, &edition_mint, PropertyStatus::Listed);
+        let profile = svm
+            .get_account(&landlord_pda(landlord))
+            .map(|_| fetch::<Landlord>(svm, &landlord_pda(landlord)));
+        set_landlord(
+            svm,
+            landlord,
+            profile.as_ref().map_or(0, |p| p.listing_count) + 1,
+            profile.as_ref().map_or(0, |p| p.active_agreements),
+        );
+        Self {
+            edition_mint,
+            escrow,
+            vault,
+        }
+    }
+
+    /// Addresses of the listing `create_escrow` makes for `landlord` and `property_id`.
+    pub fn minted(landlord: &Pubkey, property_id: u64) -> Self {
+        let edition_mint = Pubkey::find_program_address(
+            &[
+                b"edition",
+                collection_mint_pda(landlord).as_ref(),
+                property_id.to_le_bytes().as_ref(),
+            ],
+            &capstone::ID,
+        )
+        .0;
+        let escrow =
+            Pubkey::find_program_address(&[b"escrow", edition_mint.as_ref()], &capstone::ID).0;
+        let vault = get_associated_token_address_with_program_id(
+            &escrow,
+            &edition_mint,
+            &NFT_TOKEN_PROGRAM,
+        );
+        Self {
+            edition_mint,
+            escrow,
+            vault,
+        }
+    }
+
+    pub fn create_accounts(
+        &self,
+        landlord: &Pubkey,
+        payment_mint: &Pubkey,
+    ) -> capstone::accounts::MakeEscrow {
+        let collection_mint = collection_mint_pda(landlord);
+        capstone::accounts::MakeEscrow {
+            landlord: *landlord,
+            escrow: self.escrow,
+            landlord_account: landlord_pda(landlord),
+            property: property_pda(&self.edition_mint),
+            edition_mint: self.edition_mint,
+            vault: self.vault,
+            #[cfg(not(feature = "token-2022-nft"))]
+            metadata: edition_metadata_pda(&self.edition_mint),
+            #[cfg(not(feature = "token-2022-nft"))]
+            master_edition: master_edition_pda(&self.edition_mint),
+            collection_mint,
+            #[cfg(not(feature = "token-2022-nft"))]
+            collection_metadata: edition_metadata_pda(&collection_mint),
+            #[cfg(not(feature = "token-2022-nft"))]
+            collection_master_edition: master_edition_pda(&collection_mint),
+            payment_mint: *payment_mint,
+            scoring_config: scoring_config_pda(),
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            #[cfg(not(feature = "token-2022-nft"))]
+            token_metadata_program: metadata::ID,
+            token_program: NFT_TOKEN_PROGRAM,
+            payment_token_program: spl_token::ID,
+            #[cfg(not(feature = "token-2022-nft"))]
+            rent: sysvar::rent::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn refund_accounts(&self, landlord: &Pubkey) -> capstone::accounts::Refund {
+        let collection_mint = collection_mint_pda(landlord);
+        capstone::accounts::Refund {
+            landlord: *landlord,
+            escrow: self.escrow,
+            landlord_account: landlord_pda(landlord),
+            property: property_pda(&self.edition_mint),
+            edition_mint: self.edition_mint,
+            #[cfg(not(feature = "token-2022-nft"))]
+            metadata: edition_metadata_pda(&self.edition_mint),
+            #[cfg(not(feature = "token-2022-nft"))]
+            master_edition: master_edition_pda(&self.edition_mint),
+            vault: self.vault,
+            collection_mint,
+            #[cfg(not(feature = "token-2022-nft"))]
+            collection_metadata: edition_metadata_pda(&collection_mint),
+            token_program: NFT_TOKEN_PROGRAM,
+            system_program: system_program::ID,
+            #[cfg(not(feature = "token-2022-nft"))]
+            associated_token_program: associated_token::ID,
+            #[cfg(not(feature = "token-2022-nft"))]
+            token_metadata_program: metadata::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn update_accounts(&self, landlord: &Pubkey) -> capstone::accounts::UpdateEscrow {
+        capstone::accounts::UpdateEscrow {
+            landlord: *landlord,
+            escrow: self.escrow,
+            edition_mint: self.edition_mint,
+            scoring_config: scoring_config_pda(),
+            #[cfg(not(feature = "token-2022-nft"))]
+            metadata: None,
+            #[cfg(not(feature = "token-2022-nft"))]
+            token_metadata_program: None,
+            #[cfg(feature = "token-2022-nft")]
+            token_program: NFT_TOKEN_PROGRAM,
+            #[cfg(feature = "token-2022-nft")]
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn take_accounts(
+        &self,
+        renter: &Pubkey,
+        landlord: &Pubkey,
+        payment_mint: &Pubkey,
+    ) -> capstone::accounts::Take {
+        let (agreement, _) = agreement_pda(&self.edition_mint);
+        capstone::accounts::Take {
+            renter: *renter,
+            landlord: *landlord,
+            renter_account: renter_pda(renter),
+            agreement,
+            escrow: self.escrow,
+            landlord_account: landlord_pda(landlord),
+            property: property_pda(&self.edition_mint),
+            edition_mint: self.edition_mint,
+            vault: self.vault,
+            payment_mint: *payment_mint,
+            renter_payment_ata: get_associated_token_address_with_program_id(
+                renter,
+                payment_mint,
+                &spl_token::ID,
+            ),
+            deposit_vault: get_associated_token_address_with_program_id(
+                &agreement,
+                payment_mint,
+                &spl_token::ID,
+            ),
+            nft_vault: get_associated_token_address_with_program_id(
+                &agreement,
+                &self.edition_mint,
+                &spl_token::ID,
+            ),
+            token_program: spl_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            associated_token_program: associated_token::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+}
+
+/// An agreement that was taken at `START_DATE`, with its deposit and edition NFT in custody,
+/// written straight into the bank so the Token Metadata program is not needed.
+pub struct ActiveAgreement {
+    pub landlord: Keypair,
+    pub renter: Keypair,
+    pub payment_mint: Pubkey,
+    pub edition_mint: Pubkey,
+    pub agreement: Pubkey,
+    pub renter_account: Pubkey,
+    pub deposit_vault: Pubkey,
+    pub nft_vault: Pubkey,
+    pub renter_payment_ata: Pubkey,
+    pub landlord_payment_ata: Pubkey,
+}
+
+impl ActiveAgreement {
+    pub fn new(svm: &mut LiteSVM) -> Self {
+        Self::build(svm, Pubkey::new_unique(), None)
+    }
+
+    /// Addresses of an agreement `renter` took on chain with `take_escrow`.
+    pub fn taken(
+        landlord: Keypair,
+        renter: Keypair,
+        payment_mint: Pubkey,
+        edition_mint: Pubkey,
+    ) -> Self {
+        let (agreement, _) = agreement_pda(&edition_mint);
+        let ata = |owner: &Pubkey, mint: &Pubkey| {
+            get_associated_token_address_with_program_id(owner, mint, &spl_token::ID)
+        };
+        Self {
+            renter_account: renter_pda(&renter.pubkey()),
+            deposit_vault: ata(&agreement, &payment_mint),
+            nft_vault: ata(&agreement, &edition_mint),
+            renter_payment_ata: ata(&renter.pubkey(), &payment_mint),
+            landlord_payment_ata: ata(&landlord.pubkey(), &payment_mint),
+            landlord,
+            renter,
+            payment_mint,
+            edition_mint,
+            agreement,
+        }
+    }
+
+    /// A SOL-denominated agreement whose deposit may be staked, paying the renter
+    /// `renter_share_percent` of the rewards.
+    pub fn with_yield(svm: &mut LiteSVM, renter_share_percent: u8) -> Self {
+        Self::build(svm, spl_token::native_mint::ID, Some(renter_share_percent))
+    }
+
+    fn build(
+        svm: &mut LiteSVM,
+        payment_mint: Pubkey,
+        yield_renter_share_percent: Option<u8>,
+    ) -> Self {
+        let landlord = funded_keypair(svm);
+        let renter = funded_keypair(svm);
+        let edition_mint = Pubkey::new_unique();
+        let decimals = if payment_mint == spl_token::native_mint::ID {
+            9
+        } else {
+            6
+        };
+        set_mint(svm, payment_mint, landlord.pubkey(), decimals, u64::MAX / 2);
+        set_mint(svm, edition_mint, landlord.pubkey(), 0, 1);
+
+        let (agreement, bump) = agreement_pda(&edition_mint);
+        set_program_account(
+            svm,
+            agreement,
+            &Agreement {
+                landlord: landlord.pubkey(),
+                renter: renter.pubkey(),
+                payment_mint,
+                edition_mint,
+                start_date: START_DATE,
+                end_date: START_DATE + 3 * MONTH,
+                rent_amount: RENT,
+                deposit_amount: DEPOSIT,
+                late_fee_percent: 10,
+                cancel_allowed_after: 1,
+                cancel_penalty_percent: 5,
+                payments_made: 0,
+                periods: 3,
+                schedule: monthly_schedule(),
+                missed_payments: 0,
+                status: AgreementStatus::Active,
+                move_out_date: 0,
+                yield_renter_share_percent,
+                staked_amount: 0,
+                stake_reserve: 0,
+                stake_funder: Pubkey::default(),
+                period_paid: 0,
+                late_fees_owed: 0,
+                late_fees_assessed: 0,
+                autopay: false,
+                tenants: Vec::new(),
+                min_renter_score: 0,
+                max_late_payment_percent: None,
+                successor: None,
+                tenancy_token: false,
+                bump,
+            },
+            8 + Agreement::INIT_SPACE,
+        );
+
+        let renter_account = set_renter(svm, &renter.pubkey(), 20);
+        set_landlord(svm, &landlord.pubkey(), 0, 1);
+        set_property(
+            svm,
+            &landlord.pubkey(),
+            &edition_mint,
+            PropertyStatus::Leased,
+        );
+        set_scoring_config(svm, Pubkey::new_unique(), scoring_model());
+        let deposit_vault = set_token_account(svm, agreement, payment_mint, DEPOSIT);
+        let nft_vault = set_token_account(svm, agreement, edition_mint, 1);
+        let renter_payment_ata =
+            set_token_account(svm, renter.pubkey(), payment_mint, WALLET_BALANCE);
+        let landlord_payment_ata = set_token_account(svm, landlord.pubkey(), payment_mint, 0);
+        warp_to(svm, START_DATE);
+
+        Self {
+            landlord,
+            renter,
+            payment_mint,
+            edition_mint,
+            agreement,
+            renter_account,
+            deposit_vault,
+            nft_vault,
+            renter_payment_ata,
+            landlord_payment_ata,
+        }
+    }
+
+    pub fn pay_rent_accounts(&self) -> capstone::accounts::MonthlyRent {
+        capstone::accounts::MonthlyRent {
+            signer: self.renter.pubkey(),
+            landlord: self.landlord.pubkey(),
+            deposit_vault: self.deposit_vault,
+            agreement: self.agreement,
+            renter: self.renter_account,
+            scoring_config: scoring_config_pda(),
+            ticket: None,
+            ticket_vault: None,
+            config: None,
+            payment_mint: self.payment_mint,
+            signer_payment_ata: self.renter_payment_ata,
+            landlord_payment_ata: self.landlord_payment_ata,
+            associated_token_program: associated_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn pay_partial_accounts(&self) -> capstone::accounts::PayPartial {
+        capstone::accounts::PayPartial {
+            signer: self.renter.pubkey(),
+            landlord: self.landlord.pubkey(),
+            agreement: self.agreement,
+            renter: self.renter_account,
+            scoring_config: scoring_config_pda(),
+            payment_mint: self.payment_mint,
+            signer_payment_ata: self.renter_payment_ata,
+            landlord_payment_ata: self.landlord_payment_ata,
+            associated_token_program: associated_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn add_co_renter_accounts(&self, co_renter: Pubkey) -> capstone::accounts::AddCoRenter {
+        capstone::accounts::AddCoRenter {
+            landlord: self.landlord.pubkey(),
+            renter: self.renter.pubkey(),
+            co_renter,
+            agreement: self.agreement,
+            co_renter_account: renter_pda(&co_renter),
+            payment_mint: self.payment_mint,
+            co_renter_payment_ata: get_associated_token_address_with_program_id(
+                &co_renter,
+                &self.payment_mint,
+                &spl_token::ID,
+            ),
+            renter_payment_ata: self.renter_payment_ata,
+            payment_token_program: spl_token::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn pay_share_accounts(&self, payer: Pubkey) -> capstone::accounts::PayShare {
+        capstone::accounts::PayShare {
+            signer: payer,
+            landlord: self.landlord.pubkey(),
+            agreement: self.agreement,
+            renter: renter_pda(&payer),
+            scoring_config: scoring_config_pda(),
+            payment_mint: self.payment_mint,
+            signer_payment_ata: get_associated_token_address_with_program_id(
+                &payer,
+                &self.payment_mint,
+                &spl_token::ID,
+            ),
+            landlord_payment_ata: self.landlord_payment_ata,
+            associated_token_program: associated_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn set_autopay_accounts(&self) -> capstone::accounts::SetAutopay {
+        capstone::accounts::SetAutopay {
+            renter: self.renter.pubkey(),
+            agreement: self.agreement,
+            payment_mint: self.payment_mint,
+            renter_payment_ata: self.renter_payment_ata,
+            payment_token_program: spl_token::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn collect_rent_accounts(&self, keeper: Pubkey) -> capstone::accounts::CollectRent {
+        capstone::accounts::CollectRent {
+            keeper,
+            landlord: self.landlord.pubkey(),
+            renter: self.renter.pubkey(),
+            agreement: self.agreement,
+            renter_account: self.renter_account,
+            scoring_config: scoring_config_pda(),
+            deposit_vault: self.deposit_vault,
+            payment_mint: self.payment_mint,
+            renter_payment_ata: self.renter_payment_ata,
+            landlord_payment_ata: self.landlord_payment_ata,
+            keeper_payment_ata: get_associated_token_address_with_program_id(
+                &keeper,
+                &self.payment_mint,
+                &spl_token::ID,
+            ),
+            associated_token_program: associated_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn pay_from_deposit_accounts(&self) -> capstone::accounts::PayFromDeposit {
+        capstone::accounts::PayFromDeposit {
+            landlord: self.landlord.pubkey(),
+            deposit_vault: self.deposit_vault,
+            agreement: self.agreement,
+            renter: self.renter_account,
+            scoring_config: scoring_config_pda(),
+            payment_mint: self.payment_mint,
+            landlord_payment_ata: self.landlord_payment_ata,
+            associated_token_program: associated_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn top_up_deposit_accounts(&self) -> capstone::accounts::TopUpDeposit {
+        capstone::accounts::TopUpDeposit {
+            signer: self.renter.pubkey(),
+            agreement: self.agreement,
+            deposit_vault: self.deposit_vault,
+            payment_mint: self.payment_mint,
+            signer_payment_ata: self.renter_payment_ata,
+            payment_token_program: spl_token::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn crank_accounts(&self, cranker: Pubkey) -> capstone::accounts::CrankMissedPayment {
+        capstone::accounts::CrankMissedPayment {
+            signer: cranker,
+            landlord: self.landlord.pubkey(),
+            agreement: self.agreement,
+            renter: self.renter_account,
+            scoring_config: scoring_config_pda(),
+            deposit_vault: self.deposit_vault,
+            payment_mint: self.payment_mint,
+            landlord_payment_ata: self.landlord_payment_ata,
+            associated_token_program: associated_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn close_accounts(&self) -> capstone::accounts::CloseAgreement {
+        capstone::accounts::CloseAgreement {
+            signer: self.renter.pubkey(),
+            deposit_vault: self.deposit_vault,
+            agreement: self.agreement,
+            renter: self.renter_account,
+            scoring_config: scoring_config_pda(),
+            landlord: self.landlord.pubkey(),
+            landlord_account: landlord_pda(&self.landlord.pubkey()),
+            property: property_pda(&self.edition_mint),
+            review: self.review(),
+            landlord_ata: get_associated_token_address_with_program_id(
+                &self.landlord.pubkey(),
+                &self.edition_mint,
+                &spl_token::ID,
+            ),
+            edition_mint: self.edition_mint,
+            nft_vault: self.nft_vault,
+            payment_mint: self.payment_mint,
+            landlord_payment_ata: self.landlord_payment_ata,
+            ticket: self.ticket(),
+            ticket_vault: None,
+            stake_account: None,
+            clock: None,
+            stake_program: None,
+            tenancy_mint: None,
+            renter_tenancy_ata: None,
+            token_2022_program: None,
+            associated_token_program: associated_token::ID,
+            token_program: spl_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn claim(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"claim", self.agreement.as_ref()], &capstone::ID).0
+    }
+
+    pub fn review(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"review", self.agreement.as_ref()], &capstone::ID).0
+    }
+
+    pub fn rate_counterparty_accounts(
+        &self,
+        signer: Pubkey,
+    ) -> capstone::accounts::RateCounterparty {
+        capstone::accounts::RateCounterparty {
+            signer,
+            review: self.review(),
+            landlord_account: landlord_pda(&self.landlord.pubkey()),
+            renter_account: self.renter_account,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn ticket(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"ticket", self.agreement.as_ref()], &capstone::ID).0
+    }
+
+    pub fn ticket_vault(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.ticket(),
+            &self.payment_mint,
+            &spl_token::ID,
+        )
+    }
+
+    pub fn open_ticket_accounts(&self) -> capstone::accounts::OpenTicket {
+        capstone::accounts::OpenTicket {
+            renter: self.renter.pubkey(),
+            agreement: self.agreement,
+            ticket: self.ticket(),
+            payment_mint: self.payment_mint,
+            ticket_vault: self.ticket_vault(),
+            associated_token_program: associated_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn fund_repair_accounts(&self) -> capstone::accounts::FundRepair {
+        capstone::accounts::FundRepair {
+            landlord: self.landlord.pubkey(),
+            agreement: self.agreement,
+            ticket: self.ticket(),
+            payment_mint: self.payment_mint,
+            landlord_payment_ata: self.landlord_payment_ata,
+            ticket_vault: self.ticket_vault(),
+            payment_token_program: spl_token::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn confirm_repair_accounts(&self, contractor: Pubkey) -> capstone::accounts::ConfirmRepair {
+        capstone::accounts::ConfirmRepair {
+            renter: self.renter.pubkey(),
+            landlord: self.landlord.pubkey(),
+            contractor,
+            ticket: self.ticket(),
+            payment_mint: self.payment_mint,
+            ticket_vault: self.ticket_vault(),
+            contractor_payment_ata: get_associated_token_address_with_program_id(
+                &contractor,
+                &self.payment_mint,
+                &spl_token::ID,
+            ),
+            landlord_payment_ata: self.landlord_payment_ata,
+            associated_token_program: associated_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn propose_deduction_accounts(&self) -> capstone::accounts::ProposeDeduction {
+        capstone::accounts::ProposeDeduction {
+            landlord: self.landlord.pubkey(),
+            agreement: self.agreement,
+            claim: self.claim(),
+            deposit_vault: self.deposit_vault,
+            payment_mint: self.payment_mint,
+            config: config_pda(),
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn respond_to_claim_accounts(&self) -> capstone::accounts::RespondToClaim {
+        capstone::accounts::RespondToClaim {
+            renter: self.renter.pubkey(),
+            agreement: self.agreement,
+            claim: self.claim(),
+            config: config_pda(),
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn resolve_dispute_accounts(&self, arbiter: Pubkey) -> capstone::accounts::ResolveDispute {
+        capstone::accounts::ResolveDispute {
+            arbiter,
+            config: config_pda(),
+            agreement: self.agreement,
+            claim: self.claim(),
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn settle_deposit_accounts(
+        &self,
+        signer: Pubkey,
+        with_claim: bool,
+    ) -> capstone::accounts::SettleDeposit {
+        capstone::accounts::SettleDeposit {
+            signer,
+            landlord: self.landlord.pubkey(),
+            renter: self.renter.pubkey(),
+            agreement: self.agreement,
+            claim: with_claim.then(|| self.claim()),
+            deposit_vault: self.deposit_vault,
+            payment_mint: self.payment_mint,
+            renter_payment_ata: self.renter_payment_ata,
+            landlord_payment_ata: self.landlord_payment_ata,
+            config: config_pda(),
+            associated_token_program: associated_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+    pub fn renewal(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"renewal", self.agreement.as_ref()], &capstone::ID).0
+    }
+
+    pub fn propose_renewal_accounts(&self) -> capstone::accounts::ProposeRenewal {
+        capstone::accounts::ProposeRenewal {
+            landlord: self.landlord.pubkey(),
+            agreement: self.agreement,
+            renewal: self.renewal(),
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn accept_renewal_accounts(&self) -> capstone::accounts::AcceptRenewal {
+        capstone::accounts::AcceptRenewal {
+            renter: self.renter.pubkey(),
+            landlord: self.landlord.pubkey(),
+            agreement: self.agreement,
+            renewal: self.renewal(),
+            deposit_vault: self.deposit_vault,
+            payment_mint: self.payment_mint,
+            renter_payment_ata: self.renter_payment_ata,
+            tenancy_mint: None,
+            token_2022_program: None,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn nominate_successor_accounts(&self) -> capstone::accounts::NominateSuccessor {
+        capstone::accounts::NominateSuccessor {
+            renter: self.renter.pubkey(),
+            agreement: self.agreement,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn transfer_agreement_accounts(
+        &self,
+        incoming_renter: Pubkey,
+    ) -> capstone::accounts::TransferAgreement {
+        capstone::accounts::TransferAgreement {
+            incoming_renter,
+            landlord: self.landlord.pubkey(),
+            renter: self.renter.pubkey(),
+            agreement: self.agreement,
+            incoming_renter_account: renter_pda(&incoming_renter),
+            deposit_vault: self.deposit_vault,
+            payment_mint: self.payment_mint,
+            incoming_renter_payment_ata: get_associated_token_address_with_program_id(
+                &incoming_renter,
+                &self.payment_mint,
+                &spl_token::ID,
+            ),
+            renter_payment_ata: self.renter_payment_ata,
+            tenancy_mint: None,
+            renter_tenancy_ata: None,
+            token_2022_program: None,
+            associated_token_program: associated_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn tenancy_mint(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"tenancy", self.agreement.as_ref()], &capstone::ID).0
+    }
+
+    pub fn renter_tenancy_ata(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            &self.renter.pubkey(),
+            &self.tenancy_mint(),
+            &spl_token_2022::ID,
+        )
+    }
+
+    pub fn issue_tenancy_token_accounts(&self) -> capstone::accounts::IssueTenancyToken {
+        capstone::accounts::IssueTenancyToken {
+            renter: self.renter.pubkey(),
+            agreement: self.agreement,
+            edition_mint: self.edition_mint,
+            edition_metadata: capstone::edition_metadata_address(&self.edition_mint),
+            tenancy_mint: self.tenancy_mint(),
+            renter_tenancy_ata: self.renter_tenancy_ata(),
+            token_2022_program: spl_token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn terminate_accounts(&self) -> capstone::accounts::TerminateAgreement {
+        capstone::accounts::TerminateAgreement {
+            landlord: self.landlord.pubkey(),
+            renter: self.renter.pubkey(),
+            agreement: self.agreement,
+            renter_account: self.renter_account,
+            landlord_account: landlord_pda(&self.landlord.pubkey()),
+            property: property_pda(&self.edition_mint),
+            scoring_config: scoring_config_pda(),
+            edition_mint: self.edition_mint,
+            nft_vault: self.nft_vault,
+            landlord_ata: get_associated_token_address_with_program_id(
+                &self.landlord.pubkey(),
+                &self.edition_mint,
+                &spl_token::ID,
+            ),
+            deposit_vault: self.deposit_vault,
+            payment_mint: self.payment_mint,
+            landlord_payment_ata: self.landlord_payment_ata,
+            renter_payment_ata: self.renter_payment_ata,
+            ticket: self.ticket(),
+            ticket_vault: None,
+            tenancy_mint: None,
+            renter_tenancy_ata: None,
+            token_2022_program: None,
+            associated_token_program: associated_token::ID,
+            token_program: spl_token::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn stake_account(&self) -> Pubkey {
+        Pubkey::find_program_address(&[b"stake", self.agreement.as_ref()], &capstone::ID).0
+    }
+
+    /// Close accounts with the stake accounts a staked deposit needs to start its cooldown.
+    pub fn close_staked_accounts(&self) -> capstone::accounts::CloseAgreement {
+        capstone::accounts::CloseAgreement {
+            stake_account: Some(self.stake_account()),
+            clock: Some(sysvar::clock::ID),
+            stake_program: Some(stake::program::ID),
+            ..self.close_accounts()
+        }
+    }
+
+    pub fn stake_deposit_accounts(&self, vote_account: Pubkey) -> capstone::accounts::StakeDeposit {
+        capstone::accounts::StakeDeposit {
+            renter: self.renter.pubkey(),
+            agreement: self.agreement,
+            deposit_vault: self.deposit_vault,
+            unwrap_account: Pubkey::find_program_address(
+                &[b"unwrap", self.agreement.as_ref()],
+                &capstone::ID,
+            )
+            .0,
+            stake_account: self.stake_account(),
+            vote_account,
+            payment_mint: self.payment_mint,
+            stake_history: sysvar::stake_history::ID,
+            stake_config: stake::config::ID,
+            clock: sysvar::clock::ID,
+            rent: sysvar::rent::ID,
+            stake_program: stake::program::ID,
+            payment_token_program: spl_token::ID,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn unstake_deposit_accounts(&self, signer: Pubkey) -> capstone::accounts::UnstakeDeposit {
+        capstone::accounts::UnstakeDeposit {
+            signer,
+            agreement: self.agreement,
+            stake_account: self.stake_account(),
+            clock: sysvar::clock::ID,
+            stake_program: stake::program::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+
+    pub fn withdraw_stake_accounts(&self, signer: Pubkey) -> capstone::accounts::WithdrawStake {
+        capstone::accounts::WithdrawStake {
+            signer,
+            landlord: self.landlord.pubkey(),
+            renter: self.renter.pubkey(),
+            stake_funder: self.renter.pubkey(),
+            agreement: self.agreement,
+            stake_account: self.stake_account(),
+            deposit_vault: self.deposit_vault,
+            payment_mint: self.payment_mint,
+            stake_history: sysvar::stake_history::ID,
+            clock: sysvar::clock::ID,
+            stake_program: stake::program::ID,
+            payment_token_program: spl_token::ID,
+            event_authority: event_authority(),
+            program: capstone::ID,
+        }
+    }
+}
+
+/// Writes an initialized vote account for a fresh validator identity to delegate to.
+pub fn set_vote_account(svm: &mut LiteSVM) -> Pubkey {
+    use anchor_lang::solana_program::vote::{
+        self,
+        state::{VoteInit, VoteState, VoteStateVersions},
+    };
+
+    let address = Pubkey::new_unique();
+    let vote_init = VoteInit {
+        node_pubkey: Pubkey::new_unique(),
+        authorized_voter: Pubkey::new_unique(),
+        authorized_withdrawer: Pubkey::new_unique(),
+        commission: 0,
+    };
+    let clock = svm.get_sysvar::<Clock>();
+    let versioned = VoteStateVersions::new_current(VoteState::new(&vote_init, &clock));
+    let mut data = vec![0; VoteState::size_of()];
+    VoteState::serialize(&versioned, &mut data).unwrap();
+    let lamports = svm.minimum_balance_for_rent_exemption(data.len());
+    svm.set_account(
+        address,
+        Account {
+            lamports,
+            data,
+            owner: vote::program::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+    address
+}
+
+/// Moves the clock into the next epoch, keeping the unix timestamp. Stake history is not
+/// recorded, so the stake program treats activations and cooldowns as complete.
+pub fn advance_epoch(svm: &mut LiteSVM) {
+    let clock = svm.get_sysvar::<Clock>();
+    let slots_per_epoch = svm.get_sysvar::<EpochSchedule>().slots_per_epoch;
+    svm.set_sysvar(&Clock {
+        slot: clock.slot + slots_per_epoch,
+        epoch: clock.epoch + 1,
+        ..clock
+    });
+}
+
+/// Like [`setup`], with the real Token Metadata program loaded from
+/// `tests/fixtures/mpl_token_metadata.so` so NFT minting and burning run for real.
+pub fn setup_with_metaplex() -> LiteSVM {
+    let mut svm = setup();
+    let fixture = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/mpl_token_metadata.so"
+    );
+    svm.add_program_from_file(metadata::ID, fixture)
+        .expect("fetch the Token Metadata program with tests/fixtures/fetch.sh");
+    svm
+}
+
+pub fn master_edition_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            metadata::ID.as_ref(),
+            mint.as_ref(),
+            b"edition",
+        ],
+        &metadata::ID,
+    )
+    .0
+}
+
+pub fn collection_mint_pda(landlord: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"collection_mint", landlord.as_ref()], &capstone::ID).0
+}
+
+pub fn init_landlord_accounts(landlord: &Pubkey) -> capstone::accounts::InitLandlord {
+    let collection_mint = collection_mint_pda(landlord);
+    capstone::accounts::InitLandlord {
+        landlord: *landlord,
+        landlord_account: landlord_pda(landlord),
+        collection_mint,
+        collection_token_account: get_associated_token_address_with_program_id(
+            landlord,
+            &collection_mint,
+            &NFT_TOKEN_PROGRAM,
+        ),
+        #[cfg(not(feature = "token-2022-nft"))]
+        metadata: edition_metadata_pda(&collection_mint),
+        #[cfg(not(feature = "token-2022-nft"))]
+        master_edition: master_edition_pda(&collection_mint),
+        system_program: system_program::ID,
+        associated_token_program: associated_token::ID,
+        #[cfg(not(feature = "token-2022-nft"))]
+        token_metadata_program: metadata::ID,
+        token_program: NFT_TOKEN_PROGRAM,
+        #[cfg(not(feature = "token-2022-nft"))]
+        rent: sysvar::rent::ID,
+    }
+}
+
+pub fn init_renter_accounts(renter: &Pubkey) -> capstone::accounts::InitRenter {
+    capstone::accounts::InitRenter {
+        signer: *renter,
+        renter: renter_pda(renter),
+        scoring_config: scoring_config_pda(),
+        system_program: system_program::ID,
+    }
+}
+
+pub fn edition_metadata_pda(edition_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", metadata::ID.as_ref(), edition_mint.as_ref()],
+        &metadata::ID,
+    )
+    .0
+}
+
+/// Writes a bare Metaplex `MetadataV1` account for `edition_mint`. The program only reads it, so
+/// the Token Metadata program itself does not need to be loaded.
+pub fn set_edition_metadata(svm: &mut LiteSVM, edition_mint: &Pubkey, name: &str, uri: &str) {
+    let mut data = vec![metadata::mpl_token_metadata::types::Key::MetadataV1 as u8];
+    data.extend_from_slice(Pubkey::new_unique().as_ref());
+    data.extend_from_slice(edition_mint.as_ref());
+    for field in [name, "RENT", uri] {
+        data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
+    // seller fee, no creators, primary sale, mutable, then every optional field unset
+    data.extend_from_slice(&[0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0]);
+    let lamports = svm.minimum_balance_for_rent_exemption(data.len());
+    svm.set_account(
+        edition_metadata_pda(edition_mint),
+        Account {
+            lamports,
+            data,
+            owner: metadata::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+/// Events the program emitted through `emit_cpi!`, decoded from its self-CPIs.
+pub fn events<T: anchor_lang::Event + AnchorDeserialize>(meta: &TransactionMetadata) -> Vec<T> {
+    meta.inner_instructions
+        .iter()
+        .flatten()
+        .filter_map(|inner| {
+            let data = inner
+                .instruction
+                .data
+                .strip_prefix(anchor_lang::event::EVENT_IX_TAG_LE)?
+                .strip_prefix(T::DISCRIMINATOR)?;
+            T::deserialize(&mut &data[..]).ok()
+        })
+        .collect()
+}
+
+pub fn instruction(data: impl InstructionData, accounts: impl ToAccountMetas) -> Instruction {
+    Instruction {
+        program_id: capstone::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
@@ -0,0 +1,42 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use capstone::{Agreement, Landlord, Property, PropertyStatus};
+use common::*;
+use solana_signer::Signer;
+
+#[test]
+fn renter_can_lease_two_units_from_the_same_landlord() {
+    let mut svm = setup();
+    let landlord = funded_keypair(&mut svm);
+    let renter = funded_keypair(&mut svm);
+    let payment_mint = anchor_lang::prelude::Pubkey::new_unique();
+    set_mint(&mut svm, payment_mint, landlord.pubkey(), 6, u64::MAX / 2);
+    set_renter(&mut svm, &renter.pubkey(), 20);
+    set_token_account(&mut svm, renter.pubkey(), payment_mint, WALLET_BALANCE);
+    warp_to(&mut svm, START_DATE);
+
+    let units = [
+        Listing::new(&mut svm, &landlord.pubkey(), payment_mint, 1),
+        Listing::new(&mut svm, &landlord.pubkey(), payment_mint, 2),
+    ];
+    for unit in &units {
+        let accounts = unit.take_accounts(&renter.pubkey(), &landlord.pubkey(), &payment_mint);
+        let agreement = accounts.agreement;
+        let deposit_vault = accounts.deposit_vault;
+        let ix = instruction(capstone::instruction::TakeEscrow {}, accounts);
+        send(&mut svm, ix, &renter, &[]).unwrap();
+
+        let taken: Agreement = fetch(&svm, &agreement);
+        assert_eq!(taken.edition_mint, unit.edition_mint);
+        assert_eq!(taken.renter, renter.pubkey());
+        assert_eq!(token_balance(&svm, &deposit_vault), DEPOSIT);
+        let property: Property = fetch(&svm, &property_pda(&unit.edition_mint));
+        assert_eq!(property.status, PropertyStatus::Leased);
+    }
+
+    let profile: Landlord = fetch(&svm, &landlord_pda(&landlord.pubkey()));
+    assert_eq!(profile.listing_count, 0);
+    assert_eq!(profile.active_agreements, 2);
+}
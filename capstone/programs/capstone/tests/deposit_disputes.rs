@@ -0,0 +1,214 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, system_program};
+use capstone::{error::ErrorCode, Config};
+use common::*;
+use litesvm::LiteSVM;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const MOVE_OUT: i64 = START_DATE + MONTH + DAY;
+const DEDUCTION: u64 = 500_000_000;
+
+fn moved_out_lease(svm: &mut LiteSVM) -> (ActiveAgreement, Keypair) {
+    let lease = ActiveAgreement::new(svm);
+    let arbiter = funded_keypair(svm);
+    set_config(svm, lease.landlord.pubkey(), arbiter.pubkey());
+
+    warp_to(svm, MOVE_OUT);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[]).unwrap();
+    (lease, arbiter)
+}
+
+fn propose(svm: &mut LiteSVM, lease: &ActiveAgreement) {
+    let ix = instruction(
+        capstone::instruction::ProposeDeduction {
+            amount: DEDUCTION,
+            evidence_uri: "https://example.com/move-out-photos.json".to_string(),
+        },
+        lease.propose_deduction_accounts(),
+    );
+    send(svm, ix, &lease.landlord, &[]).unwrap();
+}
+
+fn respond(svm: &mut LiteSVM, lease: &ActiveAgreement, accept: bool) {
+    let ix = instruction(
+        capstone::instruction::RespondToClaim { accept },
+        lease.respond_to_claim_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[]).unwrap();
+}
+
+#[allow(clippy::result_large_err)]
+fn settle(
+    svm: &mut LiteSVM,
+    lease: &ActiveAgreement,
+    with_claim: bool,
+) -> litesvm::types::TransactionResult {
+    let cranker = funded_keypair(svm);
+    let ix = instruction(
+        capstone::instruction::SettleDeposit {},
+        lease.settle_deposit_accounts(cranker.pubkey(), with_claim),
+    );
+    send(svm, ix, &cranker, &[])
+}
+
+fn assert_settled(svm: &LiteSVM, lease: &ActiveAgreement, landlord_amount: u64) {
+    assert_eq!(
+        token_balance(svm, &lease.landlord_payment_ata),
+        landlord_amount
+    );
+    assert_eq!(
+        token_balance(svm, &lease.renter_payment_ata),
+        WALLET_BALANCE + DEPOSIT - landlord_amount
+    );
+    assert!(svm.get_account(&lease.agreement).is_none());
+    assert!(svm.get_account(&lease.deposit_vault).is_none());
+}
+
+#[test]
+fn deposit_is_returned_when_no_claim_is_filed() {
+    let mut svm = setup();
+    let (lease, _) = moved_out_lease(&mut svm);
+
+    assert_error(settle(&mut svm, &lease, false), ErrorCode::ClaimWindowOpen);
+
+    warp_to(&mut svm, MOVE_OUT + CLAIM_WINDOW + 1);
+    settle(&mut svm, &lease, false).unwrap();
+    assert_settled(&svm, &lease, 0);
+}
+
+#[test]
+fn landlord_cannot_claim_after_the_window() {
+    let mut svm = setup();
+    let (lease, _) = moved_out_lease(&mut svm);
+    warp_to(&mut svm, MOVE_OUT + CLAIM_WINDOW + 1);
+
+    let ix = instruction(
+        capstone::instruction::ProposeDeduction {
+            amount: DEDUCTION,
+            evidence_uri: String::new(),
+        },
+        lease.propose_deduction_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.landlord, &[]),
+        ErrorCode::ClaimWindowClosed,
+    );
+}
+
+#[test]
+fn accepted_claim_splits_the_deposit() {
+    let mut svm = setup();
+    let (lease, _) = moved_out_lease(&mut svm);
+    propose(&mut svm, &lease);
+    respond(&mut svm, &lease, true);
+
+    settle(&mut svm, &lease, true).unwrap();
+    assert_settled(&svm, &lease, DEDUCTION);
+    assert!(svm.get_account(&lease.claim()).is_none());
+}
+
+#[test]
+fn unanswered_claim_is_accepted_after_the_response_window() {
+    let mut svm = setup();
+    let (lease, _) = moved_out_lease(&mut svm);
+    propose(&mut svm, &lease);
+
+    assert_error(settle(&mut svm, &lease, true), ErrorCode::ClaimPending);
+    assert_error(
+        settle(&mut svm, &lease, false),
+        ErrorCode::ClaimAccountMissing,
+    );
+
+    warp_to(&mut svm, MOVE_OUT + RESPONSE_WINDOW + 1);
+    settle(&mut svm, &lease, true).unwrap();
+    assert_settled(&svm, &lease, DEDUCTION);
+}
+
+#[test]
+fn arbiter_rules_on_a_disputed_claim() {
+    let mut svm = setup();
+    let (lease, arbiter) = moved_out_lease(&mut svm);
+    propose(&mut svm, &lease);
+    respond(&mut svm, &lease, false);
+
+    let ix = instruction(
+        capstone::instruction::ResolveDispute {
+            landlord_award: DEDUCTION,
+        },
+        lease.resolve_dispute_accounts(lease.landlord.pubkey()),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.landlord, &[]),
+        AnchorError::ConstraintHasOne,
+    );
+
+    let ix = instruction(
+        capstone::instruction::ResolveDispute {
+            landlord_award: DEDUCTION / 2,
+        },
+        lease.resolve_dispute_accounts(arbiter.pubkey()),
+    );
+    send(&mut svm, ix, &arbiter, &[]).unwrap();
+
+    settle(&mut svm, &lease, true).unwrap();
+    assert_settled(&svm, &lease, DEDUCTION / 2);
+}
+
+#[test]
+fn dispute_the_arbiter_ignores_returns_the_deposit() {
+    let mut svm = setup();
+    let (lease, _) = moved_out_lease(&mut svm);
+    propose(&mut svm, &lease);
+    respond(&mut svm, &lease, false);
+
+    assert_error(settle(&mut svm, &lease, true), ErrorCode::ClaimPending);
+
+    warp_to(&mut svm, MOVE_OUT + ARBITRATION_WINDOW + 1);
+    settle(&mut svm, &lease, true).unwrap();
+    assert_settled(&svm, &lease, 0);
+}
+
+#[test]
+fn only_the_upgrade_authority_creates_the_config() {
+    let mut svm = setup();
+    let deployer = funded_keypair(&mut svm);
+    let squatter = funded_keypair(&mut svm);
+    set_upgrade_authority(&mut svm, deployer.pubkey());
+
+    let init_config = |admin: &Keypair| {
+        instruction(
+            capstone::instruction::InitConfig {
+                arbiter: admin.pubkey(),
+                claim_window: CLAIM_WINDOW,
+                response_window: RESPONSE_WINDOW,
+                arbitration_window: ARBITRATION_WINDOW,
+                repair_sla: REPAIR_SLA,
+                rent_withhold_percent: RENT_WITHHOLD_PERCENT,
+            },
+            capstone::accounts::InitConfig {
+                admin: admin.pubkey(),
+                config: config_pda(),
+                program_data: program_data_pda(),
+                system_program: system_program::ID,
+            },
+        )
+    };
+    assert_error(
+        send(&mut svm, init_config(&squatter), &squatter, &[]),
+        ErrorCode::NotUpgradeAuthority,
+    );
+    assert!(svm.get_account(&config_pda()).is_none());
+
+    send(&mut svm, init_config(&deployer), &deployer, &[]).unwrap();
+    let config: Config = fetch(&svm, &config_pda());
+    assert_eq!(config.admin, deployer.pubkey());
+    assert_eq!(config.arbiter, deployer.pubkey());
+}
@@ -0,0 +1,154 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_spl::token::spl_token;
+use capstone::{
+    error::ErrorCode,
+    events::{DepositDrawdown, DepositToppedUp, RentPaid, ScoreChanged},
+    Agreement, Renter,
+};
+use common::*;
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_signer::Signer;
+
+// rent plus the fixture's 10% late fee
+const PERIOD_CHARGE: u64 = RENT + RENT / 10;
+
+#[allow(clippy::result_large_err)]
+fn pay_from_deposit(svm: &mut LiteSVM, lease: &ActiveAgreement) -> TransactionResult {
+    let ix = instruction(
+        capstone::instruction::PayFromDeposit {},
+        lease.pay_from_deposit_accounts(),
+    );
+    send(svm, ix, &lease.landlord, &[])
+}
+
+#[allow(clippy::result_large_err)]
+fn top_up(svm: &mut LiteSVM, lease: &ActiveAgreement) -> TransactionResult {
+    let ix = instruction(
+        capstone::instruction::TopUpDeposit {},
+        lease.top_up_deposit_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[])
+}
+
+#[test]
+fn late_rent_comes_from_a_wallet_that_covers_it() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    let meta = send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    let paid = events::<RentPaid>(&meta);
+    assert!(paid[0].late && !paid[0].from_deposit);
+    assert!(events::<DepositDrawdown>(&meta).is_empty());
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE - PERIOD_CHARGE
+    );
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT);
+    assert_eq!(
+        token_balance(&svm, &lease.landlord_payment_ata),
+        PERIOD_CHARGE
+    );
+
+    // still late, but not a missed payment
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!((renter.score, renter.late_payments), (18, 1));
+    assert_eq!(
+        fetch::<Agreement>(&svm, &lease.agreement).missed_payments,
+        0
+    );
+}
+
+#[test]
+fn someone_else_paying_late_rent_can_not_draw_on_the_deposit() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let friend = funded_keypair(&mut svm);
+    let friend_ata = set_token_account(&mut svm, friend.pubkey(), lease.payment_mint, 0);
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+
+    let ix = instruction(
+        capstone::instruction::PayRent {},
+        capstone::accounts::MonthlyRent {
+            signer: friend.pubkey(),
+            signer_payment_ata: friend_ata,
+            ..lease.pay_rent_accounts()
+        },
+    );
+    assert_error(
+        send(&mut svm, ix, &friend, &[]),
+        spl_token::error::TokenError::InsufficientFunds as u32,
+    );
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT);
+    assert_eq!(fetch::<Agreement>(&svm, &lease.agreement).payments_made, 0);
+}
+
+#[test]
+fn landlord_charges_a_missed_period_to_the_deposit() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    warp_to(&mut svm, START_DATE + MONTH + 3 * DAY);
+    assert_error(
+        pay_from_deposit(&mut svm, &lease),
+        ErrorCode::PaymentNotMissed,
+    );
+
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+    let ix = instruction(
+        capstone::instruction::PayFromDeposit {},
+        capstone::accounts::PayFromDeposit {
+            landlord: lease.renter.pubkey(),
+            ..lease.pay_from_deposit_accounts()
+        },
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        anchor_lang::error::ErrorCode::ConstraintHasOne,
+    );
+
+    let meta = pay_from_deposit(&mut svm, &lease).unwrap();
+    assert!(events::<RentPaid>(&meta)[0].from_deposit);
+    let drawdown = events::<DepositDrawdown>(&meta);
+    assert_eq!(drawdown[0].amount, PERIOD_CHARGE);
+    assert_eq!(drawdown[0].remaining, DEPOSIT - PERIOD_CHARGE);
+    assert_eq!(events::<ScoreChanged>(&meta)[0].new_score, 18);
+
+    assert_eq!(
+        token_balance(&svm, &lease.landlord_payment_ata),
+        PERIOD_CHARGE
+    );
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE
+    );
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!((agreement.payments_made, agreement.missed_payments), (1, 1));
+}
+
+#[test]
+fn top_up_restores_the_deposit_to_its_full_amount() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    assert_error(top_up(&mut svm, &lease), ErrorCode::DepositNotDepleted);
+
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+    pay_from_deposit(&mut svm, &lease).unwrap();
+
+    let meta = top_up(&mut svm, &lease).unwrap();
+    let topped_up = events::<DepositToppedUp>(&meta);
+    assert_eq!(topped_up[0].payer, lease.renter.pubkey());
+    assert_eq!(topped_up[0].amount, PERIOD_CHARGE);
+    assert_eq!(topped_up[0].balance, DEPOSIT);
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT);
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE - PERIOD_CHARGE
+    );
+    assert_error(top_up(&mut svm, &lease), ErrorCode::DepositNotDepleted);
+}
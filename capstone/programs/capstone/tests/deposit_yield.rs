@@ -0,0 +1,229 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::Space;
+use capstone::{
+    error::ErrorCode,
+    events::{StakeDeactivated, YieldDistributed},
+    Agreement,
+};
+use common::*;
+use litesvm::LiteSVM;
+use solana_signer::Signer;
+
+const REWARD: u64 = 40_000_000;
+
+fn stake(svm: &mut LiteSVM, lease: &ActiveAgreement, vote_account: anchor_lang::prelude::Pubkey) {
+    let ix = instruction(
+        capstone::instruction::StakeDeposit {},
+        lease.stake_deposit_accounts(vote_account),
+    );
+    send(svm, ix, &lease.renter, &[]).unwrap();
+}
+
+// stake rewards land as extra lamports on the stake account at the epoch boundary
+fn pay_epoch_rewards(svm: &mut LiteSVM, lease: &ActiveAgreement) {
+    advance_epoch(svm);
+    let mut stake_account = svm.get_account(&lease.stake_account()).unwrap();
+    stake_account.lamports += REWARD;
+    svm.set_account(lease.stake_account(), stake_account)
+        .unwrap();
+}
+
+#[test]
+fn staking_requires_yield_mode() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let vote_account = set_vote_account(&mut svm);
+
+    let ix = instruction(
+        capstone::instruction::StakeDeposit {},
+        lease.stake_deposit_accounts(vote_account),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::YieldNotEnabled,
+    );
+}
+
+#[test]
+fn staked_deposit_is_unwound_after_move_out_and_rewards_split() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::with_yield(&mut svm, 25);
+    let vote_account = set_vote_account(&mut svm);
+    let cranker = funded_keypair(&mut svm);
+
+    stake(&mut svm, &lease, vote_account);
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.staked_amount, DEPOSIT);
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), 0);
+    let stake_lamports = svm.get_account(&lease.stake_account()).unwrap().lamports;
+    assert_eq!(stake_lamports, DEPOSIT + agreement.stake_reserve);
+
+    pay_epoch_rewards(&mut svm, &lease);
+
+    // moving out after the cancel window starts the cooldown
+    warp_to(&mut svm, START_DATE + MONTH + DAY);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_staked_accounts(),
+    );
+    let meta = send(&mut svm, ix, &lease.renter, &[]).unwrap();
+    assert_eq!(events::<StakeDeactivated>(&meta).len(), 1);
+
+    // still cooling down in the deactivation epoch
+    let ix = instruction(
+        capstone::instruction::WithdrawStake {},
+        lease.withdraw_stake_accounts(cranker.pubkey()),
+    );
+    assert!(send(&mut svm, ix.clone(), &cranker, &[]).is_err());
+
+    advance_epoch(&mut svm);
+    let renter_before = svm.get_balance(&lease.renter.pubkey()).unwrap();
+    let landlord_before = svm.get_balance(&lease.landlord.pubkey()).unwrap();
+    let meta = send(&mut svm, ix, &cranker, &[]).unwrap();
+
+    let distributed = events::<YieldDistributed>(&meta);
+    assert_eq!(distributed[0].principal, DEPOSIT);
+    assert_eq!(distributed[0].renter_reward, REWARD / 4);
+    assert_eq!(distributed[0].landlord_reward, REWARD - REWARD / 4);
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT);
+    assert_eq!(
+        svm.get_balance(&lease.renter.pubkey()).unwrap(),
+        renter_before + REWARD / 4 + agreement.stake_reserve
+    );
+    assert_eq!(
+        svm.get_balance(&lease.landlord.pubkey()).unwrap(),
+        landlord_before + REWARD - REWARD / 4
+    );
+    assert!(svm.get_account(&lease.stake_account()).is_none());
+    assert_eq!(fetch::<Agreement>(&svm, &lease.agreement).staked_amount, 0);
+
+    // with the deposit liquid again the usual settlement runs
+    set_config(&mut svm, cranker.pubkey(), cranker.pubkey());
+    warp_to(&mut svm, START_DATE + MONTH + DAY + CLAIM_WINDOW + 1);
+    let ix = instruction(
+        capstone::instruction::SettleDeposit {},
+        lease.settle_deposit_accounts(cranker.pubkey(), false),
+    );
+    send(&mut svm, ix, &cranker, &[]).unwrap();
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE + DEPOSIT
+    );
+}
+
+#[test]
+fn staked_deposit_cannot_be_drawn_until_withdrawn() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::with_yield(&mut svm, 50);
+    let vote_account = set_vote_account(&mut svm);
+    stake(&mut svm, &lease, vote_account);
+    advance_epoch(&mut svm);
+
+    // two periods past their grace window
+    warp_to(&mut svm, START_DATE + 2 * MONTH + 4 * DAY);
+    let ix = instruction(
+        capstone::instruction::CrankMissedPayment {},
+        lease.crank_accounts(lease.landlord.pubkey()),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.landlord, &[]),
+        ErrorCode::DepositStaked,
+    );
+    let terminate = instruction(
+        capstone::instruction::TerminateAgreement {},
+        lease.terminate_accounts(),
+    );
+    assert_error(
+        send(&mut svm, terminate.clone(), &lease.landlord, &[]),
+        ErrorCode::DepositStaked,
+    );
+
+    let ix = instruction(
+        capstone::instruction::UnstakeDeposit {},
+        lease.unstake_deposit_accounts(lease.landlord.pubkey()),
+    );
+    send(&mut svm, ix, &lease.landlord, &[]).unwrap();
+    advance_epoch(&mut svm);
+    let ix = instruction(
+        capstone::instruction::WithdrawStake {},
+        lease.withdraw_stake_accounts(lease.landlord.pubkey()),
+    );
+    send(&mut svm, ix, &lease.landlord, &[]).unwrap();
+
+    send(&mut svm, terminate, &lease.landlord, &[]).unwrap();
+    assert!(svm.get_account(&lease.agreement).is_none());
+}
+
+#[test]
+fn move_out_with_staked_deposit() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::with_yield(&mut svm, 50);
+    let vote_account = set_vote_account(&mut svm);
+    stake(&mut svm, &lease, vote_account);
+
+    // the early-cancel penalty needs the deposit in the vault
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_staked_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::DepositStaked,
+    );
+
+    warp_to(&mut svm, START_DATE + MONTH + DAY);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::StakeAccountMissing,
+    );
+}
+
+#[test]
+fn stake_reserve_goes_back_to_the_renter_who_funded_it() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::with_yield(&mut svm, 25);
+    let vote_account = set_vote_account(&mut svm);
+    stake(&mut svm, &lease, vote_account);
+    pay_epoch_rewards(&mut svm, &lease);
+
+    let ix = instruction(
+        capstone::instruction::UnstakeDeposit {},
+        lease.unstake_deposit_accounts(lease.landlord.pubkey()),
+    );
+    send(&mut svm, ix, &lease.landlord, &[]).unwrap();
+    advance_epoch(&mut svm);
+
+    // the agreement names another renter by the time the stake is withdrawn
+    let successor = funded_keypair(&mut svm);
+    let mut agreement: Agreement = fetch(&svm, &lease.agreement);
+    agreement.renter = successor.pubkey();
+    set_program_account(
+        &mut svm,
+        lease.agreement,
+        &agreement,
+        8 + Agreement::INIT_SPACE,
+    );
+
+    let funder_before = svm.get_balance(&lease.renter.pubkey()).unwrap();
+    let successor_before = svm.get_balance(&successor.pubkey()).unwrap();
+    let mut accounts = lease.withdraw_stake_accounts(lease.landlord.pubkey());
+    accounts.renter = successor.pubkey();
+    let ix = instruction(capstone::instruction::WithdrawStake {}, accounts);
+    send(&mut svm, ix, &lease.landlord, &[]).unwrap();
+
+    assert_eq!(
+        svm.get_balance(&lease.renter.pubkey()).unwrap(),
+        funder_before + agreement.stake_reserve
+    );
+    assert_eq!(
+        svm.get_balance(&successor.pubkey()).unwrap(),
+        successor_before + REWARD / 4
+    );
+}
@@ -0,0 +1,172 @@
+#![cfg(all(feature = "test-sbf", not(feature = "token-2022-nft")))]
+
+mod common;
+
+use anchor_lang::{error::ErrorCode as AnchorError, prelude::Pubkey};
+use capstone::{error::ErrorCode, events::ListingUpdated, Escrow};
+use common::*;
+use litesvm::LiteSVM;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+fn listing(svm: &mut LiteSVM) -> (Keypair, Pubkey, Listing) {
+    let landlord = funded_keypair(svm);
+    let payment_mint = Pubkey::new_unique();
+    set_mint(svm, payment_mint, landlord.pubkey(), 6, u64::MAX / 2);
+    set_scoring_config(svm, Pubkey::new_unique(), scoring_model());
+    let listing = Listing::new(svm, &landlord.pubkey(), payment_mint, 1);
+    (landlord, payment_mint, listing)
+}
+
+fn update(
+    rent: u64,
+    months: u8,
+    cancel_allowed_after: u16,
+    uri: Option<String>,
+) -> capstone::instruction::UpdateEscrow {
+    capstone::instruction::UpdateEscrow {
+        monthly_rent: rent,
+        deposit_amount: 2 * rent,
+        late_fee_percent: 5,
+        min_renter_score: 18,
+        max_late_payment_percent: Some(20),
+        cancel_allowed_after,
+        cancel_penalty_percent: 10,
+        months,
+        schedule: monthly_schedule(),
+        yield_renter_share_percent: None,
+        nft_uri: uri,
+    }
+}
+
+#[test]
+fn landlord_reprices_an_open_listing() {
+    let mut svm = setup();
+    let (landlord, _, listing) = listing(&mut svm);
+
+    let ix = instruction(
+        update(2 * RENT, 6, 2, None),
+        listing.update_accounts(&landlord.pubkey()),
+    );
+    let meta = send(&mut svm, ix, &landlord, &[]).unwrap();
+    let updated = events::<ListingUpdated>(&meta);
+    assert_eq!(updated[0].monthly_rent, 2 * RENT);
+    assert!(!updated[0].uri_updated);
+
+    let escrow: Escrow = fetch(&svm, &listing.escrow);
+    assert_eq!(escrow.monthly_rent, 2 * RENT);
+    assert_eq!(escrow.deposit_amount, 4 * RENT);
+    assert_eq!(escrow.months, 6);
+    assert_eq!(escrow.min_renter_score, 18);
+    assert_eq!(escrow.max_late_payment_percent, Some(20));
+}
+
+#[test]
+fn update_is_bounded_and_landlord_only() {
+    let mut svm = setup();
+    let (landlord, _, listing) = listing(&mut svm);
+    let intruder = funded_keypair(&mut svm);
+
+    let ix = instruction(
+        update(RENT, 3, 1, None),
+        listing.update_accounts(&intruder.pubkey()),
+    );
+    assert_error(
+        send(&mut svm, ix, &intruder, &[]),
+        AnchorError::ConstraintHasOne,
+    );
+
+    for (rent, months, cancel_allowed_after) in [(0, 3, 1), (RENT, 3, 4)] {
+        let ix = instruction(
+            update(rent, months, cancel_allowed_after, None),
+            listing.update_accounts(&landlord.pubkey()),
+        );
+        assert_error(
+            send(&mut svm, ix, &landlord, &[]),
+            ErrorCode::InvalidEscrowTerms,
+        );
+    }
+    let ix = instruction(
+        update(RENT, 0, 0, None),
+        listing.update_accounts(&landlord.pubkey()),
+    );
+    assert_error(
+        send(&mut svm, ix, &landlord, &[]),
+        ErrorCode::InvalidSchedule,
+    );
+
+    let ix = instruction(
+        update(
+            RENT,
+            3,
+            1,
+            Some("https://example.com/unit-1.json".to_string()),
+        ),
+        listing.update_accounts(&landlord.pubkey()),
+    );
+    assert_error(
+        send(&mut svm, ix, &landlord, &[]),
+        ErrorCode::MetadataAccountsMissing,
+    );
+}
+
+#[test]
+fn update_rejects_a_deposit_the_lease_can_not_hold() {
+    let mut svm = setup();
+    let (landlord, _, listing) = listing(&mut svm);
+
+    for deposit_amount in [0, u64::MAX] {
+        let ix = instruction(
+            capstone::instruction::UpdateEscrow {
+                deposit_amount,
+                ..update(RENT, 3, 1, None)
+            },
+            listing.update_accounts(&landlord.pubkey()),
+        );
+        assert_error(
+            send(&mut svm, ix, &landlord, &[]),
+            ErrorCode::InvalidEscrowTerms,
+        );
+    }
+}
+
+#[test]
+fn update_rejects_a_score_the_model_never_reaches() {
+    let mut svm = setup();
+    let (landlord, _, listing) = listing(&mut svm);
+
+    let ix = instruction(
+        capstone::instruction::UpdateEscrow {
+            min_renter_score: scoring_model().max_score as u16 + 1,
+            ..update(RENT, 3, 1, None)
+        },
+        listing.update_accounts(&landlord.pubkey()),
+    );
+    assert_error(
+        send(&mut svm, ix, &landlord, &[]),
+        ErrorCode::InvalidEscrowTerms,
+    );
+}
+
+#[test]
+fn taken_listing_can_not_be_updated() {
+    let mut svm = setup();
+    let (landlord, payment_mint, listing) = listing(&mut svm);
+    let renter = funded_keypair(&mut svm);
+    set_renter(&mut svm, &renter.pubkey(), 20);
+    set_token_account(&mut svm, renter.pubkey(), payment_mint, WALLET_BALANCE);
+    let ix = instruction(
+        capstone::instruction::TakeEscrow {},
+        listing.take_accounts(&renter.pubkey(), &landlord.pubkey(), &payment_mint),
+    );
+    send(&mut svm, ix, &renter, &[]).unwrap();
+
+    let ix = instruction(
+        update(RENT, 3, 1, None),
+        listing.update_accounts(&landlord.pubkey()),
+    );
+    assert_error(
+        send(&mut svm, ix, &landlord, &[]),
+        AnchorError::AccountNotInitialized,
+    );
+}
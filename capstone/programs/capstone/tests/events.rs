@@ -0,0 +1,139 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use capstone::events::{
+    AgreementClosed, AgreementTaken, DepositDrawdown, DepositSettled, MoveOutStarted, RentPaid,
+    ScoreChanged,
+};
+use common::*;
+use solana_signer::Signer;
+
+const LATE_FEE: u64 = RENT / 10;
+
+#[test]
+fn take_escrow_emits_agreement_taken() {
+    let mut svm = setup();
+    let landlord = funded_keypair(&mut svm);
+    let renter = funded_keypair(&mut svm);
+    let payment_mint = anchor_lang::prelude::Pubkey::new_unique();
+    set_mint(&mut svm, payment_mint, landlord.pubkey(), 6, u64::MAX / 2);
+    set_renter(&mut svm, &renter.pubkey(), 20);
+    set_token_account(&mut svm, renter.pubkey(), payment_mint, WALLET_BALANCE);
+    warp_to(&mut svm, START_DATE);
+
+    let listing = Listing::new(&mut svm, &landlord.pubkey(), payment_mint, 1);
+    let accounts = listing.take_accounts(&renter.pubkey(), &landlord.pubkey(), &payment_mint);
+    let agreement = accounts.agreement;
+    let ix = instruction(capstone::instruction::TakeEscrow {}, accounts);
+    let meta = send(&mut svm, ix, &renter, &[]).unwrap();
+
+    let taken = events::<AgreementTaken>(&meta);
+    assert_eq!(taken.len(), 1);
+    assert_eq!(taken[0].agreement, agreement);
+    assert_eq!(taken[0].renter, renter.pubkey());
+    assert_eq!(taken[0].edition_mint, listing.edition_mint);
+    assert_eq!(taken[0].start_date, START_DATE);
+    assert_eq!(taken[0].rent_amount, RENT);
+    assert_eq!(taken[0].deposit_amount, DEPOSIT);
+}
+
+#[test]
+fn on_time_rent_emits_payment_and_score_change() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    let meta = send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    let paid = events::<RentPaid>(&meta);
+    assert_eq!(paid.len(), 1);
+    assert_eq!(paid[0].agreement, lease.agreement);
+    assert_eq!(paid[0].period, 0);
+    assert_eq!(paid[0].amount, RENT);
+    assert_eq!(paid[0].late_fee, 0);
+    assert!(!paid[0].late);
+    assert!(events::<DepositDrawdown>(&meta).is_empty());
+
+    let score = events::<ScoreChanged>(&meta);
+    assert_eq!(score.len(), 1);
+    assert_eq!(score[0].renter, lease.renter.pubkey());
+    assert_eq!((score[0].previous_score, score[0].new_score), (20, 22));
+}
+
+#[test]
+fn late_rent_emits_deposit_drawdown() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    // the renter's wallet cannot cover the late period, so the deposit does
+    set_token_account(&mut svm, lease.renter.pubkey(), lease.payment_mint, RENT);
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    let meta = send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    let paid = events::<RentPaid>(&meta);
+    assert!(paid[0].late && paid[0].from_deposit);
+    assert_eq!(paid[0].amount, RENT + LATE_FEE);
+    assert_eq!(paid[0].late_fee, LATE_FEE);
+
+    let drawdown = events::<DepositDrawdown>(&meta);
+    assert_eq!(drawdown.len(), 1);
+    assert_eq!(drawdown[0].amount, RENT + LATE_FEE);
+    assert_eq!(drawdown[0].remaining, DEPOSIT - RENT - LATE_FEE);
+
+    let score = events::<ScoreChanged>(&meta);
+    assert_eq!((score[0].previous_score, score[0].new_score), (20, 18));
+}
+
+#[test]
+fn crank_emits_missed_payment_events() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let cranker = funded_keypair(&mut svm);
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+
+    let ix = instruction(
+        capstone::instruction::CrankMissedPayment {},
+        lease.crank_accounts(cranker.pubkey()),
+    );
+    let meta = send(&mut svm, ix, &cranker, &[]).unwrap();
+
+    assert!(events::<RentPaid>(&meta)[0].from_deposit);
+    assert_eq!(events::<DepositDrawdown>(&meta)[0].amount, RENT + LATE_FEE);
+    assert_eq!(events::<ScoreChanged>(&meta)[0].new_score, 18);
+}
+
+#[test]
+fn move_out_and_settlement_emit_closing_events() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    set_config(&mut svm, lease.landlord.pubkey(), lease.landlord.pubkey());
+
+    let move_out_date = START_DATE + MONTH + DAY;
+    warp_to(&mut svm, move_out_date);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    let meta = send(&mut svm, ix, &lease.renter, &[]).unwrap();
+    let move_out = events::<MoveOutStarted>(&meta);
+    assert_eq!(move_out.len(), 1);
+    assert_eq!(move_out[0].move_out_date, move_out_date);
+    assert_eq!(move_out[0].cancel_penalty, 0);
+
+    warp_to(&mut svm, move_out_date + CLAIM_WINDOW + 1);
+    let ix = instruction(
+        capstone::instruction::SettleDeposit {},
+        lease.settle_deposit_accounts(lease.renter.pubkey(), false),
+    );
+    let meta = send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    let settled = events::<DepositSettled>(&meta);
+    assert_eq!(settled[0].landlord_amount, 0);
+    assert_eq!(settled[0].renter_amount, DEPOSIT);
+    let closed = events::<AgreementClosed>(&meta);
+    assert_eq!(closed.len(), 1);
+    assert_eq!(closed[0].edition_mint, lease.edition_mint);
+    assert!(!closed[0].terminated);
+}
@@ -0,0 +1,19 @@
+#!/usr/bin/env sh
+# Dumps the Token Metadata program from mainnet into this directory and checks it against the
+# checksum pinned in mpl_token_metadata.so.sha256, so every run tests against the same build.
+# Pass --pin to record the checksum of a fresh dump when moving to a new release on purpose.
+set -eu
+
+cd "$(dirname "$0")"
+solana program dump -u m metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s mpl_token_metadata.so
+
+if [ "${1:-}" = "--pin" ]; then
+    shasum -a 256 mpl_token_metadata.so > mpl_token_metadata.so.sha256
+    exit 0
+fi
+
+if ! shasum -a 256 -c mpl_token_metadata.so.sha256; then
+    rm -f mpl_token_metadata.so
+    echo "mainnet Token Metadata no longer matches the pinned build, re-pin with --pin" >&2
+    exit 1
+fi
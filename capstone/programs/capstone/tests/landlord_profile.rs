@@ -0,0 +1,50 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::Space;
+use capstone::{Agreement, Landlord, Property, PropertyStatus};
+use common::*;
+use litesvm::LiteSVM;
+use solana_signer::Signer;
+
+fn close(svm: &mut LiteSVM, lease: &ActiveAgreement) {
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[]).unwrap();
+}
+
+#[test]
+fn paid_through_lease_counts_as_completed() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let mut agreement: Agreement = fetch(&svm, &lease.agreement);
+    agreement.payments_made = u16::from(agreement.periods);
+    set_program_account(
+        &mut svm,
+        lease.agreement,
+        &agreement,
+        8 + Agreement::INIT_SPACE,
+    );
+    warp_to(&mut svm, agreement.end_date);
+    close(&mut svm, &lease);
+
+    let profile: Landlord = fetch(&svm, &landlord_pda(&lease.landlord.pubkey()));
+    assert_eq!(profile.active_agreements, 0);
+    assert_eq!(profile.completed_leases, 1);
+    let property: Property = fetch(&svm, &property_pda(&lease.edition_mint));
+    assert_eq!(property.status, PropertyStatus::Delisted);
+}
+
+#[test]
+fn early_move_out_is_not_a_completed_lease() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    close(&mut svm, &lease);
+
+    let profile: Landlord = fetch(&svm, &landlord_pda(&lease.landlord.pubkey()));
+    assert_eq!(profile.active_agreements, 0);
+    assert_eq!(profile.completed_leases, 0);
+}
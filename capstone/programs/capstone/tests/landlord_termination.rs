@@ -0,0 +1,151 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_spl::associated_token::get_associated_token_address;
+use capstone::{
+    error::ErrorCode,
+    events::{AgreementClosed, AgreementTerminated, TicketSettled},
+    Renter, Urgency,
+};
+use common::*;
+use litesvm::LiteSVM;
+use solana_signer::Signer;
+
+// rent plus the fixture's 10% late fee
+const PERIOD_CHARGE: u64 = RENT + RENT / 10;
+
+fn late_after(period: i64) -> i64 {
+    START_DATE + (period + 1) * MONTH + 3 * DAY
+}
+
+#[allow(clippy::result_large_err)]
+fn terminate(svm: &mut LiteSVM, lease: &ActiveAgreement) -> litesvm::types::TransactionResult {
+    let ix = instruction(
+        capstone::instruction::TerminateAgreement {},
+        lease.terminate_accounts(),
+    );
+    send(svm, ix, &lease.landlord, &[])
+}
+
+fn assert_terminated(svm: &LiteSVM, lease: &ActiveAgreement) {
+    let landlord_ata = get_associated_token_address(&lease.landlord.pubkey(), &lease.edition_mint);
+    assert_eq!(token_balance(svm, &landlord_ata), 1);
+    assert!(svm.get_account(&lease.agreement).is_none());
+    assert!(svm.get_account(&lease.nft_vault).is_none());
+    assert!(svm.get_account(&lease.deposit_vault).is_none());
+}
+
+#[test]
+fn landlord_cannot_terminate_a_renter_in_good_standing() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    assert_error(
+        terminate(&mut svm, &lease),
+        ErrorCode::TerminationNotAllowed,
+    );
+
+    // one late period with the deposit still covering it is not enough
+    warp_to(&mut svm, late_after(0) + 1);
+    assert_error(
+        terminate(&mut svm, &lease),
+        ErrorCode::TerminationNotAllowed,
+    );
+}
+
+#[test]
+fn landlord_terminates_after_missed_payments() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    warp_to(&mut svm, late_after(0) + 1);
+    let ix = instruction(
+        capstone::instruction::CrankMissedPayment {},
+        lease.crank_accounts(lease.landlord.pubkey()),
+    );
+    send(&mut svm, ix, &lease.landlord, &[]).unwrap();
+
+    warp_to(&mut svm, late_after(1) + 1);
+    let meta = terminate(&mut svm, &lease).unwrap();
+    assert_terminated(&svm, &lease);
+    assert_eq!(events::<AgreementTerminated>(&meta)[0].unpaid_periods, 2);
+    assert!(events::<AgreementClosed>(&meta)[0].terminated);
+
+    // the crank took the first period, the second is paid from what is left
+    let left_after_crank = DEPOSIT - PERIOD_CHARGE;
+    let arrears_paid = PERIOD_CHARGE.min(left_after_crank);
+    assert_eq!(
+        token_balance(&svm, &lease.landlord_payment_ata),
+        PERIOD_CHARGE + arrears_paid
+    );
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE + left_after_crank - arrears_paid
+    );
+
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!(renter.score, 20 - 2 - 2 * 5);
+    assert_eq!(renter.late_payments, 2);
+}
+
+#[test]
+fn landlord_terminates_when_the_deposit_is_exhausted() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    set_token_account(&mut svm, lease.agreement, lease.payment_mint, RENT / 2);
+
+    warp_to(&mut svm, late_after(0) + 1);
+    terminate(&mut svm, &lease).unwrap();
+    assert_terminated(&svm, &lease);
+
+    assert_eq!(token_balance(&svm, &lease.landlord_payment_ata), RENT / 2);
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE
+    );
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!(renter.score, 20 - 5);
+}
+
+#[test]
+fn renter_cannot_terminate_for_the_landlord() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    warp_to(&mut svm, late_after(1) + 1);
+
+    let mut accounts = lease.terminate_accounts();
+    accounts.landlord = lease.renter.pubkey();
+    accounts.landlord_ata =
+        get_associated_token_address(&lease.renter.pubkey(), &lease.edition_mint);
+    accounts.landlord_payment_ata = lease.renter_payment_ata;
+    let ix = instruction(capstone::instruction::TerminateAgreement {}, accounts);
+
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        anchor_lang::error::ErrorCode::ConstraintHasOne,
+    );
+}
+
+#[test]
+fn open_ticket_does_not_block_termination() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let ix = instruction(
+        capstone::instruction::OpenTicket {
+            description_hash: [7; 32],
+            urgency: Urgency::Urgent,
+        },
+        lease.open_ticket_accounts(),
+    );
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+    warp_to(&mut svm, late_after(1) + 1);
+
+    let mut accounts = lease.terminate_accounts();
+    accounts.ticket_vault = Some(lease.ticket_vault());
+    let ix = instruction(capstone::instruction::TerminateAgreement {}, accounts);
+    let meta = send(&mut svm, ix, &lease.landlord, &[]).unwrap();
+    assert_terminated(&svm, &lease);
+    assert_eq!(events::<TicketSettled>(&meta)[0].amount, 0);
+    assert!(svm.get_account(&lease.ticket()).is_none());
+    assert!(svm.get_account(&lease.ticket_vault()).is_none());
+}
@@ -0,0 +1,187 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use capstone::{error::ErrorCode, Agreement, RENEWAL_WINDOW};
+use common::*;
+use litesvm::LiteSVM;
+use solana_signer::Signer;
+
+const EXTRA_PERIODS: u8 = 6;
+
+fn propose(svm: &mut LiteSVM, lease: &ActiveAgreement, rent: u64, deposit: u64) {
+    let ix = instruction(
+        capstone::instruction::ProposeRenewal {
+            extra_periods: EXTRA_PERIODS,
+            rent_amount: rent,
+            deposit_amount: deposit,
+        },
+        lease.propose_renewal_accounts(),
+    );
+    send(svm, ix, &lease.landlord, &[]).unwrap();
+}
+
+#[allow(clippy::result_large_err)]
+fn accept(
+    svm: &mut LiteSVM,
+    lease: &ActiveAgreement,
+    rent: u64,
+    deposit: u64,
+) -> litesvm::types::TransactionResult {
+    let ix = instruction(
+        capstone::instruction::AcceptRenewal {
+            extra_periods: EXTRA_PERIODS,
+            rent_amount: rent,
+            deposit_amount: deposit,
+        },
+        lease.accept_renewal_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[])
+}
+
+#[test]
+fn accepted_renewal_extends_the_lease_and_tops_up_the_deposit() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let rent = RENT + RENT / 10;
+    let deposit = DEPOSIT + RENT;
+
+    propose(&mut svm, &lease, rent, deposit);
+    accept(&mut svm, &lease, rent, deposit).unwrap();
+
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.periods, 3 + EXTRA_PERIODS);
+    assert_eq!(
+        agreement.end_date,
+        agreement
+            .schedule
+            .due_date(START_DATE, u16::from(agreement.periods) - 1)
+            .unwrap()
+    );
+    assert_eq!(agreement.rent_amount, rent);
+    assert_eq!(agreement.deposit_amount, deposit);
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), deposit);
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE - RENT
+    );
+    assert_eq!(token_balance(&svm, &lease.nft_vault), 1);
+    assert!(svm.get_account(&lease.renewal()).is_none());
+}
+
+#[test]
+fn lower_deposit_is_released_to_the_renter() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    propose(&mut svm, &lease, RENT, DEPOSIT / 2);
+    accept(&mut svm, &lease, RENT, DEPOSIT / 2).unwrap();
+
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT / 2);
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE + DEPOSIT / 2
+    );
+}
+
+#[test]
+fn rent_change_above_the_cap_is_rejected() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    let ix = instruction(
+        capstone::instruction::ProposeRenewal {
+            extra_periods: EXTRA_PERIODS,
+            rent_amount: RENT + RENT / 5,
+            deposit_amount: DEPOSIT,
+        },
+        lease.propose_renewal_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.landlord, &[]),
+        ErrorCode::RentChangeTooLarge,
+    );
+}
+
+#[test]
+fn renter_only_accepts_the_terms_they_signed() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    propose(&mut svm, &lease, RENT, DEPOSIT);
+    propose(&mut svm, &lease, RENT + RENT / 10, DEPOSIT);
+
+    assert_error(
+        accept(&mut svm, &lease, RENT, DEPOSIT),
+        ErrorCode::RenewalTermsChanged,
+    );
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.periods, 3);
+}
+
+#[test]
+fn stale_renewal_cannot_be_accepted() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    propose(&mut svm, &lease, RENT, DEPOSIT);
+    warp_to(&mut svm, START_DATE + RENEWAL_WINDOW + 1);
+    assert_error(
+        accept(&mut svm, &lease, RENT, DEPOSIT),
+        ErrorCode::RenewalExpired,
+    );
+
+    // the landlord proposes again and the fresh terms can be accepted
+    propose(&mut svm, &lease, RENT + RENT / 10, DEPOSIT);
+    accept(&mut svm, &lease, RENT + RENT / 10, DEPOSIT).unwrap();
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.periods, 3 + EXTRA_PERIODS);
+}
+
+#[test]
+fn landlord_cannot_accept_their_own_renewal() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    propose(&mut svm, &lease, RENT, DEPOSIT);
+
+    let mut accounts = lease.accept_renewal_accounts();
+    accounts.renter = lease.landlord.pubkey();
+    accounts.renter_payment_ata = lease.landlord_payment_ata;
+    let ix = instruction(
+        capstone::instruction::AcceptRenewal {
+            extra_periods: EXTRA_PERIODS,
+            rent_amount: RENT,
+            deposit_amount: DEPOSIT,
+        },
+        accounts,
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.landlord, &[]),
+        anchor_lang::error::ErrorCode::ConstraintHasOne,
+    );
+}
+
+#[test]
+fn shared_lease_cannot_be_renewed() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let co_renter = funded_keypair(&mut svm);
+    set_renter(&mut svm, &co_renter.pubkey(), 20);
+    set_token_account(
+        &mut svm,
+        co_renter.pubkey(),
+        lease.payment_mint,
+        WALLET_BALANCE,
+    );
+    let ix = instruction(
+        capstone::instruction::AddCoRenter { share_percent: 40 },
+        lease.add_co_renter_accounts(co_renter.pubkey()),
+    );
+    send(&mut svm, ix, &co_renter, &[&lease.landlord, &lease.renter]).unwrap();
+
+    propose(&mut svm, &lease, RENT, DEPOSIT);
+    assert_error(
+        accept(&mut svm, &lease, RENT, DEPOSIT),
+        ErrorCode::SharedAgreement,
+    );
+}
@@ -0,0 +1,375 @@
+#![cfg(all(feature = "test-sbf", not(feature = "token-2022-nft")))]
+
+mod common;
+
+use anchor_lang::{prelude::Pubkey, Space};
+use capstone::{
+    Agreement, AgreementStatus, Escrow, Landlord, Property, PropertyStatus, Renter, Review,
+};
+use common::*;
+use litesvm::LiteSVM;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+// one signature at litesvm's default lamports per signature
+const FEE: i128 = 5_000;
+const TOKEN_ACCOUNT_LEN: usize = 165;
+const PROPERTY_ID: u64 = 7;
+const LATE_FEE: u64 = RENT / 10;
+const PENALTY: u64 = DEPOSIT * 5 / 100;
+
+fn lamports(svm: &LiteSVM, address: &Pubkey) -> i128 {
+    svm.get_account(address)
+        .map_or(0, |account| account.lamports as i128)
+}
+
+fn rent(svm: &LiteSVM, len: usize) -> i128 {
+    svm.minimum_balance_for_rent_exemption(len) as i128
+}
+
+/// Lamports held by a set of accounts, to check a payer covered exactly what they gained.
+struct Snapshot(Vec<(Pubkey, i128)>);
+
+impl Snapshot {
+    fn take(svm: &LiteSVM, accounts: &[Pubkey]) -> Self {
+        Self(accounts.iter().map(|a| (*a, lamports(svm, a))).collect())
+    }
+
+    fn gained(&self, svm: &LiteSVM) -> i128 {
+        self.0
+            .iter()
+            .map(|(address, before)| lamports(svm, address) - before)
+            .sum()
+    }
+}
+
+struct Market {
+    svm: LiteSVM,
+    landlord: Keypair,
+    renter: Keypair,
+    payment_mint: Pubkey,
+    listing: Listing,
+}
+
+/// A landlord with a minted listing and a renter with a profile, both made through the program.
+fn market() -> Market {
+    let mut svm = setup_with_metaplex();
+    let landlord = funded_keypair(&mut svm);
+    let renter = funded_keypair(&mut svm);
+    let payment_mint = Pubkey::new_unique();
+    set_mint(&mut svm, payment_mint, landlord.pubkey(), 6, u64::MAX / 2);
+    set_token_account(&mut svm, renter.pubkey(), payment_mint, WALLET_BALANCE);
+    set_scoring_config(&mut svm, Pubkey::new_unique(), scoring_model());
+    warp_to(&mut svm, START_DATE);
+
+    let accounts = init_landlord_accounts(&landlord.pubkey());
+    let created = Snapshot::take(
+        &svm,
+        &[
+            accounts.landlord_account,
+            accounts.collection_mint,
+            accounts.collection_token_account,
+            accounts.metadata,
+            accounts.master_edition,
+        ],
+    );
+    let before = lamports(&svm, &landlord.pubkey());
+    let ix = instruction(
+        capstone::instruction::InitLandlord {
+            name: "Harbour Lofts".to_string(),
+            symbol: "RENT".to_string(),
+            uri: "https://example.com/collection.json".to_string(),
+        },
+        accounts,
+    );
+    send(&mut svm, ix, &landlord, &[]).unwrap();
+    assert_eq!(
+        lamports(&svm, &landlord.pubkey()),
+        before - FEE - created.gained(&svm)
+    );
+    assert_eq!(
+        lamports(&svm, &landlord_pda(&landlord.pubkey())),
+        rent(&svm, 8 + Landlord::INIT_SPACE)
+    );
+
+    let listing = Listing::minted(&landlord.pubkey(), PROPERTY_ID);
+    let accounts = listing.create_accounts(&landlord.pubkey(), &payment_mint);
+    let created = Snapshot::take(
+        &svm,
+        &[
+            accounts.escrow,
+            accounts.landlord_account,
+            accounts.property,
+            accounts.edition_mint,
+            accounts.vault,
+            accounts.metadata,
+            accounts.master_edition,
+            accounts.collection_metadata,
+            accounts.collection_master_edition,
+        ],
+    );
+    let before = lamports(&svm, &landlord.pubkey());
+    let ix = instruction(
+        capstone::instruction::CreateEscrow {
+            property_id: PROPERTY_ID,
+            monthly_rent: RENT,
+            deposit_amount: DEPOSIT,
+            late_fee_percent: 10,
+            min_renter_score: 15,
+            max_late_payment_percent: None,
+            cancel_allowed_after: 1,
+            cancel_penalty_percent: 5,
+            months: 3,
+            schedule: monthly_schedule(),
+            yield_renter_share_percent: None,
+            property_details: property_details(),
+            nft_name: "Unit 7".to_string(),
+            nft_symbol: "RENT".to_string(),
+            nft_uri: "https://example.com/7.json".to_string(),
+        },
+        accounts,
+    );
+    send(&mut svm, ix, &landlord, &[]).unwrap();
+    assert_eq!(
+        lamports(&svm, &landlord.pubkey()),
+        before - FEE - created.gained(&svm)
+    );
+    assert_eq!(
+        lamports(&svm, &listing.escrow),
+        rent(&svm, 8 + Escrow::INIT_SPACE)
+    );
+    assert_eq!(
+        lamports(&svm, &property_pda(&listing.edition_mint)),
+        rent(&svm, 8 + Property::INIT_SPACE)
+    );
+    assert_eq!(token_balance(&svm, &listing.vault), 1);
+    assert_eq!(
+        fetch::<Landlord>(&svm, &landlord_pda(&landlord.pubkey())).listing_count,
+        1
+    );
+
+    let before = lamports(&svm, &renter.pubkey());
+    let ix = instruction(
+        capstone::instruction::InitRenter {},
+        init_renter_accounts(&renter.pubkey()),
+    );
+    send(&mut svm, ix, &renter, &[]).unwrap();
+    assert_eq!(
+        lamports(&svm, &renter.pubkey()),
+        before - FEE - rent(&svm, 8 + Renter::INIT_SPACE)
+    );
+
+    Market {
+        svm,
+        landlord,
+        renter,
+        payment_mint,
+        listing,
+    }
+}
+
+/// Takes the market's listing and pays the first period on time.
+fn take_and_pay_first_month(market: Market) -> (LiteSVM, ActiveAgreement) {
+    let Market {
+        mut svm,
+        landlord,
+        renter,
+        payment_mint,
+        listing,
+    } = market;
+
+    let landlord_before = lamports(&svm, &landlord.pubkey());
+    let renter_before = lamports(&svm, &renter.pubkey());
+    let escrow_rent = lamports(&svm, &listing.escrow);
+    let ix = instruction(
+        capstone::instruction::TakeEscrow {},
+        listing.take_accounts(&renter.pubkey(), &landlord.pubkey(), &payment_mint),
+    );
+    send(&mut svm, ix, &renter, &[]).unwrap();
+    let lease = ActiveAgreement::taken(landlord, renter, payment_mint, listing.edition_mint);
+
+    // the renter pays for the agreement and its two vaults, the closed escrow goes to the landlord
+    assert_eq!(
+        lamports(&svm, &lease.renter.pubkey()),
+        renter_before
+            - FEE
+            - rent(&svm, 8 + Agreement::INIT_SPACE)
+            - 2 * rent(&svm, TOKEN_ACCOUNT_LEN)
+    );
+    assert_eq!(
+        lamports(&svm, &lease.landlord.pubkey()),
+        landlord_before + escrow_rent
+    );
+    assert!(svm.get_account(&listing.escrow).is_none());
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT);
+    assert_eq!(token_balance(&svm, &lease.nft_vault), 1);
+    assert_eq!(
+        fetch::<Property>(&svm, &property_pda(&lease.edition_mint)).status,
+        PropertyStatus::Leased
+    );
+
+    // the first payment also opens the landlord's payment token account
+    let renter_before = lamports(&svm, &lease.renter.pubkey());
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+    assert_eq!(
+        lamports(&svm, &lease.renter.pubkey()),
+        renter_before - FEE - rent(&svm, TOKEN_ACCOUNT_LEN)
+    );
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE - DEPOSIT - RENT
+    );
+    assert_eq!(token_balance(&svm, &lease.landlord_payment_ata), RENT);
+    assert_eq!(fetch::<Renter>(&svm, &lease.renter_account).score, 22);
+
+    (svm, lease)
+}
+
+fn pay_rent(svm: &mut LiteSVM, lease: &ActiveAgreement) {
+    let before = lamports(svm, &lease.renter.pubkey());
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    send(svm, ix, &lease.renter, &[]).unwrap();
+    assert_eq!(lamports(svm, &lease.renter.pubkey()), before - FEE);
+}
+
+/// Closes the agreement, checking the renter paid for the review and the landlord's NFT account
+/// and the landlord got the NFT vault's rent back.
+fn close(svm: &mut LiteSVM, lease: &ActiveAgreement) {
+    let renter_before = lamports(svm, &lease.renter.pubkey());
+    let landlord_before = lamports(svm, &lease.landlord.pubkey());
+    let nft_vault_rent = lamports(svm, &lease.nft_vault);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[]).unwrap();
+    assert_eq!(
+        lamports(svm, &lease.renter.pubkey()),
+        renter_before - FEE - rent(svm, 8 + Review::INIT_SPACE) - rent(svm, TOKEN_ACCOUNT_LEN)
+    );
+    assert_eq!(
+        lamports(svm, &lease.landlord.pubkey()),
+        landlord_before + nft_vault_rent
+    );
+    assert!(svm.get_account(&lease.nft_vault).is_none());
+    assert_eq!(token_balance(svm, &lease.close_accounts().landlord_ata), 1);
+    assert_eq!(
+        fetch::<Agreement>(svm, &lease.agreement).status,
+        AgreementStatus::MoveOut
+    );
+}
+
+#[test]
+fn lease_runs_to_term_with_one_late_payment() {
+    let (mut svm, lease) = take_and_pay_first_month(market());
+
+    // period 1 is paid a day past its grace period, the renter's wallet covers rent and the late fee
+    warp_to(&mut svm, START_DATE + 2 * MONTH + 4 * DAY);
+    pay_rent(&mut svm, &lease);
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE - DEPOSIT - 2 * RENT - LATE_FEE
+    );
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT);
+    assert_eq!(
+        token_balance(&svm, &lease.landlord_payment_ata),
+        2 * RENT + LATE_FEE
+    );
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!(renter.score, 20);
+    assert_eq!((renter.total_payments, renter.late_payments), (2, 1));
+
+    warp_to(&mut svm, START_DATE + 2 * MONTH + 5 * DAY);
+    pay_rent(&mut svm, &lease);
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE - DEPOSIT - 3 * RENT - LATE_FEE
+    );
+    assert_eq!(fetch::<Renter>(&svm, &lease.renter_account).score, 22);
+
+    // every period is paid, so moving out completes the lease without a penalty
+    warp_to(&mut svm, START_DATE + 2 * MONTH + 6 * DAY);
+    close(&mut svm, &lease);
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!(renter.score, 32);
+    assert_eq!(renter.completed_leases, 1);
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT);
+    assert_eq!(
+        fetch::<Agreement>(&svm, &lease.agreement).missed_payments,
+        0
+    );
+    let landlord: Landlord = fetch(&svm, &landlord_pda(&lease.landlord.pubkey()));
+    assert_eq!(
+        (landlord.active_agreements, landlord.completed_leases),
+        (0, 1)
+    );
+}
+
+#[test]
+fn leaving_early_costs_the_cancel_penalty() {
+    let (mut svm, lease) = take_and_pay_first_month(market());
+
+    warp_to(&mut svm, START_DATE + DAY);
+    close(&mut svm, &lease);
+    assert_eq!(token_balance(&svm, &lease.deposit_vault), DEPOSIT - PENALTY);
+    assert_eq!(
+        token_balance(&svm, &lease.landlord_payment_ata),
+        RENT + PENALTY
+    );
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!(renter.score, 17);
+    assert_eq!(renter.completed_leases, 0);
+    assert_eq!(
+        fetch::<Property>(&svm, &property_pda(&lease.edition_mint)).status,
+        PropertyStatus::Delisted
+    );
+    let landlord: Landlord = fetch(&svm, &landlord_pda(&lease.landlord.pubkey()));
+    assert_eq!(
+        (landlord.active_agreements, landlord.completed_leases),
+        (0, 0)
+    );
+}
+
+#[test]
+fn closing_an_untaken_listing_burns_the_nft_and_refunds_the_landlord() {
+    let Market {
+        mut svm,
+        landlord,
+        listing,
+        ..
+    } = market();
+
+    let accounts = listing.refund_accounts(&landlord.pubkey());
+    let released = Snapshot::take(
+        &svm,
+        &[
+            accounts.escrow,
+            accounts.landlord_account,
+            accounts.property,
+            accounts.edition_mint,
+            accounts.metadata,
+            accounts.master_edition,
+            accounts.vault,
+            accounts.collection_metadata,
+        ],
+    );
+    let before = lamports(&svm, &landlord.pubkey());
+    let ix = instruction(capstone::instruction::CloseEscrow {}, accounts);
+    send(&mut svm, ix, &landlord, &[]).unwrap();
+
+    // everything the burned accounts and the closed escrow held lands with the landlord
+    let refunded = -released.gained(&svm);
+    assert!(refunded >= rent(&svm, 8 + Escrow::INIT_SPACE) + rent(&svm, TOKEN_ACCOUNT_LEN));
+    assert_eq!(lamports(&svm, &landlord.pubkey()), before - FEE + refunded);
+    assert!(svm.get_account(&listing.escrow).is_none());
+    assert!(svm.get_account(&listing.vault).is_none());
+    assert_eq!(
+        fetch::<Property>(&svm, &property_pda(&listing.edition_mint)).status,
+        PropertyStatus::Delisted
+    );
+    assert_eq!(
+        fetch::<Landlord>(&svm, &landlord_pda(&landlord.pubkey())).listing_count,
+        0
+    );
+}
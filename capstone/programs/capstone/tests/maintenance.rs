@@ -0,0 +1,250 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use capstone::{
+    error::ErrorCode,
+    events::{RentWithheld, RepairConfirmed, TicketSettled},
+    Agreement, MaintenanceTicket, TicketStatus, Urgency,
+};
+use common::*;
+use litesvm::LiteSVM;
+use solana_signer::Signer;
+
+const BUDGET: u64 = 300_000_000;
+
+fn open_ticket(svm: &mut LiteSVM, lease: &ActiveAgreement, urgency: Urgency) {
+    let ix = instruction(
+        capstone::instruction::OpenTicket {
+            description_hash: [7; 32],
+            urgency,
+        },
+        lease.open_ticket_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[]).unwrap();
+}
+
+fn fund_repair(svm: &mut LiteSVM, lease: &ActiveAgreement, contractor: Pubkey) {
+    let ix = instruction(
+        capstone::instruction::FundRepair {
+            contractor,
+            budget: BUDGET,
+        },
+        lease.fund_repair_accounts(),
+    );
+    send(svm, ix, &lease.landlord, &[]).unwrap();
+}
+
+#[test]
+fn confirmed_repair_pays_the_contractor_and_closes_the_ticket() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    set_token_account(
+        &mut svm,
+        lease.landlord.pubkey(),
+        lease.payment_mint,
+        BUDGET,
+    );
+    let contractor = Pubkey::new_unique();
+    open_ticket(&mut svm, &lease, Urgency::Routine);
+
+    let ix = instruction(
+        capstone::instruction::ConfirmRepair {},
+        lease.confirm_repair_accounts(contractor),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::InvalidTicketStatus,
+    );
+
+    fund_repair(&mut svm, &lease, contractor);
+    let ticket: MaintenanceTicket = fetch(&svm, &lease.ticket());
+    assert_eq!(ticket.status, TicketStatus::Funded);
+    assert_eq!(ticket.contractor, Some(contractor));
+    assert_eq!(token_balance(&svm, &lease.ticket_vault()), BUDGET);
+    assert_eq!(token_balance(&svm, &lease.landlord_payment_ata), 0);
+
+    let ix = instruction(
+        capstone::instruction::ConfirmRepair {},
+        lease.confirm_repair_accounts(Pubkey::new_unique()),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::InvalidTicketStatus,
+    );
+
+    let accounts = lease.confirm_repair_accounts(contractor);
+    let contractor_ata = accounts.contractor_payment_ata;
+    let ix = instruction(capstone::instruction::ConfirmRepair {}, accounts);
+    let meta = send(&mut svm, ix, &lease.renter, &[]).unwrap();
+    assert_eq!(events::<RepairConfirmed>(&meta)[0].amount, BUDGET);
+    assert_eq!(token_balance(&svm, &contractor_ata), BUDGET);
+    assert!(svm.get_account(&lease.ticket()).is_none());
+    assert!(svm.get_account(&lease.ticket_vault()).is_none());
+
+    // the agreement can take a new ticket once the last one is resolved
+    open_ticket(&mut svm, &lease, Urgency::Urgent);
+}
+
+#[test]
+fn token_budget_does_not_stop_the_repair_clock() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    set_token_account(
+        &mut svm,
+        lease.landlord.pubkey(),
+        lease.payment_mint,
+        BUDGET,
+    );
+    open_ticket(&mut svm, &lease, Urgency::Urgent);
+
+    // the minimum budget is a tenth of the fixture's rent
+    let ix = instruction(
+        capstone::instruction::FundRepair {
+            contractor: Pubkey::new_unique(),
+            budget: RENT / 10 - 1,
+        },
+        lease.fund_repair_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.landlord, &[]),
+        ErrorCode::RepairBudgetTooLow,
+    );
+    let ticket: MaintenanceTicket = fetch(&svm, &lease.ticket());
+    assert_eq!(ticket.status, TicketStatus::Open);
+}
+
+#[test]
+fn open_ticket_is_settled_to_the_landlord_at_move_out() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    set_token_account(
+        &mut svm,
+        lease.landlord.pubkey(),
+        lease.payment_mint,
+        BUDGET,
+    );
+    open_ticket(&mut svm, &lease, Urgency::Routine);
+    fund_repair(&mut svm, &lease, Pubkey::new_unique());
+    warp_to(&mut svm, START_DATE + MONTH + DAY);
+
+    // the ticket can't be left behind with the budget in its vault
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::TicketVaultMissing,
+    );
+
+    let mut accounts = lease.close_accounts();
+    accounts.ticket_vault = Some(lease.ticket_vault());
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        accounts,
+    );
+    let meta = send(&mut svm, ix, &lease.renter, &[]).unwrap();
+    assert_eq!(events::<TicketSettled>(&meta)[0].amount, BUDGET);
+    assert_eq!(token_balance(&svm, &lease.landlord_payment_ata), BUDGET);
+    assert!(svm.get_account(&lease.ticket()).is_none());
+    assert!(svm.get_account(&lease.ticket_vault()).is_none());
+}
+
+fn pay_rent_with_ticket(svm: &mut LiteSVM, lease: &ActiveAgreement) -> Vec<RentWithheld> {
+    let mut accounts = lease.pay_rent_accounts();
+    accounts.ticket = Some(lease.ticket());
+    accounts.ticket_vault = Some(lease.ticket_vault());
+    accounts.config = Some(config_pda());
+    let ix = instruction(capstone::instruction::PayRent {}, accounts);
+    let meta = send(svm, ix, &lease.renter, &[]).unwrap();
+    events::<RentWithheld>(&meta)
+}
+
+#[test]
+fn unfunded_urgent_ticket_past_the_sla_lets_the_renter_withhold_rent() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    set_config(&mut svm, Pubkey::new_unique(), Pubkey::new_unique());
+    open_ticket(&mut svm, &lease, Urgency::Urgent);
+
+    // still inside the SLA, the full rent goes to the landlord
+    assert!(pay_rent_with_ticket(&mut svm, &lease).is_empty());
+    assert_eq!(token_balance(&svm, &lease.landlord_payment_ata), RENT);
+
+    warp_to(&mut svm, START_DATE + MONTH);
+    let mut accounts = lease.pay_rent_accounts();
+    accounts.ticket = Some(lease.ticket());
+    let ix = instruction(capstone::instruction::PayRent {}, accounts);
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::ConfigAccountMissing,
+    );
+
+    let mut accounts = lease.pay_rent_accounts();
+    accounts.ticket = Some(lease.ticket());
+    accounts.config = Some(config_pda());
+    let ix = instruction(capstone::instruction::PayRent {}, accounts);
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::TicketVaultMissing,
+    );
+
+    let withheld = RENT * RENT_WITHHOLD_PERCENT as u64 / 100;
+    let event = pay_rent_with_ticket(&mut svm, &lease).remove(0);
+    assert_eq!(event.period, 1);
+    assert_eq!(event.amount, withheld);
+    assert_eq!(
+        token_balance(&svm, &lease.landlord_payment_ata),
+        2 * RENT - withheld
+    );
+    assert_eq!(token_balance(&svm, &lease.ticket_vault()), withheld);
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.payments_made, 2);
+    assert_eq!(
+        fetch::<MaintenanceTicket>(&svm, &lease.ticket()).withheld,
+        withheld
+    );
+}
+
+#[test]
+fn withheld_rent_is_held_once_and_released_to_the_landlord_on_repair() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    set_config(&mut svm, Pubkey::new_unique(), Pubkey::new_unique());
+    open_ticket(&mut svm, &lease, Urgency::Urgent);
+    pay_rent_with_ticket(&mut svm, &lease);
+
+    warp_to(&mut svm, START_DATE + MONTH);
+    let withheld = RENT * RENT_WITHHOLD_PERCENT as u64 / 100;
+    assert_eq!(pay_rent_with_ticket(&mut svm, &lease)[0].amount, withheld);
+
+    // the ticket is still overdue, but the renter already holds back a share of the rent for it
+    warp_to(&mut svm, START_DATE + 2 * MONTH);
+    assert!(pay_rent_with_ticket(&mut svm, &lease).is_empty());
+    assert_eq!(
+        token_balance(&svm, &lease.landlord_payment_ata),
+        3 * RENT - withheld
+    );
+    assert_eq!(token_balance(&svm, &lease.ticket_vault()), withheld);
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!((agreement.payments_made, agreement.period_paid), (3, 0));
+
+    set_token_account(
+        &mut svm,
+        lease.landlord.pubkey(),
+        lease.payment_mint,
+        BUDGET,
+    );
+    let contractor = Pubkey::new_unique();
+    fund_repair(&mut svm, &lease, contractor);
+    let accounts = lease.confirm_repair_accounts(contractor);
+    let contractor_ata = accounts.contractor_payment_ata;
+    let ix = instruction(capstone::instruction::ConfirmRepair {}, accounts);
+    let meta = send(&mut svm, ix, &lease.renter, &[]).unwrap();
+    let confirmed = events::<RepairConfirmed>(&meta).remove(0);
+    assert_eq!((confirmed.amount, confirmed.withheld), (BUDGET, withheld));
+    assert_eq!(token_balance(&svm, &contractor_ata), BUDGET);
+    assert_eq!(token_balance(&svm, &lease.landlord_payment_ata), withheld);
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use capstone::{
+    error::ErrorCode,
+    events::{PartialPaymentApplied, RentPaid},
+    Agreement, Renter,
+};
+use common::*;
+use litesvm::{types::TransactionResult, LiteSVM};
+
+// rent plus the fixture's 10% late fee
+const PERIOD_CHARGE: u64 = RENT + RENT / 10;
+
+#[allow(clippy::result_large_err)]
+fn pay_partial(svm: &mut LiteSVM, lease: &ActiveAgreement, amount: u64) -> TransactionResult {
+    let ix = instruction(
+        capstone::instruction::PayPartial { amount },
+        lease.pay_partial_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[])
+}
+
+#[test]
+fn period_counts_as_paid_only_once_fully_settled() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    let meta = pay_partial(&mut svm, &lease, RENT / 2).unwrap();
+    assert!(events::<RentPaid>(&meta).is_empty());
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.payments_made, 0);
+    assert_eq!(agreement.period_paid, RENT / 2);
+    assert_eq!(token_balance(&svm, &lease.landlord_payment_ata), RENT / 2);
+
+    let meta = pay_partial(&mut svm, &lease, RENT / 2).unwrap();
+    let rent_paid = events::<RentPaid>(&meta);
+    assert_eq!(rent_paid.len(), 1);
+    assert_eq!(rent_paid[0].amount, RENT);
+    assert!(!rent_paid[0].late);
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.payments_made, 1);
+    assert_eq!(agreement.period_paid, 0);
+    assert_eq!(fetch::<Renter>(&svm, &lease.renter_account).score, 22);
+}
+
+#[test]
+fn payment_goes_to_the_oldest_period_first() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    // period 0 is past its grace window and period 1 has started
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+    let meta = pay_partial(&mut svm, &lease, PERIOD_CHARGE + RENT / 2).unwrap();
+
+    let rent_paid = events::<RentPaid>(&meta);
+    assert_eq!(rent_paid.len(), 1);
+    assert_eq!(rent_paid[0].period, 0);
+    assert_eq!(rent_paid[0].late_fee, RENT / 10);
+    let applied = events::<PartialPaymentApplied>(&meta);
+    assert_eq!(applied[0].period_paid, RENT / 2);
+    assert_eq!(applied[0].late_fees_owed, 0);
+    assert_eq!(applied[0].amount_owed, RENT / 2);
+
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(agreement.payments_made, 1);
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!(renter.late_payments, 1);
+
+    // a full payment afterwards only charges what is left of the period
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+    assert_eq!(
+        token_balance(&svm, &lease.landlord_payment_ata),
+        PERIOD_CHARGE + RENT
+    );
+    assert_eq!(fetch::<Agreement>(&svm, &lease.agreement).payments_made, 2);
+}
+
+#[test]
+fn future_periods_cannot_be_prepaid() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    let meta = pay_partial(&mut svm, &lease, 5 * RENT).unwrap();
+    assert_eq!(events::<PartialPaymentApplied>(&meta)[0].amount, RENT);
+    assert_eq!(
+        token_balance(&svm, &lease.renter_payment_ata),
+        WALLET_BALANCE - RENT
+    );
+
+    assert_error(pay_partial(&mut svm, &lease, RENT), ErrorCode::RentNotDue);
+    assert_error(
+        pay_partial(&mut svm, &lease, 0),
+        ErrorCode::InvalidPaymentAmount,
+    );
+}
+
+#[test]
+fn renewal_waits_for_a_half_paid_period() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    pay_partial(&mut svm, &lease, RENT / 2).unwrap();
+
+    let terms = (6, RENT, DEPOSIT);
+    let ix = instruction(
+        capstone::instruction::ProposeRenewal {
+            extra_periods: terms.0,
+            rent_amount: terms.1,
+            deposit_amount: terms.2,
+        },
+        lease.propose_renewal_accounts(),
+    );
+    send(&mut svm, ix, &lease.landlord, &[]).unwrap();
+    let ix = instruction(
+        capstone::instruction::AcceptRenewal {
+            extra_periods: terms.0,
+            rent_amount: terms.1,
+            deposit_amount: terms.2,
+        },
+        lease.accept_renewal_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::ArrearsOutstanding,
+    );
+}
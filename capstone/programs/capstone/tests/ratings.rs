@@ -0,0 +1,86 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use capstone::{error::ErrorCode, events::RatingLeft, Landlord, Renter, Review, REVIEW_WINDOW};
+use common::*;
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+const MOVE_OUT_DATE: i64 = START_DATE + MONTH + DAY;
+
+fn moved_out_lease(svm: &mut LiteSVM) -> ActiveAgreement {
+    let lease = ActiveAgreement::new(svm);
+    warp_to(svm, MOVE_OUT_DATE);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    send(svm, ix, &lease.renter, &[]).unwrap();
+    lease
+}
+
+#[allow(clippy::result_large_err)]
+fn rate(
+    svm: &mut LiteSVM,
+    lease: &ActiveAgreement,
+    rater: &Keypair,
+    rating: u8,
+) -> TransactionResult {
+    let ix = instruction(
+        capstone::instruction::RateCounterparty {
+            rating,
+            review_hash: [rating; 32],
+        },
+        lease.rate_counterparty_accounts(rater.pubkey()),
+    );
+    send(svm, ix, rater, &[])
+}
+
+#[test]
+fn both_parties_rate_each_other_once() {
+    let mut svm = setup();
+    let lease = moved_out_lease(&mut svm);
+
+    let meta = rate(&mut svm, &lease, &lease.renter, 4).unwrap();
+    assert_eq!(
+        events::<RatingLeft>(&meta)[0].rated,
+        lease.landlord.pubkey()
+    );
+    rate(&mut svm, &lease, &lease.landlord, 5).unwrap();
+    assert_error(
+        rate(&mut svm, &lease, &lease.renter, 3),
+        ErrorCode::AlreadyRated,
+    );
+
+    let review: Review = fetch(&svm, &lease.review());
+    assert_eq!(review.landlord_rating, Some(4));
+    assert_eq!(review.renter_rating, Some(5));
+    assert_eq!(review.renter_review_hash, [5; 32]);
+    let landlord: Landlord = fetch(&svm, &landlord_pda(&lease.landlord.pubkey()));
+    assert_eq!((landlord.rating_total, landlord.rating_count), (4, 1));
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!((renter.rating_total, renter.rating_count), (5, 1));
+}
+
+#[test]
+fn only_parties_rate_within_the_window() {
+    let mut svm = setup();
+    let lease = moved_out_lease(&mut svm);
+    let outsider = funded_keypair(&mut svm);
+
+    assert_error(
+        rate(&mut svm, &lease, &outsider, 5),
+        ErrorCode::Unauthorized,
+    );
+    assert_error(
+        rate(&mut svm, &lease, &lease.renter, 6),
+        ErrorCode::InvalidRating,
+    );
+    warp_to(&mut svm, MOVE_OUT_DATE + REVIEW_WINDOW + 1);
+    assert_error(
+        rate(&mut svm, &lease, &lease.landlord, 5),
+        ErrorCode::ReviewWindowClosed,
+    );
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::{prelude::Pubkey, Space};
+use capstone::{error::ErrorCode, Escrow, Renter};
+use common::*;
+use litesvm::{types::TransactionResult, LiteSVM};
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+struct Market {
+    landlord: Keypair,
+    renter: Keypair,
+    payment_mint: Pubkey,
+    listing: Listing,
+}
+
+/// A fixture listing, asking for a score of 15, and a funded renter with the given record.
+fn market(svm: &mut LiteSVM, score: i16, total_payments: u32, late_payments: u32) -> Market {
+    let landlord = funded_keypair(svm);
+    let renter = funded_keypair(svm);
+    let payment_mint = Pubkey::new_unique();
+    set_mint(svm, payment_mint, landlord.pubkey(), 6, u64::MAX / 2);
+    set_token_account(svm, renter.pubkey(), payment_mint, WALLET_BALANCE);
+    let address = set_renter(svm, &renter.pubkey(), score);
+    let mut profile: Renter = fetch(svm, &address);
+    profile.total_payments = total_payments;
+    profile.late_payments = late_payments;
+    set_program_account(svm, address, &profile, 8 + Renter::INIT_SPACE);
+    warp_to(svm, START_DATE);
+    let listing = Listing::new(svm, &landlord.pubkey(), payment_mint, 1);
+    Market {
+        landlord,
+        renter,
+        payment_mint,
+        listing,
+    }
+}
+
+fn cap_late_payments(svm: &mut LiteSVM, market: &Market, percent: u8) {
+    let mut escrow: Escrow = fetch(svm, &market.listing.escrow);
+    escrow.max_late_payment_percent = Some(percent);
+    set_program_account(svm, market.listing.escrow, &escrow, 8 + Escrow::INIT_SPACE);
+}
+
+#[allow(clippy::result_large_err)]
+fn take(svm: &mut LiteSVM, market: &Market) -> TransactionResult {
+    let ix = instruction(
+        capstone::instruction::TakeEscrow {},
+        market.listing.take_accounts(
+            &market.renter.pubkey(),
+            &market.landlord.pubkey(),
+            &market.payment_mint,
+        ),
+    );
+    send(svm, ix, &market.renter, &[])
+}
+
+#[test]
+fn renter_below_the_minimum_score_cannot_take_the_listing() {
+    let mut svm = setup();
+    let below = market(&mut svm, 14, 0, 0);
+    assert_error(take(&mut svm, &below), ErrorCode::RenterScoreTooLow);
+
+    let at_minimum = market(&mut svm, 15, 0, 0);
+    take(&mut svm, &at_minimum).unwrap();
+}
+
+#[test]
+fn renter_over_the_late_payment_cap_cannot_take_the_listing() {
+    let mut svm = setup();
+    // 3 of 10 payments late is 30%, over a 20% cap
+    let over = market(&mut svm, 20, 10, 3);
+    cap_late_payments(&mut svm, &over, 20);
+    assert_error(take(&mut svm, &over), ErrorCode::TooManyLatePayments);
+
+    let at_cap = market(&mut svm, 20, 10, 2);
+    cap_late_payments(&mut svm, &at_cap, 20);
+    take(&mut svm, &at_cap).unwrap();
+}
@@ -0,0 +1,87 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::Space;
+use capstone::{events::ReputationAttested, Agreement, Attestation};
+use common::*;
+use litesvm::LiteSVM;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+
+fn attest(svm: &mut LiteSVM, renter: &Keypair) -> ReputationAttested {
+    let ix = instruction(
+        capstone::instruction::AttestReputation {},
+        attest_reputation_accounts(&renter.pubkey(), &renter.pubkey()),
+    );
+    let meta = send(svm, ix, renter, &[]).unwrap();
+    events::<ReputationAttested>(&meta).remove(0)
+}
+
+#[test]
+fn attestation_mirrors_the_renter_account() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    let attested = attest(&mut svm, &lease.renter);
+    assert_eq!(attested.score, 22);
+    assert_eq!(attested.total_payments, 1);
+    assert_eq!(attested.attested_at, START_DATE);
+
+    let attestation: Attestation = fetch(&svm, &attestation_pda(&lease.renter.pubkey()));
+    assert_eq!(attestation.renter, lease.renter.pubkey());
+    assert_eq!(attestation.attester, capstone::ID);
+    assert_eq!(attestation.late_payments, 0);
+    assert_eq!(attestation.completed_leases, 0);
+}
+
+#[test]
+fn completed_lease_shows_up_on_the_next_attestation() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    attest(&mut svm, &lease.renter);
+
+    let mut agreement: Agreement = fetch(&svm, &lease.agreement);
+    agreement.payments_made = u16::from(agreement.periods);
+    set_program_account(
+        &mut svm,
+        lease.agreement,
+        &agreement,
+        8 + Agreement::INIT_SPACE,
+    );
+    warp_to(&mut svm, agreement.end_date);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    // the stored snapshot only moves when the renter attests again
+    let stale: Attestation = fetch(&svm, &attestation_pda(&lease.renter.pubkey()));
+    assert_eq!(stale.completed_leases, 0);
+    let attested = attest(&mut svm, &lease.renter);
+    assert_eq!(attested.completed_leases, 1);
+    assert_eq!(attested.attested_at, agreement.end_date);
+}
+
+#[test]
+fn anyone_can_refresh_a_renters_attestation() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    attest(&mut svm, &lease.renter);
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    // a verifier pays for the refresh without the renter signing
+    let verifier = funded_keypair(&mut svm);
+    let ix = instruction(
+        capstone::instruction::AttestReputation {},
+        attest_reputation_accounts(&verifier.pubkey(), &lease.renter.pubkey()),
+    );
+    let meta = send(&mut svm, ix, &verifier, &[]).unwrap();
+    let attested = events::<ReputationAttested>(&meta).remove(0);
+    assert_eq!(attested.renter, lease.renter.pubkey());
+    assert_eq!(attested.total_payments, 1);
+}
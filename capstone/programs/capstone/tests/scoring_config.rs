@@ -0,0 +1,171 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use anchor_lang::{prelude::*, solana_program::instruction::Instruction, system_program};
+use capstone::{error::ErrorCode, Agreement, Renter, ScoringModel};
+use common::*;
+use solana_signer::Signer;
+
+fn init_scoring_config(admin: Pubkey, model: ScoringModel) -> Instruction {
+    instruction(
+        capstone::instruction::InitScoringConfig { model },
+        capstone::accounts::InitScoringConfig {
+            admin,
+            scoring_config: scoring_config_pda(),
+            program_data: program_data_pda(),
+            system_program: system_program::ID,
+        },
+    )
+}
+
+#[test]
+fn new_renters_start_at_the_configured_score() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    set_upgrade_authority(&mut svm, admin.pubkey());
+    let model = ScoringModel {
+        start_score: 500,
+        ..scoring_model()
+    };
+    send(
+        &mut svm,
+        init_scoring_config(admin.pubkey(), model),
+        &admin,
+        &[],
+    )
+    .unwrap();
+
+    let renter = funded_keypair(&mut svm);
+    let ix = instruction(
+        capstone::instruction::InitRenter {},
+        capstone::accounts::InitRenter {
+            signer: renter.pubkey(),
+            renter: renter_pda(&renter.pubkey()),
+            scoring_config: scoring_config_pda(),
+            system_program: system_program::ID,
+        },
+    );
+    send(&mut svm, ix, &renter, &[]).unwrap();
+
+    let account: Renter = fetch(&svm, &renter_pda(&renter.pubkey()));
+    assert_eq!(account.score, 500);
+}
+
+#[test]
+fn only_the_upgrade_authority_creates_the_model() {
+    let mut svm = setup();
+    let deployer = funded_keypair(&mut svm);
+    let squatter = funded_keypair(&mut svm);
+    set_upgrade_authority(&mut svm, deployer.pubkey());
+
+    let ix = init_scoring_config(squatter.pubkey(), scoring_model());
+    assert_error(
+        send(&mut svm, ix, &squatter, &[]),
+        ErrorCode::NotUpgradeAuthority,
+    );
+    assert!(svm.get_account(&scoring_config_pda()).is_none());
+
+    let ix = init_scoring_config(deployer.pubkey(), scoring_model());
+    send(&mut svm, ix, &deployer, &[]).unwrap();
+}
+
+#[test]
+fn only_the_admin_updates_the_model_and_it_must_be_valid() {
+    let mut svm = setup();
+    let admin = funded_keypair(&mut svm);
+    let intruder = funded_keypair(&mut svm);
+    set_scoring_config(&mut svm, admin.pubkey(), scoring_model());
+
+    let update = |signer: Pubkey, model: ScoringModel| {
+        instruction(
+            capstone::instruction::UpdateScoringConfig { model },
+            capstone::accounts::UpdateScoringConfig {
+                admin: signer,
+                scoring_config: scoring_config_pda(),
+            },
+        )
+    };
+
+    assert_error(
+        send(
+            &mut svm,
+            update(intruder.pubkey(), scoring_model()),
+            &intruder,
+            &[],
+        ),
+        anchor_lang::error::ErrorCode::ConstraintHasOne,
+    );
+    let inverted_bounds = ScoringModel {
+        min_score: 100,
+        max_score: 0,
+        ..scoring_model()
+    };
+    assert_error(
+        send(
+            &mut svm,
+            update(admin.pubkey(), inverted_bounds),
+            &admin,
+            &[],
+        ),
+        ErrorCode::InvalidScoringModel,
+    );
+}
+
+#[test]
+fn late_penalty_grows_with_days_past_the_due_date() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let model = ScoringModel {
+        late_penalty_per_day: 1,
+        ..scoring_model()
+    };
+    set_scoring_config(&mut svm, Pubkey::new_unique(), model);
+
+    // due at the end of the first month, paid four days later
+    warp_to(&mut svm, START_DATE + MONTH + 4 * DAY);
+    let ix = instruction(capstone::instruction::PayRent {}, lease.pay_rent_accounts());
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!(renter.score, 20 - 2 - 4);
+}
+
+#[test]
+fn fully_paid_lease_earns_the_completion_bonus() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let mut agreement: Agreement = fetch(&svm, &lease.agreement);
+    agreement.payments_made = u16::from(agreement.periods);
+    set_program_account(
+        &mut svm,
+        lease.agreement,
+        &agreement,
+        8 + Agreement::INIT_SPACE,
+    );
+
+    warp_to(&mut svm, agreement.end_date);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!(renter.score, 20 + 10);
+}
+
+#[test]
+fn leaving_before_the_cancel_date_costs_score() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    let renter: Renter = fetch(&svm, &lease.renter_account);
+    assert_eq!(renter.score, 20 - 5);
+}
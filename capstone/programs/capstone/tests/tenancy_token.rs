@@ -0,0 +1,206 @@
+#![cfg(all(feature = "test-sbf", not(feature = "token-2022-nft")))]
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{
+    associated_token::{
+        get_associated_token_address_with_program_id,
+        spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+    },
+    token_2022::spl_token_2022::{
+        self,
+        extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
+        state::{Account as TokenAccount, Mint},
+    },
+    token_2022_extensions::spl_token_metadata_interface::state::TokenMetadata,
+};
+use capstone::{
+    error::ErrorCode, events::TenancyTokenIssued, Agreement, END_DATE_FIELD, RENT_AMOUNT_FIELD,
+};
+use common::*;
+use litesvm::LiteSVM;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+fn issue(svm: &mut LiteSVM, lease: &ActiveAgreement) -> TenancyTokenIssued {
+    set_edition_metadata(
+        svm,
+        &lease.edition_mint,
+        "Unit 4B",
+        "https://example.com/4b.json",
+    );
+    let ix = instruction(
+        capstone::instruction::IssueTenancyToken {},
+        lease.issue_tenancy_token_accounts(),
+    );
+    let meta = send(svm, ix, &lease.renter, &[]).unwrap();
+    events::<TenancyTokenIssued>(&meta).remove(0)
+}
+
+fn tenancy_balance(svm: &LiteSVM, address: &Pubkey) -> u64 {
+    let account = svm.get_account(address).expect("token account missing");
+    StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+#[test]
+fn renter_cannot_move_the_tenancy_token() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    let issued = issue(&mut svm, &lease);
+    assert_eq!(issued.tenancy_mint, lease.tenancy_mint());
+    assert!(fetch::<Agreement>(&svm, &lease.agreement).tenancy_token);
+    assert_eq!(tenancy_balance(&svm, &lease.renter_tenancy_ata()), 1);
+
+    let mint = svm.get_account(&lease.tenancy_mint()).unwrap();
+    let mint = StateWithExtensions::<Mint>::unpack(&mint.data).unwrap();
+    assert_eq!(mint.base.supply, 1);
+    let extensions = mint.get_extension_types().unwrap();
+    assert!(extensions.contains(&ExtensionType::NonTransferable));
+    assert!(extensions.contains(&ExtensionType::PermanentDelegate));
+
+    let friend = funded_keypair(&mut svm);
+    let friend_ata = get_associated_token_address_with_program_id(
+        &friend.pubkey(),
+        &lease.tenancy_mint(),
+        &spl_token_2022::ID,
+    );
+    let create_ix = create_associated_token_account_idempotent(
+        &lease.renter.pubkey(),
+        &friend.pubkey(),
+        &lease.tenancy_mint(),
+        &spl_token_2022::ID,
+    );
+    let transfer_ix = spl_token_2022::instruction::transfer_checked(
+        &spl_token_2022::ID,
+        &lease.renter_tenancy_ata(),
+        &lease.tenancy_mint(),
+        &friend_ata,
+        &lease.renter.pubkey(),
+        &[],
+        1,
+        0,
+    )
+    .unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[create_ix, transfer_ix],
+        Some(&lease.renter.pubkey()),
+        &[&lease.renter],
+        svm.latest_blockhash(),
+    );
+    assert!(svm.send_transaction(tx).is_err());
+    assert_eq!(tenancy_balance(&svm, &lease.renter_tenancy_ata()), 1);
+
+    let ix = instruction(
+        capstone::instruction::IssueTenancyToken {},
+        lease.issue_tenancy_token_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::TenancyTokenIssued,
+    );
+}
+
+#[test]
+fn closing_the_agreement_burns_the_tenancy_token() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    issue(&mut svm, &lease);
+    warp_to(&mut svm, START_DATE + MONTH + DAY);
+
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        lease.close_accounts(),
+    );
+    assert_error(
+        send(&mut svm, ix, &lease.renter, &[]),
+        ErrorCode::TenancyTokenAccountsMissing,
+    );
+
+    let mut accounts = lease.close_accounts();
+    accounts.tenancy_mint = Some(lease.tenancy_mint());
+    accounts.renter_tenancy_ata = Some(lease.renter_tenancy_ata());
+    accounts.token_2022_program = Some(spl_token_2022::ID);
+    let ix = instruction(
+        capstone::instruction::CloseAgreementTransferNft {},
+        accounts,
+    );
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+
+    assert_eq!(tenancy_balance(&svm, &lease.renter_tenancy_ata()), 0);
+    assert!(!fetch::<Agreement>(&svm, &lease.agreement).tenancy_token);
+}
+
+fn tenancy_field(svm: &LiteSVM, mint: &Pubkey, key: &str) -> String {
+    let account = svm.get_account(mint).expect("tenancy mint missing");
+    let metadata = StateWithExtensions::<Mint>::unpack(&account.data)
+        .unwrap()
+        .get_variable_len_extension::<TokenMetadata>()
+        .unwrap();
+    metadata
+        .additional_metadata
+        .into_iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v)
+        .expect("field missing")
+}
+
+#[test]
+fn renewal_rewrites_the_term_and_rent_on_the_tenancy_token() {
+    let mut svm = setup();
+    let lease = ActiveAgreement::new(&mut svm);
+    issue(&mut svm, &lease);
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(
+        tenancy_field(&svm, &lease.tenancy_mint(), END_DATE_FIELD),
+        agreement.end_date.to_string()
+    );
+
+    let terms = (12, RENT + RENT / 10, DEPOSIT);
+    let ix = instruction(
+        capstone::instruction::ProposeRenewal {
+            extra_periods: terms.0,
+            rent_amount: terms.1,
+            deposit_amount: terms.2,
+        },
+        lease.propose_renewal_accounts(),
+    );
+    send(&mut svm, ix, &lease.landlord, &[]).unwrap();
+    let accept = |accounts| {
+        instruction(
+            capstone::instruction::AcceptRenewal {
+                extra_periods: terms.0,
+                rent_amount: terms.1,
+                deposit_amount: terms.2,
+            },
+            accounts,
+        )
+    };
+    assert_error(
+        send(
+            &mut svm,
+            accept(lease.accept_renewal_accounts()),
+            &lease.renter,
+            &[],
+        ),
+        ErrorCode::TenancyTokenAccountsMissing,
+    );
+
+    let mut accounts = lease.accept_renewal_accounts();
+    accounts.tenancy_mint = Some(lease.tenancy_mint());
+    accounts.token_2022_program = Some(spl_token_2022::ID);
+    send(&mut svm, accept(accounts), &lease.renter, &[]).unwrap();
+
+    let agreement: Agreement = fetch(&svm, &lease.agreement);
+    assert_eq!(
+        tenancy_field(&svm, &lease.tenancy_mint(), END_DATE_FIELD),
+        agreement.end_date.to_string()
+    );
+    assert_eq!(
+        tenancy_field(&svm, &lease.tenancy_mint(), RENT_AMOUNT_FIELD),
+        terms.1.to_string()
+    );
+}
@@ -0,0 +1,222 @@
+#![cfg(all(feature = "test-sbf", feature = "token-2022-nft"))]
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            mint_close_authority::MintCloseAuthority, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::{Account as TokenAccount, Mint},
+    },
+    token_2022_extensions::spl_token_metadata_interface::state::TokenMetadata,
+};
+use capstone::{
+    events::ListingUpdated,
+    instructions::token_2022::{RENT_FIELD, TERM_FIELD},
+};
+use common::*;
+use litesvm::LiteSVM;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use spl_token_group_interface::state::{TokenGroup, TokenGroupMember};
+
+const PROPERTY_ID: u64 = 7;
+
+fn metadata(svm: &LiteSVM, mint: &Pubkey) -> TokenMetadata {
+    let account = svm.get_account(mint).expect("mint missing");
+    StateWithExtensions::<Mint>::unpack(&account.data)
+        .unwrap()
+        .get_variable_len_extension::<TokenMetadata>()
+        .unwrap()
+}
+
+fn field<'a>(metadata: &'a TokenMetadata, key: &str) -> Option<&'a str> {
+    metadata
+        .additional_metadata
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+fn nft_balance(svm: &LiteSVM, address: &Pubkey) -> u64 {
+    let account = svm.get_account(address).expect("token account missing");
+    StateWithExtensions::<TokenAccount>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+/// Opens the landlord's collection and lists `PROPERTY_ID` through the program.
+fn list(svm: &mut LiteSVM, landlord: &Keypair, payment_mint: &Pubkey) -> Listing {
+    let ix = instruction(
+        capstone::instruction::InitLandlord {
+            name: "Harbour Lofts".to_string(),
+            symbol: "RENT".to_string(),
+            uri: "https://example.com/collection.json".to_string(),
+        },
+        init_landlord_accounts(&landlord.pubkey()),
+    );
+    send(svm, ix, landlord, &[]).unwrap();
+
+    let listing = Listing::minted(&landlord.pubkey(), PROPERTY_ID);
+    let ix = instruction(
+        capstone::instruction::CreateEscrow {
+            property_id: PROPERTY_ID,
+            monthly_rent: RENT,
+            deposit_amount: DEPOSIT,
+            late_fee_percent: 10,
+            min_renter_score: 15,
+            max_late_payment_percent: None,
+            cancel_allowed_after: 1,
+            cancel_penalty_percent: 5,
+            months: 3,
+            schedule: monthly_schedule(),
+            yield_renter_share_percent: None,
+            property_details: property_details(),
+            nft_name: "Unit 7".to_string(),
+            nft_symbol: "RENT".to_string(),
+            nft_uri: "https://example.com/7.json".to_string(),
+        },
+        listing.create_accounts(&landlord.pubkey(), payment_mint),
+    );
+    send(svm, ix, landlord, &[]).unwrap();
+    listing
+}
+
+#[test]
+fn edition_nft_carries_the_listing_terms_in_the_landlord_collection() {
+    let mut svm = setup();
+    let landlord = funded_keypair(&mut svm);
+    let payment_mint = Pubkey::new_unique();
+    set_mint(&mut svm, payment_mint, landlord.pubkey(), 6, u64::MAX / 2);
+    set_scoring_config(&mut svm, Pubkey::new_unique(), scoring_model());
+    let listing = list(&mut svm, &landlord, &payment_mint);
+
+    let collection_mint = collection_mint_pda(&landlord.pubkey());
+    assert_eq!(metadata(&svm, &collection_mint).name, "Harbour Lofts");
+    assert_eq!(
+        nft_balance(
+            &svm,
+            &init_landlord_accounts(&landlord.pubkey()).collection_token_account
+        ),
+        1
+    );
+    let account = svm.get_account(&collection_mint).unwrap();
+    let collection = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    assert!(collection.base.mint_authority.is_none());
+    let group = collection.get_extension::<TokenGroup>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(group.update_authority),
+        Some(landlord.pubkey())
+    );
+    assert_eq!(u64::from(group.size), 1);
+
+    let edition = metadata(&svm, &listing.edition_mint);
+    assert_eq!(edition.name, "Unit 7");
+    assert_eq!(edition.uri, "https://example.com/7.json");
+    assert_eq!(field(&edition, RENT_FIELD), Some(RENT.to_string().as_str()));
+    assert_eq!(field(&edition, TERM_FIELD), Some("3"));
+    assert_eq!(nft_balance(&svm, &listing.vault), 1);
+    let account = svm.get_account(&listing.edition_mint).unwrap();
+    let edition_mint = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    assert_eq!(edition_mint.base.supply, 1);
+    assert!(edition_mint.base.mint_authority.is_none());
+    let member = edition_mint.get_extension::<TokenGroupMember>().unwrap();
+    assert_eq!(member.group, collection_mint);
+    assert_eq!(u64::from(member.member_number), 1);
+    let close_authority = edition_mint.get_extension::<MintCloseAuthority>().unwrap();
+    assert_eq!(
+        Option::<Pubkey>::from(close_authority.close_authority),
+        Some(landlord.pubkey())
+    );
+
+    // new terms are written into the mint, growing it for the longer uri
+    let ix = instruction(
+        capstone::instruction::UpdateEscrow {
+            monthly_rent: 2 * RENT,
+            deposit_amount: 2 * DEPOSIT,
+            late_fee_percent: 10,
+            min_renter_score: 15,
+            max_late_payment_percent: None,
+            cancel_allowed_after: 2,
+            cancel_penalty_percent: 5,
+            months: 12,
+            schedule: monthly_schedule(),
+            yield_renter_share_percent: None,
+            nft_uri: Some("https://example.com/7-renovated.json".to_string()),
+        },
+        listing.update_accounts(&landlord.pubkey()),
+    );
+    let meta = send(&mut svm, ix, &landlord, &[]).unwrap();
+    assert!(events::<ListingUpdated>(&meta)[0].uri_updated);
+    let edition = metadata(&svm, &listing.edition_mint);
+    assert_eq!(edition.uri, "https://example.com/7-renovated.json");
+    assert_eq!(
+        field(&edition, RENT_FIELD),
+        Some((2 * RENT).to_string().as_str())
+    );
+    assert_eq!(field(&edition, TERM_FIELD), Some("12"));
+
+    // closing the listing burns the NFT and closes its mint and vault
+    let ix = instruction(
+        capstone::instruction::CloseEscrow {},
+        listing.refund_accounts(&landlord.pubkey()),
+    );
+    send(&mut svm, ix, &landlord, &[]).unwrap();
+    for closed in [listing.escrow, listing.vault, listing.edition_mint] {
+        assert!(svm.get_account(&closed).is_none());
+    }
+}
+
+#[test]
+fn tenancy_token_takes_its_name_from_the_edition_mint() {
+    let mut svm = setup();
+    let landlord = funded_keypair(&mut svm);
+    let renter = funded_keypair(&mut svm);
+    let payment_mint = Pubkey::new_unique();
+    set_mint(&mut svm, payment_mint, landlord.pubkey(), 6, u64::MAX / 2);
+    set_token_account(&mut svm, renter.pubkey(), payment_mint, WALLET_BALANCE);
+    set_scoring_config(&mut svm, Pubkey::new_unique(), scoring_model());
+    warp_to(&mut svm, START_DATE);
+    let listing = list(&mut svm, &landlord, &payment_mint);
+    let ix = instruction(
+        capstone::instruction::InitRenter {},
+        init_renter_accounts(&renter.pubkey()),
+    );
+    send(&mut svm, ix, &renter, &[]).unwrap();
+
+    let (agreement, _) = agreement_pda(&listing.edition_mint);
+    let nft_vault = get_associated_token_address_with_program_id(
+        &agreement,
+        &listing.edition_mint,
+        &spl_token_2022::ID,
+    );
+    let ix = instruction(
+        capstone::instruction::TakeEscrow {},
+        capstone::accounts::Take {
+            nft_vault,
+            token_program: NFT_TOKEN_PROGRAM,
+            ..listing.take_accounts(&renter.pubkey(), &landlord.pubkey(), &payment_mint)
+        },
+    );
+    send(&mut svm, ix, &renter, &[]).unwrap();
+    let lease = ActiveAgreement {
+        nft_vault,
+        ..ActiveAgreement::taken(landlord, renter, payment_mint, listing.edition_mint)
+    };
+    assert_eq!(nft_balance(&svm, &lease.nft_vault), 1);
+
+    let ix = instruction(
+        capstone::instruction::IssueTenancyToken {},
+        lease.issue_tenancy_token_accounts(),
+    );
+    send(&mut svm, ix, &lease.renter, &[]).unwrap();
+    let tenancy = metadata(&svm, &lease.tenancy_mint());
+    assert_eq!(tenancy.name, "Unit 7");
+    assert_eq!(tenancy.uri, "https://example.com/7.json");
+    assert_eq!(nft_balance(&svm, &lease.renter_tenancy_ata()), 1);
+}
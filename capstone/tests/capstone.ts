@@ -4,13 +4,18 @@ import { Capstone } from "../target/types/capstone";
 import {
   getAssociatedTokenAddress,
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
   ASSOCIATED_TOKEN_PROGRAM_ID,
   getAccount,
   createAssociatedTokenAccount,
+  getOrCreateAssociatedTokenAccount,
+  createSyncNativeInstruction,
+  NATIVE_MINT,
 } from "@solana/spl-token";
 import { PublicKey } from "@solana/web3.js";
 import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
 import fs from "fs";
+import { createHash } from "crypto";
 const { LAMPORTS_PER_SOL } = anchor.web3;
 
 describe("capstone", () => {
@@ -24,13 +29,52 @@ describe("capstone", () => {
   const URI =
     "https://raw.githubusercontent.com/Devansh-Aage/SPL-token/refs/heads/main/master.json";
 
+  // Rent and deposit are paid in wrapped SOL; any SPL / Token-2022 mint works the same way.
+  const PAYMENT_MINT = NATIVE_MINT;
+  const PROPERTY_ID = new anchor.BN(1);
   const MONTHLY_RENT = new anchor.BN(0.05 * LAMPORTS_PER_SOL);
   const DEPOSIT_AMOUNT = new anchor.BN(1 * LAMPORTS_PER_SOL);
   const LATE_FEE_PERCENT = 3;
   const MIN_RENTER_SCORE = 15;
+  const MAX_LATE_PAYMENT_PERCENT = 50;
   const CANCEL_ALLOWED_AFTER = 1;
   const CANCEL_PENALTY_PERCENT = 7;
   const MONTHS = 3;
+  // renter's share of staking rewards; this run never stakes, the cooldown spans epochs
+  const YIELD_RENTER_SHARE_PERCENT = 50;
+  const CLAIM_WINDOW = new anchor.BN(7 * 24 * 60 * 60);
+  const RESPONSE_WINDOW = new anchor.BN(5 * 24 * 60 * 60);
+  const ARBITRATION_WINDOW = new anchor.BN(10 * 24 * 60 * 60);
+  const REPAIR_SLA = new anchor.BN(3 * 24 * 60 * 60);
+  const RENT_WITHHOLD_PERCENT = 30;
+  const SCORING_MODEL = {
+    startScore: 20,
+    onTimeReward: 2,
+    latePenalty: 2,
+    latePenaltyPerDay: 1,
+    earlyTerminationPenalty: 5,
+    evictionPenalty: 5,
+    completionBonus: 10,
+    minScore: 0,
+    maxScore: 1000,
+  };
+  const DEDUCTION = new anchor.BN(0.1 * LAMPORTS_PER_SOL);
+  const RENEWAL_EXTRA_MONTHS = 6;
+  const RENEWAL_RENT = new anchor.BN(0.055 * LAMPORTS_PER_SOL);
+  const EVIDENCE_URI = "https://example.com/rajhans-residence-move-out.json";
+  const SCHEDULE = {
+    periodSeconds: 30 * 24 * 60 * 60,
+    graceSeconds: 3 * 24 * 60 * 60,
+    calendarMonth: false,
+  };
+  const PROPERTY_DETAILS = {
+    // sha256 of the street address, the address itself stays off-chain
+    addressHash: Array.from(
+      createHash("sha256").update("12 Lake Road, Unit 1").digest()
+    ),
+    unitType: { apartment: {} },
+    capacity: 2,
+  };
   const ITEM_NFT_NAME = "RajHans Residence #1";
   const ITEM_NFT_SYMBOL = "RAJ";
   const ITEM_NFT_URI =
@@ -50,11 +94,24 @@ describe("capstone", () => {
     escrowPDA: PublicKey;
     agreementPDA: PublicKey;
     renterPDA: PublicKey;
+    configPDA: PublicKey;
+    claimPDA: PublicKey;
+    renewalPDA: PublicKey;
+    eventAuthorityPDA: PublicKey;
+    scoringConfigPDA: PublicKey;
+    tenancyMintPDA: PublicKey;
+    landlordPDA: PublicKey;
+    propertyPDA: PublicKey;
+    reviewPDA: PublicKey;
+    programDataPDA: PublicKey;
   };
 
   const TOKEN_METADATA_PROGRAM = new PublicKey(
     "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s"
   );
+  const BPF_LOADER_UPGRADEABLE = new PublicKey(
+    "BPFLoaderUpgradeab1e11111111111111111111111"
+  );
 
   before(async () => {
     const collectionMintSeeds = [
@@ -67,6 +124,7 @@ describe("capstone", () => {
     const editionMintSeeds = [
       Buffer.from("edition"),
       collectionMint.toBuffer(),
+      PROPERTY_ID.toArrayLike(Buffer, "le", 8),
     ];
     const [editionMint, editionMintBump] = PublicKey.findProgramAddressSync(
       editionMintSeeds,
@@ -79,11 +137,7 @@ describe("capstone", () => {
       program.programId
     );
 
-    const agreementSeeds = [
-      Buffer.from("agreement"),
-      renter.publicKey.toBuffer(),
-      landlord.publicKey.toBuffer(),
-    ];
+    const agreementSeeds = [Buffer.from("agreement"), editionMint.toBuffer()];
     const [agreement, agreementBump] = PublicKey.findProgramAddressSync(
       agreementSeeds,
       program.programId
@@ -95,12 +149,73 @@ describe("capstone", () => {
       program.programId
     );
 
+    const [config, configBump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+
+    const [claim, claimBump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("claim"), agreement.toBuffer()],
+      program.programId
+    );
+
+    const [renewal, renewalBump] = PublicKey.findProgramAddressSync(
+      [Buffer.from("renewal"), agreement.toBuffer()],
+      program.programId
+    );
+
+    const [eventAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from("__event_authority")],
+      program.programId
+    );
+
+    const [scoringConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from("scoring_config")],
+      program.programId
+    );
+
+    const [tenancyMint] = PublicKey.findProgramAddressSync(
+      [Buffer.from("tenancy"), agreement.toBuffer()],
+      program.programId
+    );
+
+    const [landlordPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("landlord"), landlord.publicKey.toBuffer()],
+      program.programId
+    );
+
+    const [property] = PublicKey.findProgramAddressSync(
+      [Buffer.from("property"), editionMint.toBuffer()],
+      program.programId
+    );
+
+    const [review] = PublicKey.findProgramAddressSync(
+      [Buffer.from("review"), agreement.toBuffer()],
+      program.programId
+    );
+
+    // the deploying wallet is the upgrade authority that may create the configs
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      BPF_LOADER_UPGRADEABLE
+    );
+
     shared = {
       collectionMintPDA: collectionMint,
       editionMintPDA: editionMint,
       escrowPDA: escrow,
       agreementPDA: agreement,
       renterPDA: renterPDA,
+      configPDA: config,
+      claimPDA: claim,
+      renewalPDA: renewal,
+      eventAuthorityPDA: eventAuthority,
+      scoringConfigPDA: scoringConfig,
+      tenancyMintPDA: tenancyMint,
+      landlordPDA: landlordPDA,
+      propertyPDA: property,
+      reviewPDA: review,
+      programDataPDA: programData,
     };
   });
 
@@ -135,6 +250,7 @@ describe("capstone", () => {
       .initLandlord(NAME, SYMBOL, URI)
       .accountsPartial({
         landlord: landlord.publicKey,
+        landlordAccount: shared.landlordPDA,
         collectionMint: shared.collectionMintPDA,
         collectionTokenAccount: landlordCollectionATA,
         metadata: metadata,
@@ -151,11 +267,49 @@ describe("capstone", () => {
     );
   });
 
+  it("init config with deposit claim windows", async () => {
+    const tx = await program.methods
+      .initConfig(
+        provider.wallet.publicKey,
+        CLAIM_WINDOW,
+        RESPONSE_WINDOW,
+        ARBITRATION_WINDOW,
+        REPAIR_SLA,
+        RENT_WITHHOLD_PERCENT
+      )
+      .accountsStrict({
+        admin: provider.wallet.publicKey,
+        config: shared.configPDA,
+        programData: shared.programDataPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(
+      `Init config transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+  });
+
+  it("init scoring config", async () => {
+    const tx = await program.methods
+      .initScoringConfig(SCORING_MODEL)
+      .accountsStrict({
+        admin: provider.wallet.publicKey,
+        scoringConfig: shared.scoringConfigPDA,
+        programData: shared.programDataPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .rpc();
+    console.log(
+      `Init scoring config transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+  });
+
   it("init renter PDA", async () => {
     const tx = await program.methods
       .initRenter()
       .accountsStrict({
         renter: shared.renterPDA,
+        scoringConfig: shared.scoringConfigPDA,
         signer: renter.publicKey,
         systemProgram: SYSTEM_PROGRAM_ID,
       })
@@ -174,13 +328,18 @@ describe("capstone", () => {
     );
     const initEscrowIx = await program.methods
       .createEscrow(
+        PROPERTY_ID,
         MONTHLY_RENT,
         DEPOSIT_AMOUNT,
         LATE_FEE_PERCENT,
         MIN_RENTER_SCORE,
+        MAX_LATE_PAYMENT_PERCENT,
         CANCEL_ALLOWED_AFTER,
         CANCEL_PENALTY_PERCENT,
         MONTHS,
+        SCHEDULE,
+        YIELD_RENTER_SHARE_PERCENT,
+        PROPERTY_DETAILS,
         ITEM_NFT_NAME,
         ITEM_NFT_SYMBOL,
         ITEM_NFT_URI
@@ -191,7 +350,9 @@ describe("capstone", () => {
         escrow: shared.escrowPDA,
         editionMint: shared.editionMintPDA,
         vault: vaultATA,
+        paymentMint: PAYMENT_MINT,
         tokenProgram: TOKEN_PROGRAM_ID,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
       })
       .instruction();
 
@@ -223,6 +384,46 @@ describe("capstone", () => {
     );
   });
 
+  it("landlord updates the listing terms and nft uri", async () => {
+    const [metadata] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM.toBuffer(),
+        shared.editionMintPDA.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM
+    );
+    const tx = await program.methods
+      .updateEscrow(
+        MONTHLY_RENT,
+        DEPOSIT_AMOUNT,
+        LATE_FEE_PERCENT,
+        MIN_RENTER_SCORE,
+        MAX_LATE_PAYMENT_PERCENT,
+        CANCEL_ALLOWED_AFTER,
+        CANCEL_PENALTY_PERCENT,
+        MONTHS,
+        SCHEDULE,
+        YIELD_RENTER_SHARE_PERCENT,
+        ITEM_NFT_URI
+      )
+      .accountsStrict({
+        landlord: landlord.publicKey,
+        escrow: shared.escrowPDA,
+        editionMint: shared.editionMintPDA,
+        scoringConfig: shared.scoringConfigPDA,
+        metadata: metadata,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .signers([landlord])
+      .rpc();
+    console.log(
+      `Update escrow transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+  });
+
   // it("close escrow and burn item nft", async () => {
   //   const vaultATA = await getAssociatedTokenAddress(
   //     shared.editionMintPDA,
@@ -286,6 +487,32 @@ describe("capstone", () => {
   //   );
   // });
 
+  it("wrap SOL for renter rent and deposit", async () => {
+    const renterPaymentAta = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      renter,
+      PAYMENT_MINT,
+      renter.publicKey
+    );
+    const wrapTx = new anchor.web3.Transaction()
+      .add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: renter.publicKey,
+          toPubkey: renterPaymentAta.address,
+          lamports: DEPOSIT_AMOUNT.add(MONTHLY_RENT).toNumber(),
+        })
+      )
+      .add(createSyncNativeInstruction(renterPaymentAta.address));
+    const signature = await anchor.web3.sendAndConfirmTransaction(
+      provider.connection,
+      wrapTx,
+      [renter]
+    );
+    console.log(
+      `Wrap SOL transaction at https://explorer.solana.com/tx/${signature}?cluster=devnet`
+    );
+  });
+
   it("accept escrow and init agreement", async () => {
     const vaultATA = await getAssociatedTokenAddress(
       shared.editionMintPDA,
@@ -298,13 +525,14 @@ describe("capstone", () => {
       true
     );
 
-    const depositSeeds = [
-      Buffer.from("deposit"),
-      shared.agreementPDA.toBuffer(),
-    ];
-    const [depositPDA, depositBumps] = PublicKey.findProgramAddressSync(
-      depositSeeds,
-      program.programId
+    const depositVault = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      shared.agreementPDA,
+      true
+    );
+    const renterPaymentAta = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      renter.publicKey
     );
 
     const tx = await program.methods
@@ -312,13 +540,17 @@ describe("capstone", () => {
       .accountsPartial({
         renter: renter.publicKey,
         landlord: landlord.publicKey,
+        renterAccount: shared.renterPDA,
         editionMint: shared.editionMintPDA,
         tokenProgram: TOKEN_PROGRAM_ID,
         agreement: shared.agreementPDA,
-        depositVault: depositPDA,
+        depositVault: depositVault,
         escrow: shared.escrowPDA,
         nftVault: nftATA,
         vault: vaultATA,
+        paymentMint: PAYMENT_MINT,
+        renterPaymentAta: renterPaymentAta,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([renter])
       .rpc();
@@ -327,24 +559,84 @@ describe("capstone", () => {
     );
   });
 
+  it("renter receives the soulbound tenancy token", async () => {
+    const [editionMetadata] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("metadata"),
+        TOKEN_METADATA_PROGRAM.toBuffer(),
+        shared.editionMintPDA.toBuffer(),
+      ],
+      TOKEN_METADATA_PROGRAM
+    );
+    const renterTenancyAta = await getAssociatedTokenAddress(
+      shared.tenancyMintPDA,
+      renter.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const tx = await program.methods
+      .issueTenancyToken()
+      .accountsStrict({
+        renter: renter.publicKey,
+        agreement: shared.agreementPDA,
+        editionMint: shared.editionMintPDA,
+        editionMetadata: editionMetadata,
+        tenancyMint: shared.tenancyMintPDA,
+        renterTenancyAta: renterTenancyAta,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .signers([renter])
+      .rpc();
+    console.log(
+      `Issue tenancy token transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+    const tenancyToken = await getAccount(
+      provider.connection,
+      renterTenancyAta,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    console.log("Tenancy tokens held by renter:", tenancyToken.amount.toString());
+  });
+
   it("renter pays monthly rent", async () => {
-    const depositSeeds = [
-      Buffer.from("deposit"),
-      shared.agreementPDA.toBuffer(),
-    ];
-    const [depositPDA, depositBumps] = PublicKey.findProgramAddressSync(
-      depositSeeds,
-      program.programId
+    const depositVault = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      shared.agreementPDA,
+      true
+    );
+    const renterPaymentAta = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      renter.publicKey
+    );
+    const landlordPaymentAta = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      landlord.publicKey
     );
     const tx = await program.methods
       .payRent()
       .accountsStrict({
         signer: renter.publicKey,
         landlord: landlord.publicKey,
-        depositVault: depositPDA,
+        depositVault: depositVault,
         agreement: shared.agreementPDA,
         renter: shared.renterPDA,
+        scoringConfig: shared.scoringConfigPDA,
+        ticket: null,
+        ticketVault: null,
+        config: null,
+        paymentMint: PAYMENT_MINT,
+        signerPaymentAta: renterPaymentAta,
+        landlordPaymentAta: landlordPaymentAta,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SYSTEM_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
       })
       .signers([renter])
       .rpc();
@@ -365,15 +657,180 @@ describe("capstone", () => {
     }
   });
 
-  it("close agreement", async () => {
-    const depositSeeds = [
-      Buffer.from("deposit"),
-      shared.agreementPDA.toBuffer(),
-    ];
-    const [depositPDA, depositBumps] = PublicKey.findProgramAddressSync(
-      depositSeeds,
+  it("renter opens a maintenance ticket and confirms the funded repair", async () => {
+    const contractor = anchor.web3.Keypair.generate();
+    const [ticket] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ticket"), shared.agreementPDA.toBuffer()],
+      program.programId
+    );
+    const ticketVault = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      ticket,
+      true
+    );
+    const landlordPaymentAta = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      landlord.publicKey
+    );
+    const contractorPaymentAta = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      contractor.publicKey
+    );
+    const descriptionHash = Array.from(
+      createHash("sha256").update("Leaking kitchen tap").digest()
+    );
+
+    let tx = await program.methods
+      .openTicket(descriptionHash, { routine: {} })
+      .accountsStrict({
+        renter: renter.publicKey,
+        agreement: shared.agreementPDA,
+        ticket: ticket,
+        paymentMint: PAYMENT_MINT,
+        ticketVault: ticketVault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .signers([renter])
+      .rpc();
+    console.log(
+      `Open ticket transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+
+    tx = await program.methods
+      .fundRepair(contractor.publicKey, MONTHLY_RENT.divn(10))
+      .accountsStrict({
+        landlord: landlord.publicKey,
+        agreement: shared.agreementPDA,
+        ticket: ticket,
+        paymentMint: PAYMENT_MINT,
+        landlordPaymentAta: landlordPaymentAta,
+        ticketVault: ticketVault,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .signers([landlord])
+      .rpc();
+    console.log(
+      `Fund repair transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+
+    tx = await program.methods
+      .confirmRepair()
+      .accountsStrict({
+        renter: renter.publicKey,
+        landlord: landlord.publicKey,
+        contractor: contractor.publicKey,
+        ticket: ticket,
+        paymentMint: PAYMENT_MINT,
+        ticketVault: ticketVault,
+        contractorPaymentAta: contractorPaymentAta,
+        landlordPaymentAta: landlordPaymentAta,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .signers([renter])
+      .rpc();
+    console.log(
+      `Confirm repair transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+  });
+
+  it("anyone refreshes the renter's reputation attestation", async () => {
+    const [attestation] = PublicKey.findProgramAddressSync(
+      [Buffer.from("attestation"), renter.publicKey.toBuffer()],
       program.programId
     );
+    const tx = await program.methods
+      .attestReputation()
+      .accountsStrict({
+        payer: provider.wallet.publicKey,
+        renter: renter.publicKey,
+        renterAccount: shared.renterPDA,
+        attestation: attestation,
+        systemProgram: SYSTEM_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .rpc();
+    console.log(
+      `Attest reputation transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+    const attestationAccount = await program.account.attestation.fetch(
+      attestation
+    );
+    console.log("Attestation PDA:\n", attestationAccount);
+  });
+
+  it("landlord proposes a lease renewal", async () => {
+    const tx = await program.methods
+      .proposeRenewal(RENEWAL_EXTRA_MONTHS, RENEWAL_RENT, DEPOSIT_AMOUNT)
+      .accountsStrict({
+        landlord: landlord.publicKey,
+        agreement: shared.agreementPDA,
+        renewal: shared.renewalPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .signers([landlord])
+      .rpc();
+    console.log(
+      `Propose renewal transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+  });
+
+  it("renter accepts the lease renewal", async () => {
+    const depositVault = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      shared.agreementPDA,
+      true
+    );
+    const renterPaymentAta = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      renter.publicKey
+    );
+    const tx = await program.methods
+      .acceptRenewal(RENEWAL_EXTRA_MONTHS, RENEWAL_RENT, DEPOSIT_AMOUNT)
+      .accountsStrict({
+        renter: renter.publicKey,
+        landlord: landlord.publicKey,
+        agreement: shared.agreementPDA,
+        renewal: shared.renewalPDA,
+        depositVault: depositVault,
+        paymentMint: PAYMENT_MINT,
+        renterPaymentAta: renterPaymentAta,
+        tenancyMint: shared.tenancyMintPDA,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .signers([renter])
+      .rpc();
+    console.log(
+      `Accept renewal transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+  });
+
+  it("renter moves out and returns the nft", async () => {
+    const depositVault = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      shared.agreementPDA,
+      true
+    );
+    const landlordPaymentAta = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      landlord.publicKey
+    );
 
     const landlordAta = await getAssociatedTokenAddress(
       shared.editionMintPDA,
@@ -384,20 +841,48 @@ describe("capstone", () => {
       shared.agreementPDA,
       true
     );
+    const renterTenancyAta = await getAssociatedTokenAddress(
+      shared.tenancyMintPDA,
+      renter.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    // the repair was confirmed earlier, so there is no ticket left to settle
+    const [ticket] = PublicKey.findProgramAddressSync(
+      [Buffer.from("ticket"), shared.agreementPDA.toBuffer()],
+      program.programId
+    );
     const tx = await program.methods
       .closeAgreementTransferNft()
       .accountsStrict({
         signer: renter.publicKey,
-        depositVault: depositPDA,
+        depositVault: depositVault,
         agreement: shared.agreementPDA,
         renter: shared.renterPDA,
+        scoringConfig: shared.scoringConfigPDA,
         landlord: landlord.publicKey,
+        landlordAccount: shared.landlordPDA,
+        property: shared.propertyPDA,
+        review: shared.reviewPDA,
         landlordAta: landlordAta,
         editionMint: shared.editionMintPDA,
         nftVault: nftATA,
+        paymentMint: PAYMENT_MINT,
+        landlordPaymentAta: landlordPaymentAta,
+        ticket: ticket,
+        ticketVault: null,
+        stakeAccount: null,
+        clock: null,
+        stakeProgram: null,
+        tenancyMint: shared.tenancyMintPDA,
+        renterTenancyAta: renterTenancyAta,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SYSTEM_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
       })
       .signers([renter])
       .rpc();
@@ -405,4 +890,117 @@ describe("capstone", () => {
       `Close agreement transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
     );
   });
+
+  it("renter and landlord rate each other", async () => {
+    for (const [rater, rating, text] of [
+      [renter, 5, "Responsive landlord, flat as described"],
+      [landlord, 4, "Paid on time, left the flat tidy"],
+    ] as const) {
+      const tx = await program.methods
+        .rateCounterparty(
+          rating,
+          Array.from(createHash("sha256").update(text).digest())
+        )
+        .accountsStrict({
+          signer: rater.publicKey,
+          review: shared.reviewPDA,
+          landlordAccount: shared.landlordPDA,
+          renterAccount: shared.renterPDA,
+          eventAuthority: shared.eventAuthorityPDA,
+          program: program.programId,
+        })
+        .signers([rater])
+        .rpc();
+      console.log(
+        `Rate counterparty transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+      );
+    }
+    const review = await program.account.review.fetch(shared.reviewPDA);
+    console.log("Review PDA:\n", review);
+  });
+
+  it("landlord proposes a deposit deduction", async () => {
+    const depositVault = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      shared.agreementPDA,
+      true
+    );
+    const tx = await program.methods
+      .proposeDeduction(DEDUCTION, EVIDENCE_URI)
+      .accountsStrict({
+        landlord: landlord.publicKey,
+        agreement: shared.agreementPDA,
+        claim: shared.claimPDA,
+        depositVault: depositVault,
+        paymentMint: PAYMENT_MINT,
+        config: shared.configPDA,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .signers([landlord])
+      .rpc();
+    console.log(
+      `Propose deduction transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+  });
+
+  it("renter accepts the deduction", async () => {
+    const tx = await program.methods
+      .respondToClaim(true)
+      .accountsStrict({
+        renter: renter.publicKey,
+        agreement: shared.agreementPDA,
+        claim: shared.claimPDA,
+        config: shared.configPDA,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .signers([renter])
+      .rpc();
+    console.log(
+      `Accept deduction transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+  });
+
+  it("settle the deposit", async () => {
+    const depositVault = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      shared.agreementPDA,
+      true
+    );
+    const renterPaymentAta = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      renter.publicKey
+    );
+    const landlordPaymentAta = await getAssociatedTokenAddress(
+      PAYMENT_MINT,
+      landlord.publicKey
+    );
+    const tx = await program.methods
+      .settleDeposit()
+      .accountsStrict({
+        signer: renter.publicKey,
+        landlord: landlord.publicKey,
+        renter: renter.publicKey,
+        agreement: shared.agreementPDA,
+        claim: shared.claimPDA,
+        depositVault: depositVault,
+        paymentMint: PAYMENT_MINT,
+        renterPaymentAta: renterPaymentAta,
+        landlordPaymentAta: landlordPaymentAta,
+        config: shared.configPDA,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        paymentTokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM_ID,
+        eventAuthority: shared.eventAuthorityPDA,
+        program: program.programId,
+      })
+      .signers([renter])
+      .rpc();
+    console.log(
+      `Settle deposit transaction at https://explorer.solana.com/tx/${tx}?cluster=devnet`
+    );
+  });
 });